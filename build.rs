@@ -21,8 +21,10 @@ fn main() {
         "GL_ARB_blend_func_extended",
         "GL_ARB_buffer_storage",
         "GL_ARB_copy_image",
+        "GL_ARB_ES2_compatibility",
         "GL_ARB_get_program_binary",
         "GL_ARB_invalidate_subdata",
+        "GL_ARB_program_interface_query",
         "GL_ARB_texture_rectangle",
         "GL_ARB_texture_storage",
         "GL_ARB_vertex_attrib_binding",
@@ -47,13 +49,16 @@ fn main() {
 
     // GLES 3.0 bindings
     let gles_extensions = [
+        "GL_EXT_blend_func_extended",
         "GL_EXT_buffer_storage",
         "GL_EXT_copy_image",
+        "GL_EXT_debug_label",
         "GL_EXT_debug_marker",
         "GL_EXT_disjoint_timer_query",
         "GL_EXT_shader_texture_lod",
         "GL_EXT_texture_filter_anisotropic",
         "GL_EXT_texture_format_BGRA8888",
+        "GL_EXT_texture_buffer",
         "GL_EXT_texture_storage",
         "GL_OES_EGL_image_external",
         "GL_OES_EGL_image",
@@ -65,6 +70,7 @@ fn main() {
         "GL_KHR_debug",
         "GL_KHR_blend_equation_advanced",
         "GL_KHR_blend_equation_advanced_coherent",
+        "GL_KHR_robustness",
         "GL_ANGLE_copy_texture_3d",
         "GL_QCOM_tiled_rendering",
     ];
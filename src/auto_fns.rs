@@ -0,0 +1,93 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Loads whichever of `GlFns` and `GlesFns` matches the current context.
+/// Whether the context is GLES is detected from its `VERSION` string, and
+/// the backend then picks the entry points of the context's version and
+/// extensions.
+pub struct AutoFns;
+
+type GetStringFn = extern "system" fn(GLenum) -> *const GLubyte;
+
+impl AutoFns {
+    /// Loads the context that is current on the calling thread, which may be
+    /// either a desktop GL or a GLES context.
+    ///
+    /// # Safety
+    ///
+    /// See `GlFns::load_with_report`.
+    pub unsafe fn load_with<F>(loadfn: F) -> Rc<dyn Gl>
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        AutoFns::load_with_report(loadfn).0
+    }
+
+    /// Loads the context like `load_with`, also reporting every entry point
+    /// the context's API needs that the loader couldn't find.
+    ///
+    /// # Safety
+    ///
+    /// See `GlFns::load_with_report`.
+    pub unsafe fn load_with_report<F>(mut loadfn: F) -> (Rc<dyn Gl>, LoadReport)
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        match AutoFns::detect(&mut loadfn) {
+            GlType::Gl => GlFns::load_with_report(loadfn),
+            GlType::Gles => GlesFns::load_with_report(loadfn),
+        }
+    }
+
+    /// Loads the context like `load_with`, from `loader`.
+    ///
+    /// # Safety
+    ///
+    /// See `GlFns::load_with_report`.
+    pub unsafe fn load_from<L: GlLoader>(mut loader: L) -> Rc<dyn Gl> {
+        AutoFns::load_with(|symbol| loader.get_proc_address(symbol))
+    }
+
+    /// Loads the context like `load_with`, for `SendGl` to move between
+    /// threads.
+    unsafe fn load_send<F>(mut loadfn: F) -> Box<dyn Gl + Send>
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        match AutoFns::detect(&mut loadfn) {
+            GlType::Gl => Box::new(GlFns::load(loadfn).0),
+            GlType::Gles => Box::new(GlesFns::load(loadfn).0),
+        }
+    }
+
+    /// Whether the current context is a desktop GL or a GLES context, or the
+    /// platform's default if it can't be queried.
+    unsafe fn detect<F>(loadfn: &mut F) -> GlType
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        let get_string = loadfn("glGetString");
+        if get_string.is_null() {
+            return GlType::default();
+        }
+        let get_string: GetStringFn = mem::transmute(get_string);
+        let version = get_string(ffi::VERSION);
+        if version.is_null() {
+            return GlType::default();
+        }
+        if CStr::from_ptr(version as *const c_char)
+            .to_bytes()
+            .starts_with(b"OpenGL ES")
+        {
+            GlType::Gles
+        } else {
+            GlType::Gl
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The `Gl` methods a backend implements, each with the driver entry points
+/// it calls and whether they were loaded.
+type EntryPoints = Vec<(&'static str, Vec<(&'static str, bool)>)>;
+
+/// Lists `method: [Symbol, ...];` entries of a loaded ffi struct as
+/// `EntryPoints`. A `Symbol | Fallback` entry point counts as loaded if
+/// either symbol is, and is reported missing under the first one.
+macro_rules! entry_points {
+    ($ffi:expr; $($method:ident: [$($symbol:ident $(| $fallback:ident)*),*];)+) => {
+        vec![$((
+            stringify!($method),
+            vec![$((
+                stringify!($symbol),
+                $ffi.$symbol.is_loaded() $(|| $ffi.$fallback.is_loaded())*,
+            )),*],
+        )),+]
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GlProfile {
+    Core,
+    Compatibility,
+    Es,
+}
+
+/// Implementation limits queried when the context was loaded. A limit the
+/// context doesn't know about is left at zero.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GlLimits {
+    pub max_texture_size: GLint,
+    pub max_3d_texture_size: GLint,
+    pub max_cube_map_texture_size: GLint,
+    pub max_array_texture_layers: GLint,
+    pub max_renderbuffer_size: GLint,
+    pub max_viewport_dims: (GLint, GLint),
+    pub max_texture_image_units: GLint,
+    pub max_combined_texture_image_units: GLint,
+    pub max_vertex_attribs: GLint,
+    pub max_draw_buffers: GLint,
+    pub max_color_attachments: GLint,
+    pub max_samples: GLint,
+    pub max_uniform_block_size: GLint,
+    pub max_uniform_buffer_bindings: GLint,
+}
+
+/// What a context supports, queried once when it is loaded.
+#[derive(Clone, Debug, Default)]
+pub struct GlCapabilities {
+    pub gl_type: GlType,
+    /// The major and minor version parsed from `VERSION`.
+    pub version: (u32, u32),
+    /// The major and minor version parsed from `SHADING_LANGUAGE_VERSION`.
+    pub shading_language_version: (u32, u32),
+    /// `None` for desktop contexts older than 3.2, which have no profiles.
+    pub profile: Option<GlProfile>,
+    pub version_string: String,
+    pub renderer: String,
+    pub vendor: String,
+    pub extensions: HashSet<String>,
+    pub limits: GlLimits,
+    loaded: HashSet<&'static str>,
+}
+
+impl GlCapabilities {
+    /// Queries `gl`, calling only the methods named in `loaded`. Errors
+    /// raised by queries the context doesn't know are cleared.
+    fn query(gl: &dyn Gl, loaded: HashSet<&'static str>) -> GlCapabilities {
+        let gl_type = gl.get_type();
+        let string = |name| {
+            if loaded.contains("get_string") {
+                gl.get_string(name)
+            } else {
+                String::new()
+            }
+        };
+        let integers = |name, count| {
+            let mut values = vec![0; count];
+            if loaded.contains("get_integer_v") {
+                unsafe { gl.get_integer_v(name, &mut values) };
+            }
+            values
+        };
+        let integer = |name| integers(name, 1)[0];
+
+        let version_string = string(ffi::VERSION);
+        let version = parse_version(&version_string);
+        let profile = match gl_type {
+            GlType::Gles => Some(GlProfile::Es),
+            GlType::Gl if version < (3, 2) => None,
+            GlType::Gl => {
+                let mask = integer(ffi::CONTEXT_PROFILE_MASK) as GLenum;
+                Some(if mask & ffi::CONTEXT_CORE_PROFILE_BIT != 0 {
+                    GlProfile::Core
+                } else {
+                    GlProfile::Compatibility
+                })
+            }
+        };
+
+        let count = if version >= (3, 0) && loaded.contains("get_string_i") {
+            integer(ffi::NUM_EXTENSIONS).max(0) as GLuint
+        } else {
+            0
+        };
+        let extensions = if count > 0 {
+            (0..count)
+                .map(|index| gl.get_string_i(ffi::EXTENSIONS, index))
+                .collect()
+        } else {
+            string(ffi::EXTENSIONS)
+                .split_whitespace()
+                .map(str::to_string)
+                .collect()
+        };
+
+        let viewport = integers(ffi::MAX_VIEWPORT_DIMS, 2);
+        let limits = GlLimits {
+            max_texture_size: integer(ffi::MAX_TEXTURE_SIZE),
+            max_3d_texture_size: integer(ffi::MAX_3D_TEXTURE_SIZE),
+            max_cube_map_texture_size: integer(ffi::MAX_CUBE_MAP_TEXTURE_SIZE),
+            max_array_texture_layers: integer(ffi::MAX_ARRAY_TEXTURE_LAYERS),
+            max_renderbuffer_size: integer(ffi::MAX_RENDERBUFFER_SIZE),
+            max_viewport_dims: (viewport[0], viewport[1]),
+            max_texture_image_units: integer(ffi::MAX_TEXTURE_IMAGE_UNITS),
+            max_combined_texture_image_units: integer(ffi::MAX_COMBINED_TEXTURE_IMAGE_UNITS),
+            max_vertex_attribs: integer(ffi::MAX_VERTEX_ATTRIBS),
+            max_draw_buffers: integer(ffi::MAX_DRAW_BUFFERS),
+            max_color_attachments: integer(ffi::MAX_COLOR_ATTACHMENTS),
+            max_samples: integer(ffi::MAX_SAMPLES),
+            max_uniform_block_size: integer(ffi::MAX_UNIFORM_BLOCK_SIZE),
+            max_uniform_buffer_bindings: integer(ffi::MAX_UNIFORM_BUFFER_BINDINGS),
+        };
+
+        if loaded.contains("get_error") {
+            // A lost context may keep reporting errors, so don't loop forever.
+            for _ in 0..32 {
+                if gl.get_error() == NO_ERROR {
+                    break;
+                }
+            }
+        }
+
+        GlCapabilities {
+            gl_type,
+            version,
+            shading_language_version: parse_version(&string(ffi::SHADING_LANGUAGE_VERSION)),
+            profile,
+            version_string,
+            renderer: string(ffi::RENDERER),
+            vendor: string(ffi::VENDOR),
+            extensions,
+            limits,
+            loaded,
+        }
+    }
+
+    /// Queries the capabilities of a backend whose loaded methods are those
+    /// of `entry_points` with every entry point loaded.
+    fn query_entry_points(gl: &dyn Gl, entry_points: &EntryPoints) -> GlCapabilities {
+        let loaded = entry_points
+            .iter()
+            .filter(|&(_, symbols)| symbols.iter().all(|&(_, loaded)| loaded))
+            .map(|&(method, _)| method)
+            .collect();
+        GlCapabilities::query(gl, loaded)
+    }
+
+    /// Whether the version is at least `major.minor`.
+    pub fn is_version_at_least(&self, major: u32, minor: u32) -> bool {
+        self.version >= (major, minor)
+    }
+
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.contains(name)
+    }
+
+    /// Whether the `Gl` method called `method` is implemented and every
+    /// entry point it calls was loaded.
+    pub fn is_loaded(&self, method: &str) -> bool {
+        self.loaded.contains(method)
+    }
+}
+
+/// The entry points a backend failed to load, returned by
+/// the `load_with_report` method of `GlFns`, `GlesFns` and `AutoFns`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LoadReport {
+    /// Each symbol the loader returned null for, such as "glTexStorage2D",
+    /// with the `Gl` methods that call it.
+    pub missing: BTreeMap<String, Vec<&'static str>>,
+    /// The `Gl` methods the backend has no implementation of, whatever the
+    /// loader returns.
+    pub unimplemented: Vec<&'static str>,
+}
+
+impl LoadReport {
+    fn new(entry_points: &EntryPoints) -> LoadReport {
+        let mut report = LoadReport::default();
+        for &(method, ref symbols) in entry_points {
+            for &(symbol, loaded) in symbols {
+                if !loaded {
+                    report
+                        .missing
+                        .entry(format!("gl{}", symbol))
+                        .or_insert_with(Vec::new)
+                        .push(method);
+                }
+            }
+        }
+        report.unimplemented = GL_METHOD_NAMES
+            .iter()
+            .filter(|name| !entry_points.iter().any(|&(method, _)| method == **name))
+            .cloned()
+            .collect();
+        report
+    }
+
+    /// Whether every entry point was loaded.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Whether the `Gl` method called `method` can be called, as it is
+    /// implemented and every entry point it calls was loaded.
+    pub fn is_supported(&self, method: &str) -> bool {
+        !self.unimplemented.contains(&method)
+            && !self
+                .missing
+                .values()
+                .any(|methods| methods.contains(&method))
+    }
+}
+
+/// Parses the first `major.minor` number of a `VERSION` or
+/// `SHADING_LANGUAGE_VERSION` string, such as "4.6.0 NVIDIA 535.54" or
+/// "OpenGL ES GLSL ES 3.20".
+fn parse_version(version: &str) -> (u32, u32) {
+    let start = match version.find(|c: char| c.is_ascii_digit()) {
+        Some(start) => start,
+        None => return (0, 0),
+    };
+    let mut numbers = version[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|number| number.parse().unwrap_or(0));
+    let major = numbers.next().unwrap_or(0);
+    let minor = numbers.next().unwrap_or(0);
+    (major, minor)
+}
@@ -0,0 +1,383 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+gl_enum! {
+    /// Where a `DebugMessage` comes from.
+    pub enum DebugSource {
+        Api = DEBUG_SOURCE_API,
+        WindowSystem = DEBUG_SOURCE_WINDOW_SYSTEM,
+        ShaderCompiler = DEBUG_SOURCE_SHADER_COMPILER,
+        ThirdParty = DEBUG_SOURCE_THIRD_PARTY,
+        Application = DEBUG_SOURCE_APPLICATION,
+        Other = DEBUG_SOURCE_OTHER,
+    }
+}
+
+gl_enum! {
+    /// What a `DebugMessage` is about.
+    pub enum DebugType {
+        Error = DEBUG_TYPE_ERROR,
+        DeprecatedBehavior = DEBUG_TYPE_DEPRECATED_BEHAVIOR,
+        UndefinedBehavior = DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+        Portability = DEBUG_TYPE_PORTABILITY,
+        Performance = DEBUG_TYPE_PERFORMANCE,
+        Marker = DEBUG_TYPE_MARKER,
+        PushGroup = DEBUG_TYPE_PUSH_GROUP,
+        PopGroup = DEBUG_TYPE_POP_GROUP,
+        Other = DEBUG_TYPE_OTHER,
+    }
+}
+
+gl_enum! {
+    /// How important a `DebugMessage` is.
+    pub enum DebugSeverity {
+        High = DEBUG_SEVERITY_HIGH,
+        Medium = DEBUG_SEVERITY_MEDIUM,
+        Low = DEBUG_SEVERITY_LOW,
+        Notification = DEBUG_SEVERITY_NOTIFICATION,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugMessage {
+    pub message: String,
+    pub source: DebugSource,
+    pub ty: DebugType,
+    pub id: GLenum,
+    pub severity: DebugSeverity,
+    /// The messages of the groups pushed with `push_debug_group_khr` when
+    /// this message was logged, outermost first. Messages read with
+    /// `get_debug_messages` from a driver have no groups.
+    pub groups: Vec<String>,
+}
+
+impl DebugMessage {
+    /// Builds a message from the values the driver logged it with. Sources
+    /// and types newer than `DebugSource` and `DebugType` become `Other`,
+    /// and severities newer than `DebugSeverity` become `Notification`.
+    fn from_gl(
+        message: String,
+        source: GLenum,
+        ty: GLenum,
+        id: GLuint,
+        severity: GLenum,
+        groups: Vec<String>,
+    ) -> DebugMessage {
+        DebugMessage {
+            message,
+            source: DebugSource::from_gl(source).unwrap_or(DebugSource::Other),
+            ty: DebugType::from_gl(ty).unwrap_or(DebugType::Other),
+            id,
+            severity: DebugSeverity::from_gl(severity).unwrap_or(DebugSeverity::Notification),
+            groups,
+        }
+    }
+}
+
+/// Receives each message logged by the context once it's passed to
+/// `Gl::set_debug_callback`, which makes debug output synchronous so that it
+/// is called on the thread making the call that logged the message, tagged
+/// with the debug groups pushed at that point. Only debug contexts, or ones
+/// with `DEBUG_OUTPUT` enabled, log messages.
+///
+/// It must still be `Sync`, as a driver calls it from its own threads once
+/// `DEBUG_OUTPUT_SYNCHRONOUS` is disabled behind the backend's back.
+pub type DebugCallback = Box<dyn Fn(&DebugMessage) + Send + Sync>;
+
+/// The debug callback of a backend that loads its entry points from a
+/// driver, and the debug groups pushed through it. The driver is given a
+/// pointer to the boxed state, so that it stays put when the backend moves.
+#[derive(Default)]
+struct DebugOutput {
+    state: Box<DebugOutputState>,
+}
+
+#[derive(Default)]
+struct DebugOutputState {
+    /// Shared so that it can be called without holding the lock, which the
+    /// callback may need to log another message or replace itself.
+    callback: Mutex<Option<Arc<DebugCallback>>>,
+    groups: Mutex<Vec<String>>,
+}
+
+impl DebugOutput {
+    /// Replaces the callback, returning the user parameter to register
+    /// `debug_message_callback` with, or `None` if there is no callback
+    /// anymore.
+    fn set_callback(&self, callback: Option<DebugCallback>) -> Option<*const c_void> {
+        let set = callback.is_some();
+        *lock(&self.state.callback) = callback.map(Arc::new);
+        if set {
+            Some(&*self.state as *const DebugOutputState as *const c_void)
+        } else {
+            None
+        }
+    }
+
+    fn has_callback(&self) -> bool {
+        lock(&self.state.callback).is_some()
+    }
+
+    // The groups aren't locked while the driver is called, since it may log
+    // the push or pop to the callback right away.
+    fn push_group(&self, message: &str) {
+        lock(&self.state.groups).push(message.to_string());
+    }
+
+    fn pop_group(&self) {
+        lock(&self.state.groups).pop();
+    }
+}
+
+/// Locks `mutex`, ignoring that a callback panicked while holding it.
+fn lock<'a, T>(mutex: &'a Mutex<T>) -> MutexGuard<'a, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The `GLDEBUGPROC` registered with the driver, whose user parameter
+/// points to the `DebugOutputState` of the backend.
+extern "system" fn debug_message_callback(
+    source: GLenum,
+    ty: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    message: *const GLchar,
+    user_param: *mut c_void,
+) {
+    let state = unsafe { &*(user_param as *const DebugOutputState) };
+    let bytes = unsafe {
+        if length < 0 {
+            CStr::from_ptr(message).to_bytes()
+        } else {
+            slice::from_raw_parts(message as *const u8, length as usize)
+        }
+    };
+    let message = DebugMessage::from_gl(
+        String::from_utf8_lossy(bytes).into_owned(),
+        source,
+        ty,
+        id,
+        severity,
+        lock(&state.groups).clone(),
+    );
+    let callback = lock(&state.callback).clone();
+    if let Some(callback) = callback {
+        callback(&message);
+    }
+}
+
+/// Reads an object label with `get_label(buf_size, length, label)`, which is
+/// first called without a buffer to query the length of the label.
+fn read_object_label<F: Fn(GLsizei, &mut GLsizei, *mut GLchar)>(get_label: F) -> String {
+    let mut length = 0;
+    get_label(0, &mut length, ptr::null_mut());
+    if length <= 0 {
+        return String::new();
+    }
+    let mut label = vec![0u8; length as usize + 1];
+    get_label(
+        label.len() as GLsizei,
+        &mut length,
+        label.as_mut_ptr() as *mut GLchar,
+    );
+    label.truncate(length.max(0) as usize);
+    String::from_utf8_lossy(&label).into_owned()
+}
+
+/// The `GL_EXT_debug_label` type of the objects a `GL_KHR_debug` identifier
+/// names. Textures, framebuffers, renderbuffers, samplers and transform
+/// feedbacks use the same enum in both.
+fn ext_debug_label_type(identifier: GLenum) -> GLenum {
+    match identifier {
+        ffi::BUFFER => ffi::BUFFER_OBJECT_EXT,
+        ffi::SHADER => ffi::SHADER_OBJECT_EXT,
+        ffi::PROGRAM => ffi::PROGRAM_OBJECT_EXT,
+        ffi::VERTEX_ARRAY => ffi::VERTEX_ARRAY_OBJECT_EXT,
+        ffi::QUERY => ffi::QUERY_OBJECT_EXT,
+        ffi::PROGRAM_PIPELINE => ffi::PROGRAM_PIPELINE_OBJECT_EXT,
+        identifier => identifier,
+    }
+}
+
+/// The extension a `DebugGroup` was pushed with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum DebugGroupApi {
+    KhrDebug,
+    ExtDebugMarker,
+}
+
+impl DebugGroupApi {
+    /// Picks `GL_KHR_debug` over `GL_EXT_debug_marker`, or neither if the
+    /// context has no way to push groups.
+    fn pick(gl: &dyn Gl, khr_method: &str, ext_method: &str) -> Option<DebugGroupApi> {
+        let capabilities = gl.capabilities();
+        if capabilities.is_loaded(khr_method) {
+            Some(DebugGroupApi::KhrDebug)
+        } else if capabilities.is_loaded(ext_method) {
+            Some(DebugGroupApi::ExtDebugMarker)
+        } else {
+            None
+        }
+    }
+}
+
+/// A debug group, which tools like RenderDoc show the calls made during its
+/// lifetime under, popped when it is dropped.
+///
+/// The group is pushed with `push_debug_group_khr` if it was loaded, with
+/// `push_group_marker_ext` otherwise, and not at all if neither was.
+///
+/// ```no_run
+/// # fn draw(gl: &dyn gleam::gl::Gl) {
+/// let _group = gleam::gl::DebugGroup::new(gl, "shadow pass");
+/// gl.draw_arrays(gleam::gl::TRIANGLES, 0, 3);
+/// # }
+/// ```
+#[must_use = "the group is popped as soon as it is dropped"]
+pub struct DebugGroup<'a> {
+    gl: &'a dyn Gl,
+    api: Option<DebugGroupApi>,
+}
+
+impl<'a> DebugGroup<'a> {
+    pub fn new(gl: &'a dyn Gl, message: &str) -> DebugGroup<'a> {
+        let api = DebugGroupApi::pick(gl, "push_debug_group_khr", "push_group_marker_ext");
+        match api {
+            Some(DebugGroupApi::KhrDebug) => {
+                gl.push_debug_group_khr(ffi::DEBUG_SOURCE_APPLICATION, 0, message)
+            }
+            Some(DebugGroupApi::ExtDebugMarker) => gl.push_group_marker_ext(message),
+            None => {}
+        }
+        DebugGroup { gl, api }
+    }
+}
+
+impl<'a> Drop for DebugGroup<'a> {
+    fn drop(&mut self) {
+        match self.api {
+            Some(DebugGroupApi::KhrDebug) => self.gl.pop_debug_group_khr(),
+            Some(DebugGroupApi::ExtDebugMarker) => self.gl.pop_group_marker_ext(),
+            None => {}
+        }
+    }
+}
+
+/// Inserts a marker in the command stream, with `debug_message_insert_khr`
+/// if it was loaded and with `insert_event_marker_ext` otherwise.
+pub fn insert_debug_marker(gl: &dyn Gl, message: &str) {
+    match DebugGroupApi::pick(gl, "debug_message_insert_khr", "insert_event_marker_ext") {
+        Some(DebugGroupApi::KhrDebug) => gl.debug_message_insert_khr(
+            ffi::DEBUG_SOURCE_APPLICATION,
+            ffi::DEBUG_TYPE_MARKER,
+            0,
+            ffi::DEBUG_SEVERITY_NOTIFICATION,
+            message,
+        ),
+        Some(DebugGroupApi::ExtDebugMarker) => gl.insert_event_marker_ext(message),
+        None => {}
+    }
+}
+
+/// The debug groups a frame left unbalanced, as reported by
+/// `DebugGroupCheckingGl::end_frame`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UnbalancedDebugGroups {
+    /// The groups pushed with `push_debug_group_khr` and not popped yet,
+    /// outermost first, whichever frame pushed them.
+    pub khr_debug: Vec<String>,
+    /// The groups pushed with `push_group_marker_ext` and not popped yet,
+    /// outermost first, whichever frame pushed them.
+    pub ext_debug_marker: Vec<String>,
+    /// How many times this frame `pop_debug_group_khr` was called with no
+    /// group left.
+    pub khr_debug_extra_pops: usize,
+    /// How many times this frame `pop_group_marker_ext` was called with no
+    /// group left.
+    pub ext_debug_marker_extra_pops: usize,
+}
+
+impl UnbalancedDebugGroups {
+    fn is_empty(&self) -> bool {
+        *self == UnbalancedDebugGroups::default()
+    }
+}
+
+/// A wrapper around GL context that tracks the debug groups pushed and
+/// popped through it, and reports the ones left unbalanced when
+/// `end_frame` is called.
+///
+/// Keep the returned wrapper to call `end_frame`, and pass it on as an
+/// `Rc<dyn Gl>` to the code to check.
+pub struct DebugGroupCheckingGl<F, G = Rc<dyn Gl>> {
+    gl: G,
+    groups: Mutex<UnbalancedDebugGroups>,
+    callback: F,
+}
+
+impl<F: 'static + Fn(&UnbalancedDebugGroups)> DebugGroupCheckingGl<F> {
+    pub fn wrap(fns: Rc<dyn Gl>, callback: F) -> Rc<DebugGroupCheckingGl<F>> {
+        Rc::new(DebugGroupCheckingGl {
+            gl: fns,
+            groups: Mutex::default(),
+            callback,
+        })
+    }
+}
+
+impl<F: Fn(&UnbalancedDebugGroups), G: SharedGl> DebugGroupCheckingGl<F, G> {
+    /// Wraps the functions of a `SendGl`, with `SendGl::wrap`.
+    pub fn wrap_send(fns: G, callback: F) -> DebugGroupCheckingGl<F, G> {
+        DebugGroupCheckingGl {
+            gl: fns,
+            groups: Mutex::default(),
+            callback,
+        }
+    }
+}
+
+impl<F: Fn(&UnbalancedDebugGroups), G> DebugGroupCheckingGl<F, G> {
+    /// Calls the callback if groups are still open or were popped once too
+    /// often. The open groups stay open, so that a group the next frame pops
+    /// isn't counted as an extra pop, and are reported again until then.
+    pub fn end_frame(&self) {
+        let groups = {
+            let mut groups = lock(&self.groups);
+            let frame = groups.clone();
+            groups.khr_debug_extra_pops = 0;
+            groups.ext_debug_marker_extra_pops = 0;
+            frame
+        };
+        if !groups.is_empty() {
+            (self.callback)(&groups);
+        }
+    }
+
+    fn track(&self, call: &GlCall) {
+        let mut groups = lock(&self.groups);
+        match call.method {
+            "push_debug_group_khr" => {
+                let message = call.str_arg("message");
+                groups.khr_debug.push(message.to_string());
+            }
+            "push_group_marker_ext" => {
+                let message = call.str_arg("message");
+                groups.ext_debug_marker.push(message.to_string());
+            }
+            "pop_debug_group_khr" if groups.khr_debug.pop().is_none() => {
+                groups.khr_debug_extra_pops += 1;
+            }
+            "pop_group_marker_ext" if groups.ext_debug_marker.pop().is_none() => {
+                groups.ext_debug_marker_extra_pops += 1;
+            }
+            _ => {}
+        }
+    }
+}
@@ -8,18 +8,38 @@
 // except according to those terms.
 
 use ffi;
+#[cfg(feature = "dlopen")]
+use libloading;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+#[cfg(feature = "dlopen")]
+use std::ffi::OsStr;
 use std::ffi::{CStr, CString};
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
 use std::mem;
 use std::mem::size_of;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, Range};
 use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
 use std::rc::Rc;
+use std::slice;
 use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::{Duration, Instant};
 
 pub use ffi::types::*;
 pub use ffi::*;
 
+pub use pixel_format::{PixelDataError, PixelFormat, PixelStore};
+
 pub use ffi_gl::Gl as GlFfi;
 pub use ffi_gles::Gles2 as GlesFfi;
 
@@ -40,52 +60,92 @@ impl Default for GlType {
     }
 }
 
-fn bpp(format: GLenum, pixel_type: GLenum) -> GLsizei {
-    let colors = match format {
-        ffi::RED => 1,
-        ffi::RGB => 3,
-        ffi::BGR => 3,
+fn calculate_length(
+    width: GLsizei,
+    height: GLsizei,
+    format: GLenum,
+    pixel_type: GLenum,
+) -> Result<usize, PixelDataError> {
+    let pixel = PixelFormat::new(format, pixel_type)?.bytes_per_pixel;
+    (width.max(0) as usize)
+        .checked_mul(height.max(0) as usize)
+        .and_then(|pixels| pixels.checked_mul(pixel))
+        .ok_or(PixelDataError::SizeOverflow)
+}
 
-        ffi::RGBA => 4,
-        ffi::BGRA => 4,
+/// Declares an enum whose variants are the `ffi` constants they are assigned,
+/// with conversions from and to `GLenum`.
+macro_rules! gl_enum {
+    ($(#[$attr:meta])* pub enum $name:ident {
+        $($(#[$variant_attr:meta])* $variant:ident = $value:ident,)+
+    }) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+        #[repr(u32)]
+        pub enum $name {
+            $($(#[$variant_attr])* $variant = ffi::$value,)+
+        }
 
-        ffi::ALPHA => 1,
-        ffi::R16 => 1,
-        ffi::LUMINANCE => 1,
-        ffi::DEPTH_COMPONENT => 1,
-        _ => panic!("unsupported format for read_pixels: {:?}", format),
-    };
-    let depth = match pixel_type {
-        ffi::UNSIGNED_BYTE => 1,
-        ffi::UNSIGNED_SHORT => 2,
-        ffi::SHORT => 2,
-        ffi::FLOAT => 4,
-        ffi::UNSIGNED_INT_8_8_8_8_REV => return 4,
-        _ => panic!("unsupported pixel_type for read_pixels: {:?}", pixel_type),
-    };
-    colors * depth
+        impl $name {
+            /// Returns the variant whose value is `value`, if any.
+            pub fn from_gl(value: GLenum) -> Option<$name> {
+                match value {
+                    $(ffi::$value => Some($name::$variant),)+
+                    _ => None,
+                }
+            }
+
+            pub fn to_gl(self) -> GLenum {
+                self as GLenum
+            }
+        }
+
+        impl From<$name> for GLenum {
+            fn from(value: $name) -> GLenum {
+                value.to_gl()
+            }
+        }
+    }
 }
 
-fn calculate_length(width: GLsizei, height: GLsizei, format: GLenum, pixel_type: GLenum) -> usize {
-    (width * height * bpp(format, pixel_type)) as usize
+/// Returns early from a `Gl` method whose call `StateCachingGl` elided, which
+/// only happens to methods that return nothing.
+macro_rules! return_elided {
+    () => {
+        return
+    };
+    ($retty:ty) => {
+        unreachable!("calls that return a value aren't elided")
+    };
 }
 
-pub struct DebugMessage {
-    pub message: String,
-    pub source: GLenum,
-    pub ty: GLenum,
-    pub id: GLenum,
-    pub severity: GLenum,
+/// The `GlCall` of a `Gl` method with its arguments, for the wrappers that
+/// look into the calls they forward.
+macro_rules! gl_call {
+    ($name:ident $(, $arg:ident)*) => {
+        &GlCall {
+            method: stringify!($name),
+            args: &[$((stringify!($arg), &$arg as &dyn GlArg)),*],
+        }
+    };
 }
 
 macro_rules! declare_gl_apis {
     // garbo is a hack to handle unsafe methods.
     ($($(unsafe $([$garbo:expr])*)* fn $name:ident(&self $(, $arg:ident: $t:ty)* $(,)*) $(-> $retty:ty)* ;)+) => {
         pub trait Gl {
+            /// Returns what the context supports, as queried when it was
+            /// loaded.
+            fn capabilities(&self) -> &GlCapabilities;
+
             $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* ;)+
         }
 
         impl Gl for ErrorCheckingGl {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.capabilities()
+            }
+
             $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
                 let rv = self.gl.$name($($arg,)*);
                 assert_eq!(self.gl.get_error(), 0);
@@ -93,21 +153,30 @@ macro_rules! declare_gl_apis {
             })+
         }
 
-        impl<F: Fn(&dyn Gl, &str, GLenum)> Gl for ErrorReactingGl<F> {
+        impl<F: Fn(&dyn Gl, &str, GLenum), G: SharedGl> Gl for ErrorReactingGl<F, G> {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.as_gl().capabilities()
+            }
+
             $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
-                let rv = self.gl.$name($($arg,)*);
-                let error = self.gl.get_error();
+                let gl = self.gl.as_gl();
+                let rv = gl.$name($($arg,)*);
+                let error = gl.get_error();
                 if error != 0 {
-                    (self.callback)(&*self.gl, stringify!($name), error);
+                    (self.callback)(gl, stringify!($name), error);
                 }
                 rv
             })+
         }
 
-        impl<F: Fn(&str, Duration)> Gl for ProfilingGl<F> {
+        impl<F: Fn(&str, Duration), G: SharedGl> Gl for ProfilingGl<F, G> {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.as_gl().capabilities()
+            }
+
             $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
                 let start = Instant::now();
-                let rv = self.gl.$name($($arg,)*);
+                let rv = self.gl.as_gl().$name($($arg,)*);
                 let duration = Instant::now() - start;
                 if duration > self.threshold {
                     (self.callback)(stringify!($name), duration);
@@ -115,6 +184,106 @@ macro_rules! declare_gl_apis {
                 rv
             })+
         }
+
+        impl<F: Fn(&UnbalancedDebugGroups), G: SharedGl> Gl for DebugGroupCheckingGl<F, G> {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.as_gl().capabilities()
+            }
+
+            $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
+                self.track(gl_call!($name $(, $arg)*));
+                self.gl.as_gl().$name($($arg,)*)
+            })+
+        }
+
+        impl<G: SharedGl> Gl for StatsGl<G> {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.as_gl().capabilities()
+            }
+
+            $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
+                self.count(gl_call!($name $(, $arg)*));
+                self.gl.as_gl().$name($($arg,)*)
+            })+
+        }
+
+        impl<G: SharedGl> Gl for StateCachingGl<G> {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.as_gl().capabilities()
+            }
+
+            $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
+                if !self.forward(gl_call!($name $(, $arg)*)) {
+                    return_elided!($($retty)*);
+                }
+                let rv = self.gl.as_gl().$name($($arg,)*);
+                self.returned(stringify!($name), &rv);
+                rv
+            })+
+        }
+
+        impl<F: Fn(&LiveObjects), G: SharedGl> Gl for LeakTrackingGl<F, G> {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.as_gl().capabilities()
+            }
+
+            $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
+                self.track_args(gl_call!($name $(, $arg)*));
+                let rv = self.gl.as_gl().$name($($arg,)*);
+                self.track_result(stringify!($name), &rv);
+                rv
+            })+
+        }
+
+        impl Gl for RecordingGl {
+            fn capabilities(&self) -> &GlCapabilities {
+                self.gl.capabilities()
+            }
+
+            $($(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*) $(-> $retty)* {
+                let args = vec![$(<$t as ToTraceValue>::to_trace_value(&$arg)),*];
+                self.before_call(stringify!($name), &args);
+                let rv = self.gl.$name($($arg,)*);
+                self.record(stringify!($name), args, ToTraceValue::to_trace_value(&rv));
+                rv
+            })+
+        }
+
+        #[allow(unused_parens, clippy::too_many_arguments, clippy::missing_safety_doc)]
+        impl TryGl {
+            $(pub $(unsafe $($garbo)*)* fn $name(&self $(, $arg:$t)*)
+                -> Result<<($($retty)*) as TryGlResult>::Output, GlError> {
+                self.check(gl_call!($name $(, $arg)*))?;
+                let rv = self.gl.$name($($arg,)*);
+                self.driver_error(stringify!($name))?;
+                rv.into_try_result(stringify!($name))
+            })+
+        }
+
+        const GL_METHOD_NAMES: &[&str] = &[$(stringify!($name)),+];
+
+        #[allow(unused_mut, unused_variables)]
+        fn replay_call(
+            gl: &dyn Gl,
+            call: &TraceCall,
+            names: &mut ReplayNames,
+        ) -> Result<(), ReplayError> {
+            match call.name {
+                $(stringify!($name) => {
+                    let args = names.map_args(call, &[$(stringify!($arg)),*])?;
+                    let mut args = args.iter();
+                    $(
+                        let $arg = args.next().unwrap();
+                        let mut $arg = <$t as ReplayArg>::prepare($arg)
+                            .ok_or(ReplayError::BadArguments(call.name))?;
+                        let $arg = <$t as ReplayArg>::get(&mut $arg);
+                    )*
+                    let rv = $(unsafe $($garbo)*)* { gl.$name($($arg),*) };
+                    names.map_result(gl, call, &ToTraceValue::to_trace_value(&rv))
+                })+
+                _ => Err(ReplayError::UnknownMethod(call.name.to_string())),
+            }
+        }
     }
 }
 
@@ -155,7 +324,7 @@ declare_gl_apis! {
         format: GLenum,
         pixel_type: GLenum,
         dst_buffer: &mut [u8],
-    );
+    ) -> Result<(), PixelDataError>;
     fn read_pixels(
         &self,
         x: GLint,
@@ -164,7 +333,7 @@ declare_gl_apis! {
         height: GLsizei,
         format: GLenum,
         pixel_type: GLenum,
-    ) -> Vec<u8>;
+    ) -> Result<Vec<u8>, PixelDataError>;
     unsafe fn read_pixels_into_pbo(
         &self,
         x: GLint,
@@ -187,6 +356,7 @@ declare_gl_apis! {
     fn begin_query(&self, target: GLenum, id: GLuint);
     fn end_query(&self, target: GLenum);
     fn query_counter(&self, id: GLuint, target: GLenum);
+    fn get_query_iv(&self, target: GLenum, pname: GLenum) -> i32;
     fn get_query_object_iv(&self, id: GLuint, pname: GLenum) -> i32;
     fn get_query_object_uiv(&self, id: GLuint, pname: GLenum) -> u32;
     fn get_query_object_i64v(&self, id: GLuint, pname: GLenum) -> i64;
@@ -254,7 +424,7 @@ declare_gl_apis! {
         format: GLenum,
         ty: GLenum,
         opt_data: Option<&[u8]>,
-    );
+    ) -> Result<(), PixelDataError>;
     fn compressed_tex_image_2d(
         &self,
         target: GLenum,
@@ -288,7 +458,7 @@ declare_gl_apis! {
         format: GLenum,
         ty: GLenum,
         opt_data: Option<&[u8]>,
-    );
+    ) -> Result<(), PixelDataError>;
     fn copy_tex_image_2d(
         &self,
         target: GLenum,
@@ -334,7 +504,7 @@ declare_gl_apis! {
         format: GLenum,
         ty: GLenum,
         data: &[u8],
-    );
+    ) -> Result<(), PixelDataError>;
     fn tex_sub_image_2d_pbo(
         &self,
         target: GLenum,
@@ -360,7 +530,7 @@ declare_gl_apis! {
         format: GLenum,
         ty: GLenum,
         data: &[u8],
-    );
+    ) -> Result<(), PixelDataError>;
     fn tex_sub_image_3d_pbo(
         &self,
         target: GLenum,
@@ -399,7 +569,7 @@ declare_gl_apis! {
         format: GLenum,
         ty: GLenum,
         output: &mut [u8],
-    );
+    ) -> Result<(), PixelDataError>;
     unsafe fn copy_image_sub_data(
         &self,
         src_name: GLuint,
@@ -596,6 +766,28 @@ declare_gl_apis! {
         pname: GLenum,
     ) -> Vec<GLint>;
     fn get_active_uniform_block_name(&self, program: GLuint, index: GLuint) -> String;
+    // GL 4.3, GLES 3.1 or GL_ARB_program_interface_query
+    fn get_program_interface_i(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        pname: GLenum,
+    ) -> GLint;
+    fn get_program_resource_name(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+    ) -> String;
+    // Returns one value per property, or more for properties such as
+    // ACTIVE_VARIABLES that have several.
+    fn get_program_resource_iv(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+        props: &[GLenum],
+    ) -> Vec<GLint>;
     fn get_attrib_location(&self, program: GLuint, name: &str) -> c_int;
     fn get_frag_data_location(&self, program: GLuint, name: &str) -> c_int;
     fn get_uniform_location(&self, program: GLuint, name: &str) -> c_int;
@@ -680,6 +872,24 @@ declare_gl_apis! {
 
     // GL_KHR_debug
     fn get_debug_messages(&self) -> Vec<DebugMessage>;
+    // Passes the messages logged from now on to `callback` instead of the
+    // log read by `get_debug_messages`, or to the log again if `None`.
+    fn set_debug_callback(&self, callback: Option<DebugCallback>);
+    // `source`, `ty` and `severity` may be `DONT_CARE`, and an empty `ids`
+    // matches every id.
+    fn debug_message_control(
+        &self,
+        source: GLenum,
+        ty: GLenum,
+        severity: GLenum,
+        ids: &[GLuint],
+        enabled: bool,
+    );
+    // `identifier` is the kind of object `name` is, such as `TEXTURE` or
+    // `BUFFER`. An empty `label` removes the label.
+    fn object_label(&self, identifier: GLenum, name: GLuint, label: &str);
+    fn get_object_label(&self, identifier: GLenum, name: GLuint) -> String;
+    fn object_ptr_label(&self, sync: GLsync, label: &str);
 
     // GL_ANGLE_provoking_vertex
     fn provoking_vertex_angle(&self, mode: GLenum);
@@ -777,9 +987,34 @@ impl ErrorCheckingGl {
     }
 }
 
+/// A GL context that wrappers can hold: either a shared `Rc<dyn Gl>`, or the
+/// functions owned by a `SendGl`, possibly already wrapped, for wrappers
+/// that are moved between threads with it.
+pub trait SharedGl {
+    fn as_gl(&self) -> &dyn Gl;
+}
+
+impl SharedGl for Rc<dyn Gl> {
+    fn as_gl(&self) -> &dyn Gl {
+        &**self
+    }
+}
+
+impl SharedGl for Box<dyn Gl + Send> {
+    fn as_gl(&self) -> &dyn Gl {
+        &**self
+    }
+}
+
+impl<T: Gl> SharedGl for T {
+    fn as_gl(&self) -> &dyn Gl {
+        self
+    }
+}
+
 /// A wrapper around GL context that calls a specified callback on each GL error.
-pub struct ErrorReactingGl<F> {
-    gl: Rc<dyn Gl>,
+pub struct ErrorReactingGl<F, G = Rc<dyn Gl>> {
+    gl: G,
     callback: F,
 }
 
@@ -789,10 +1024,17 @@ impl<F: 'static + Fn(&dyn Gl, &str, GLenum)> ErrorReactingGl<F> {
     }
 }
 
+impl<F: Fn(&dyn Gl, &str, GLenum), G: SharedGl> ErrorReactingGl<F, G> {
+    /// Wraps the functions of a `SendGl`, with `SendGl::wrap`.
+    pub fn wrap_send(fns: G, callback: F) -> ErrorReactingGl<F, G> {
+        ErrorReactingGl { gl: fns, callback }
+    }
+}
+
 /// A wrapper around GL context that times each call and invokes the callback
 /// if the call takes longer than the threshold.
-pub struct ProfilingGl<F> {
-    gl: Rc<dyn Gl>,
+pub struct ProfilingGl<F, G = Rc<dyn Gl>> {
+    gl: G,
     threshold: Duration,
     callback: F,
 }
@@ -807,6 +1049,17 @@ impl<F: 'static + Fn(&str, Duration)> ProfilingGl<F> {
     }
 }
 
+impl<F: Fn(&str, Duration), G: SharedGl> ProfilingGl<F, G> {
+    /// Wraps the functions of a `SendGl`, with `SendGl::wrap`.
+    pub fn wrap_send(fns: G, threshold: Duration, callback: F) -> ProfilingGl<F, G> {
+        ProfilingGl {
+            gl: fns,
+            threshold,
+            callback,
+        }
+    }
+}
+
 #[inline]
 pub fn buffer_data<T>(gl_: &dyn Gl, target: GLenum, data: &[T], usage: GLenum) {
     gl_.buffer_data_untyped(
@@ -837,5 +1090,24 @@ pub fn buffer_sub_data<T>(gl_: &dyn Gl, target: GLenum, offset: isize, data: &[T
     );
 }
 
+include!("debug.rs");
+include!("capabilities.rs");
 include!("gl_fns.rs");
 include!("gles_fns.rs");
+include!("auto_fns.rs");
+include!("trace.rs");
+include!("mock_gl.rs");
+include!("validating_gl.rs");
+include!("gl_call.rs");
+include!("try_gl.rs");
+include!("send_gl.rs");
+include!("gpu_profiler.rs");
+include!("stats_gl.rs");
+include!("state_caching_gl.rs");
+include!("leak_tracking_gl.rs");
+include!("typed.rs");
+include!("loader.rs");
+#[cfg(feature = "dlopen")]
+include!("library_loader.rs");
+#[cfg(feature = "software")]
+include!("software_gl.rs");
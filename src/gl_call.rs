@@ -0,0 +1,203 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// A call to a `Gl` method, as seen by the wrappers that look into the calls
+/// they forward, such as `TryGl` and `StatsGl`.
+///
+/// Arguments are looked up by the name they have in the `Gl` trait. Asking
+/// for an argument the method doesn't have, or for one of another type, is
+/// a bug in the wrapper and panics.
+struct GlCall<'a> {
+    method: &'static str,
+    args: &'a [(&'static str, &'a dyn GlArg)],
+}
+
+impl<'a> GlCall<'a> {
+    fn arg(&self, name: &str) -> &'a dyn GlArg {
+        match self.args.iter().find(|arg| arg.0 == name) {
+            Some(&(_, arg)) => arg,
+            None => panic!("{} has no argument `{}`", self.method, name),
+        }
+    }
+
+    fn mismatch(&self, name: &str, ty: &str) -> ! {
+        panic!("argument `{}` of {} is not {}", name, self.method, ty)
+    }
+
+    /// The value of a `GLenum` argument.
+    fn enum_arg(&self, name: &str) -> GLenum {
+        self.arg(name)
+            .as_uint()
+            .unwrap_or_else(|| self.mismatch(name, "a GLenum"))
+    }
+
+    /// The value of a `GLuint` argument, such as the name of an object.
+    fn uint_arg(&self, name: &str) -> GLuint {
+        self.arg(name)
+            .as_uint()
+            .unwrap_or_else(|| self.mismatch(name, "a GLuint"))
+    }
+
+    /// The value of a signed integer argument, such as a `GLsizei` count or
+    /// a `GLsizeiptr` size.
+    fn int_arg(&self, name: &str) -> i64 {
+        self.arg(name)
+            .as_int()
+            .unwrap_or_else(|| self.mismatch(name, "a signed integer"))
+    }
+
+    fn str_arg(&self, name: &str) -> &'a str {
+        self.arg(name)
+            .as_str()
+            .unwrap_or_else(|| self.mismatch(name, "a string"))
+    }
+
+    fn ptr_arg(&self, name: &str) -> *const c_void {
+        self.arg(name)
+            .as_ptr()
+            .unwrap_or_else(|| self.mismatch(name, "a pointer"))
+    }
+
+    /// The names in a slice of them, such as the one passed to
+    /// `delete_textures`.
+    fn names_arg(&self, name: &str) -> &'a [GLuint] {
+        self.arg(name)
+            .as_names()
+            .unwrap_or_else(|| self.mismatch(name, "a slice of names"))
+    }
+
+    /// The size of the data of a slice argument, in bytes.
+    fn byte_len_arg(&self, name: &str) -> usize {
+        self.arg(name)
+            .byte_len()
+            .unwrap_or_else(|| self.mismatch(name, "a slice"))
+    }
+
+    /// Whether any argument is an output slice too short to hold a result.
+    fn has_empty_output(&self) -> bool {
+        self.args.iter().any(|&(_, arg)| arg.is_empty_output())
+    }
+}
+
+/// An argument of a `Gl` method, which reports its value if it has one of
+/// the types `GlCall` reads.
+trait GlArg {
+    /// Whether this is an output slice too short to hold any result.
+    fn is_empty_output(&self) -> bool {
+        false
+    }
+
+    fn as_uint(&self) -> Option<u32> {
+        None
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+
+    fn as_int(&self) -> Option<i64> {
+        None
+    }
+
+    /// The size of the data of a slice, in bytes.
+    fn byte_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn as_names(&self) -> Option<&[GLuint]> {
+        None
+    }
+
+    fn as_ptr(&self) -> Option<*const c_void> {
+        None
+    }
+}
+
+/// The type of the elements of a slice argument.
+trait GlArgSliceElement: Sized {
+    fn as_names(_slice: &[Self]) -> Option<&[GLuint]> {
+        None
+    }
+}
+
+impl GlArgSliceElement for u32 {
+    fn as_names(slice: &[u32]) -> Option<&[GLuint]> {
+        Some(slice)
+    }
+}
+
+impl GlArgSliceElement for u8 {}
+impl GlArgSliceElement for i32 {}
+impl GlArgSliceElement for f32 {}
+impl GlArgSliceElement for &str {}
+impl GlArgSliceElement for &[u8] {}
+
+macro_rules! impl_gl_arg {
+    ($($t:ty),+) => {
+        $(impl GlArg for $t {})+
+    }
+}
+
+impl_gl_arg!(u64, usize, f32, f64, u8, bool);
+
+impl GlArg for i32 {
+    fn as_int(&self) -> Option<i64> {
+        Some(*self as i64)
+    }
+}
+
+impl GlArg for i64 {
+    fn as_int(&self) -> Option<i64> {
+        Some(*self)
+    }
+}
+
+impl GlArg for isize {
+    fn as_int(&self) -> Option<i64> {
+        Some(*self as i64)
+    }
+}
+
+impl GlArg for &str {
+    fn as_str(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl GlArg for u32 {
+    fn as_uint(&self) -> Option<u32> {
+        Some(*self)
+    }
+}
+
+impl<T: GlArgSliceElement> GlArg for &[T] {
+    fn byte_len(&self) -> Option<usize> {
+        Some(mem::size_of_val(*self))
+    }
+
+    fn as_names(&self) -> Option<&[GLuint]> {
+        T::as_names(self)
+    }
+}
+
+impl<T> GlArg for &mut [T] {
+    fn is_empty_output(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T> GlArg for Option<T> {}
+
+impl<T> GlArg for Vec<T> {}
+
+impl<T> GlArg for *const T {
+    fn as_ptr(&self) -> Option<*const c_void> {
+        Some(*self as *const c_void)
+    }
+}
@@ -9,19 +9,347 @@
 
 pub struct GlFns {
     ffi_gl_: GlFfi,
+    capabilities: GlCapabilities,
+    debug_output: DebugOutput,
 }
 
+// SAFETY: the loaded function pointers are the only fields that aren't
+// `Send`, and they are valid on any thread the context is current on, which
+// every `Gl` call already requires. Moving the context between threads is
+// left to `SendGl`. It isn't `Sync`, as two threads can't both have the
+// context current.
+unsafe impl Send for GlFns {}
+
 impl GlFns {
     pub unsafe fn load_with<'a, F>(loadfn: F) -> Rc<dyn Gl>
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        GlFns::load_with_report(loadfn).0
+    }
+
+    /// Loads the context like `load_with`, also reporting every entry point
+    /// the loader couldn't find, so that fallbacks can be picked before any
+    /// call reaches a missing one.
+    ///
+    /// # Safety
+    ///
+    /// `loadfn` must return null or a pointer to the named GL function of
+    /// the context that will be current when this context is used.
+    pub unsafe fn load_with_report<F>(loadfn: F) -> (Rc<dyn Gl>, LoadReport)
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        let (fns, report) = GlFns::load(loadfn);
+        (Rc::new(fns) as Rc<dyn Gl>, report)
+    }
+
+    /// Loads the context like `load_with`, from `loader`.
+    ///
+    /// # Safety
+    ///
+    /// See `load_with_report`.
+    pub unsafe fn load_from<L: GlLoader>(mut loader: L) -> Rc<dyn Gl> {
+        GlFns::load_with(|symbol| loader.get_proc_address(symbol))
+    }
+
+    unsafe fn load<F>(loadfn: F) -> (GlFns, LoadReport)
     where
         F: FnMut(&str) -> *const c_void,
     {
         let ffi_gl_ = GlFfi::load_with(loadfn);
-        Rc::new(GlFns { ffi_gl_: ffi_gl_ }) as Rc<dyn Gl>
+        let entry_points = GlFns::entry_points(&ffi_gl_);
+        let mut fns = GlFns {
+            ffi_gl_,
+            capabilities: GlCapabilities::default(),
+            debug_output: DebugOutput::default(),
+        };
+        fns.capabilities = GlCapabilities::query_entry_points(&fns, &entry_points);
+        (fns, LoadReport::new(&entry_points))
+    }
+
+    /// Reads the `PACK_*` or `UNPACK_*` pixel store state.
+    fn pixel_store(&self, pack: bool, three_d: bool) -> PixelStore {
+        let get = |pack_name, unpack_name| {
+            let mut value = 0;
+            unsafe {
+                self.ffi_gl_
+                    .GetIntegerv(if pack { pack_name } else { unpack_name }, &mut value);
+            }
+            value
+        };
+        PixelStore {
+            alignment: get(ffi::PACK_ALIGNMENT, ffi::UNPACK_ALIGNMENT),
+            row_length: get(ffi::PACK_ROW_LENGTH, ffi::UNPACK_ROW_LENGTH),
+            image_height: if three_d {
+                get(ffi::PACK_IMAGE_HEIGHT, ffi::UNPACK_IMAGE_HEIGHT)
+            } else {
+                0
+            },
+            skip_pixels: get(ffi::PACK_SKIP_PIXELS, ffi::UNPACK_SKIP_PIXELS),
+            skip_rows: get(ffi::PACK_SKIP_ROWS, ffi::UNPACK_SKIP_ROWS),
+            skip_images: if three_d {
+                get(ffi::PACK_SKIP_IMAGES, ffi::UNPACK_SKIP_IMAGES)
+            } else {
+                0
+            },
+        }
+    }
+
+    /// The `Gl` methods this backend implements, with the entry points each
+    /// of them calls.
+    fn entry_points(ffi_gl_: &GlFfi) -> EntryPoints {
+        entry_points!(ffi_gl_;
+            get_type: [];
+            buffer_data_untyped: [BufferData];
+            buffer_sub_data_untyped: [BufferSubData];
+            map_buffer: [MapBuffer];
+            map_buffer_range: [MapBufferRange];
+            unmap_buffer: [UnmapBuffer];
+            tex_buffer: [TexBuffer];
+            shader_source: [ShaderSource];
+            read_buffer: [ReadBuffer];
+            read_pixels_into_buffer: [PixelStorei, ReadPixels, GetIntegerv];
+            read_pixels: [PixelStorei, ReadPixels, GetIntegerv];
+            read_pixels_into_pbo: [ReadPixels];
+            sample_coverage: [SampleCoverage];
+            polygon_offset: [PolygonOffset];
+            pixel_store_i: [PixelStorei];
+            gen_buffers: [GenBuffers];
+            gen_renderbuffers: [GenRenderbuffers];
+            gen_framebuffers: [GenFramebuffers];
+            gen_textures: [GenTextures];
+            gen_vertex_arrays: [GenVertexArrays];
+            gen_vertex_arrays_apple: [GenVertexArraysAPPLE];
+            gen_queries: [GenQueries];
+            begin_query: [BeginQuery];
+            end_query: [EndQuery];
+            query_counter: [QueryCounter];
+            get_query_iv: [GetQueryiv];
+            get_query_object_iv: [GetQueryObjectiv];
+            get_query_object_uiv: [GetQueryObjectuiv];
+            get_query_object_i64v: [GetQueryObjecti64v];
+            get_query_object_ui64v: [GetQueryObjectui64v];
+            delete_queries: [DeleteQueries];
+            delete_vertex_arrays: [DeleteVertexArrays];
+            delete_vertex_arrays_apple: [DeleteVertexArraysAPPLE];
+            delete_buffers: [DeleteBuffers];
+            delete_renderbuffers: [DeleteRenderbuffers];
+            delete_framebuffers: [DeleteFramebuffers];
+            delete_textures: [DeleteTextures];
+            framebuffer_renderbuffer: [FramebufferRenderbuffer];
+            renderbuffer_storage: [RenderbufferStorage];
+            depth_func: [DepthFunc];
+            active_texture: [ActiveTexture];
+            attach_shader: [AttachShader];
+            bind_attrib_location: [BindAttribLocation];
+            get_uniform_iv: [GetUniformiv];
+            get_uniform_fv: [GetUniformfv];
+            get_uniform_block_index: [GetUniformBlockIndex];
+            get_uniform_indices: [GetUniformIndices];
+            bind_buffer_base: [BindBufferBase];
+            bind_buffer_range: [BindBufferRange];
+            uniform_block_binding: [UniformBlockBinding];
+            bind_buffer: [BindBuffer];
+            bind_vertex_array: [BindVertexArray];
+            bind_vertex_array_apple: [BindVertexArrayAPPLE];
+            bind_renderbuffer: [BindRenderbuffer];
+            bind_framebuffer: [BindFramebuffer];
+            bind_texture: [BindTexture];
+            bind_vertex_buffer: [BindVertexBuffer];
+            draw_buffers: [DrawBuffers];
+            tex_image_2d: [TexImage2D, GetIntegerv];
+            compressed_tex_image_2d: [CompressedTexImage2D, GetIntegerv];
+            compressed_tex_sub_image_2d: [CompressedTexSubImage2D, GetIntegerv];
+            tex_image_3d: [TexImage3D, GetIntegerv];
+            copy_tex_image_2d: [CopyTexImage2D, GetIntegerv];
+            copy_tex_sub_image_2d: [CopyTexSubImage2D, GetIntegerv];
+            copy_tex_sub_image_3d: [CopyTexSubImage3D, GetIntegerv];
+            tex_sub_image_2d: [TexSubImage2D, GetIntegerv];
+            tex_sub_image_2d_pbo: [TexSubImage2D, GetIntegerv];
+            tex_sub_image_3d: [TexSubImage3D, GetIntegerv];
+            tex_sub_image_3d_pbo: [TexSubImage3D, GetIntegerv];
+            tex_storage_2d: [TexStorage2D];
+            tex_storage_3d: [TexStorage3D];
+            get_tex_image_into_buffer: [GetTexLevelParameteriv, GetTexImage, GetIntegerv];
+            copy_image_sub_data: [CopyImageSubData];
+            invalidate_framebuffer: [InvalidateFramebuffer];
+            invalidate_sub_framebuffer: [InvalidateSubFramebuffer];
+            get_integer_v: [GetIntegerv];
+            get_integer_64v: [GetInteger64v];
+            get_integer_iv: [GetIntegeri_v];
+            get_integer_64iv: [GetInteger64i_v];
+            get_boolean_v: [GetBooleanv];
+            get_float_v: [GetFloatv];
+            get_framebuffer_attachment_parameter_iv: [GetFramebufferAttachmentParameteriv];
+            get_renderbuffer_parameter_iv: [GetRenderbufferParameteriv];
+            get_tex_parameter_iv: [GetTexParameteriv];
+            get_tex_parameter_fv: [GetTexParameterfv];
+            tex_parameter_i: [TexParameteri];
+            tex_parameter_f: [TexParameterf];
+            framebuffer_texture_2d: [FramebufferTexture2D];
+            framebuffer_texture_layer: [FramebufferTextureLayer];
+            blit_framebuffer: [BlitFramebuffer];
+            vertex_attrib_4f: [VertexAttrib4f];
+            vertex_attrib_binding: [VertexAttribBinding];
+            vertex_attrib_pointer_f32: [VertexAttribPointer];
+            vertex_attrib_pointer: [VertexAttribPointer];
+            vertex_attrib_i_pointer: [VertexAttribIPointer];
+            vertex_attrib_divisor: [VertexAttribDivisor];
+            vertex_attrib_format: [VertexAttribFormat];
+            vertex_attrib_i_format: [VertexAttribIFormat];
+            vertex_binding_divisor: [VertexBindingDivisor];
+            viewport: [Viewport];
+            scissor: [Scissor];
+            line_width: [LineWidth];
+            use_program: [UseProgram];
+            validate_program: [ValidateProgram];
+            draw_arrays: [DrawArrays];
+            draw_arrays_instanced: [DrawArraysInstanced];
+            draw_elements: [DrawElements];
+            draw_elements_instanced: [DrawElementsInstanced];
+            blend_color: [BlendColor];
+            blend_func: [BlendFunc];
+            blend_func_separate: [BlendFuncSeparate];
+            blend_equation: [BlendEquation];
+            blend_equation_separate: [BlendEquationSeparate];
+            color_mask: [ColorMask];
+            cull_face: [CullFace];
+            front_face: [FrontFace];
+            enable: [Enable];
+            disable: [Disable];
+            hint: [Hint];
+            is_enabled: [IsEnabled];
+            is_shader: [IsShader];
+            is_texture: [IsTexture];
+            is_framebuffer: [IsFramebuffer];
+            is_renderbuffer: [IsRenderbuffer];
+            check_frame_buffer_status: [CheckFramebufferStatus];
+            enable_vertex_attrib_array: [EnableVertexAttribArray];
+            disable_vertex_attrib_array: [DisableVertexAttribArray];
+            uniform_1f: [Uniform1f];
+            uniform_1fv: [Uniform1fv];
+            uniform_1i: [Uniform1i];
+            uniform_1iv: [Uniform1iv];
+            uniform_1ui: [Uniform1ui];
+            uniform_2f: [Uniform2f];
+            uniform_2fv: [Uniform2fv];
+            uniform_2i: [Uniform2i];
+            uniform_2iv: [Uniform2iv];
+            uniform_2ui: [Uniform2ui];
+            uniform_3f: [Uniform3f];
+            uniform_3fv: [Uniform3fv];
+            uniform_3i: [Uniform3i];
+            uniform_3iv: [Uniform3iv];
+            uniform_3ui: [Uniform3ui];
+            uniform_4f: [Uniform4f];
+            uniform_4i: [Uniform4i];
+            uniform_4iv: [Uniform4iv];
+            uniform_4ui: [Uniform4ui];
+            uniform_4fv: [Uniform4fv];
+            uniform_matrix_2fv: [UniformMatrix2fv];
+            uniform_matrix_3fv: [UniformMatrix3fv];
+            uniform_matrix_4fv: [UniformMatrix4fv];
+            depth_mask: [DepthMask];
+            depth_range: [DepthRange];
+            get_active_attrib: [GetActiveAttrib, GetProgramiv];
+            get_active_uniform: [GetActiveUniform, GetProgramiv];
+            get_active_uniforms_iv: [GetActiveUniformsiv];
+            get_active_uniform_block_i: [GetActiveUniformBlockiv];
+            get_active_uniform_block_iv: [GetActiveUniformBlockiv];
+            get_active_uniform_block_name: [GetActiveUniformBlockName, GetActiveUniformBlockiv];
+            get_program_interface_i: [GetProgramInterfaceiv];
+            get_program_resource_name: [GetProgramResourceName, GetProgramResourceiv];
+            get_program_resource_iv: [GetProgramResourceiv];
+            get_attrib_location: [GetAttribLocation];
+            get_frag_data_location: [GetFragDataLocation];
+            get_uniform_location: [GetUniformLocation];
+            get_program_info_log: [GetProgramInfoLog, GetProgramiv];
+            get_program_iv: [GetProgramiv];
+            get_program_binary: [GetProgramBinary, GetProgramiv];
+            program_binary: [ProgramBinary];
+            program_parameter_i: [ProgramParameteri];
+            get_vertex_attrib_iv: [GetVertexAttribiv];
+            get_vertex_attrib_fv: [GetVertexAttribfv];
+            get_vertex_attrib_pointer_v: [GetVertexAttribPointerv];
+            get_buffer_parameter_iv: [GetBufferParameteriv];
+            get_shader_info_log: [GetShaderInfoLog, GetShaderiv];
+            get_string: [GetString];
+            get_string_i: [GetStringi];
+            get_shader_iv: [GetShaderiv];
+            get_shader_precision_format: [];
+            compile_shader: [CompileShader];
+            create_program: [CreateProgram];
+            delete_program: [DeleteProgram];
+            create_shader: [CreateShader];
+            delete_shader: [DeleteShader];
+            detach_shader: [DetachShader];
+            link_program: [LinkProgram];
+            clear_color: [ClearColor];
+            clear: [Clear];
+            clear_depth: [ClearDepth];
+            clear_stencil: [ClearStencil];
+            flush: [Flush];
+            finish: [Finish];
+            get_error: [GetError];
+            stencil_mask: [StencilMask];
+            stencil_mask_separate: [StencilMaskSeparate];
+            stencil_func: [StencilFunc];
+            stencil_func_separate: [StencilFuncSeparate];
+            stencil_op: [StencilOp];
+            stencil_op_separate: [StencilOpSeparate];
+            generate_mipmap: [GenerateMipmap];
+            insert_event_marker_ext: [InsertEventMarkerEXT];
+            push_group_marker_ext: [PushGroupMarkerEXT];
+            pop_group_marker_ext: [PopGroupMarkerEXT];
+            debug_message_insert_khr: [DebugMessageInsertKHR];
+            push_debug_group_khr: [PushDebugGroupKHR];
+            pop_debug_group_khr: [PopDebugGroupKHR];
+            fence_sync: [FenceSync];
+            client_wait_sync: [ClientWaitSync];
+            wait_sync: [WaitSync];
+            delete_sync: [DeleteSync];
+            texture_range_apple: [TextureRangeAPPLE];
+            gen_fences_apple: [GenFencesAPPLE];
+            delete_fences_apple: [DeleteFencesAPPLE];
+            set_fence_apple: [SetFenceAPPLE];
+            finish_fence_apple: [FinishFenceAPPLE];
+            test_fence_apple: [TestFenceAPPLE];
+            test_object_apple: [TestObjectAPPLE];
+            finish_object_apple: [FinishObjectAPPLE];
+            blend_barrier_khr: [BlendBarrierKHR];
+            bind_frag_data_location_indexed: [BindFragDataLocationIndexed];
+            get_frag_data_index: [GetFragDataIndex];
+            get_debug_messages: [GetDebugMessageLog, GetIntegerv];
+            set_debug_callback: [DebugMessageCallback, Enable];
+            debug_message_control: [DebugMessageControl];
+            object_label: [ObjectLabel];
+            get_object_label: [GetObjectLabel];
+            object_ptr_label: [ObjectPtrLabel];
+            buffer_storage: [BufferStorage];
+            flush_mapped_buffer_range: [FlushMappedBufferRange];
+            start_tiling_qcom: [];
+            end_tiling_qcom: [];
+        )
+    }
+}
+
+impl Drop for GlFns {
+    fn drop(&mut self) {
+        // Don't leave the driver with a pointer to the dropped callback.
+        if self.debug_output.has_callback() {
+            unsafe {
+                self.ffi_gl_.DebugMessageCallback(None, ptr::null());
+            }
+        }
     }
 }
 
 impl Gl for GlFns {
+    fn capabilities(&self) -> &GlCapabilities {
+        &self.capabilities
+    }
+
     fn get_type(&self) -> GlType {
         GlType::Gl
     }
@@ -110,22 +438,16 @@ impl Gl for GlFns {
         format: GLenum,
         pixel_type: GLenum,
         dst_buffer: &mut [u8],
-    ) {
-        // Assumes that the user properly allocated the size for dst_buffer.
-        let mut row_length = 0;
-        unsafe {
-            self.ffi_gl_.GetIntegerv(ffi::PACK_ROW_LENGTH, &mut row_length as _);
-        }
-        if row_length == 0 {
-            row_length = width;
-        } else {
-            assert!(row_length >= width);
-        }
-        assert_eq!(calculate_length(row_length, height, format, pixel_type), dst_buffer.len());
+    ) -> Result<(), PixelDataError> {
+        // We don't want any alignment padding on pixel rows.
+        let store = PixelStore {
+            alignment: 1,
+            ..self.pixel_store(true, false)
+        };
+        store.check((width, height, 1), format, pixel_type, dst_buffer.len())?;
 
+        self.pixel_store_i(ffi::PACK_ALIGNMENT, 1);
         unsafe {
-            // We don't want any alignment padding on pixel rows.
-            self.ffi_gl_.PixelStorei(ffi::PACK_ALIGNMENT, 1);
             self.ffi_gl_.ReadPixels(
                 x,
                 y,
@@ -136,6 +458,7 @@ impl Gl for GlFns {
                 dst_buffer.as_mut_ptr() as *mut c_void,
             );
         }
+        Ok(())
     }
 
     fn read_pixels(
@@ -146,14 +469,14 @@ impl Gl for GlFns {
         height: GLsizei,
         format: GLenum,
         pixel_type: GLenum,
-    ) -> Vec<u8> {
-        let len = calculate_length(width, height, format, pixel_type);
-        let mut pixels: Vec<u8> = Vec::new();
-        pixels.reserve(len);
-        unsafe {
-            pixels.set_len(len);
-        }
-
+    ) -> Result<Vec<u8>, PixelDataError> {
+        // Size the buffer for the pack state the read will use, which may
+        // skip pixels and rows or read rows longer than `width`.
+        let store = PixelStore {
+            alignment: 1,
+            ..self.pixel_store(true, false)
+        };
+        let mut pixels = vec![0; store.image_size((width, height, 1), format, pixel_type)?];
         self.read_pixels_into_buffer(
             x,
             y,
@@ -162,9 +485,9 @@ impl Gl for GlFns {
             format,
             pixel_type,
             pixels.as_mut_slice(),
-        );
+        )?;
 
-        pixels
+        Ok(pixels)
     }
 
     unsafe fn read_pixels_into_pbo(&self,
@@ -269,6 +592,14 @@ impl Gl for GlFns {
         }
     }
 
+    fn get_query_iv(&self, target: GLenum, pname: GLenum) -> i32 {
+        let mut result = 0;
+        unsafe {
+            self.ffi_gl_.GetQueryiv(target, pname, &mut result);
+        }
+        result
+    }
+
     fn get_query_object_iv(&self, id: GLuint, pname: GLenum) -> i32 {
         let mut result = 0;
         unsafe {
@@ -523,7 +854,6 @@ impl Gl for GlFns {
         }
     }
 
-    // FIXME: Does not verify buffer size -- unsafe!
     fn tex_image_2d(
         &self,
         target: GLenum,
@@ -535,35 +865,28 @@ impl Gl for GlFns {
         format: GLenum,
         ty: GLenum,
         opt_data: Option<&[u8]>,
-    ) {
-        match opt_data {
-            Some(data) => unsafe {
-                self.ffi_gl_.TexImage2D(
-                    target,
-                    level,
-                    internal_format,
-                    width,
-                    height,
-                    border,
-                    format,
-                    ty,
-                    data.as_ptr() as *const GLvoid,
-                );
-            },
-            None => unsafe {
-                self.ffi_gl_.TexImage2D(
-                    target,
-                    level,
-                    internal_format,
-                    width,
-                    height,
-                    border,
-                    format,
-                    ty,
-                    ptr::null(),
-                );
-            },
+    ) -> Result<(), PixelDataError> {
+        let pdata = match opt_data {
+            Some(data) => {
+                self.pixel_store(false, false).check((width, height, 1), format, ty, data.len())?;
+                data.as_ptr() as *const GLvoid
+            }
+            None => ptr::null(),
+        };
+        unsafe {
+            self.ffi_gl_.TexImage2D(
+                target,
+                level,
+                internal_format,
+                width,
+                height,
+                border,
+                format,
+                ty,
+                pdata,
+            );
         }
+        Ok(())
     }
 
     fn compressed_tex_image_2d(
@@ -616,7 +939,6 @@ impl Gl for GlFns {
         }
     }
 
-    // FIXME: Does not verify buffer size -- unsafe!
     fn tex_image_3d(
         &self,
         target: GLenum,
@@ -629,12 +951,15 @@ impl Gl for GlFns {
         format: GLenum,
         ty: GLenum,
         opt_data: Option<&[u8]>,
-    ) {
+    ) -> Result<(), PixelDataError> {
+        let pdata = match opt_data {
+            Some(data) => {
+                self.pixel_store(false, true).check((width, height, depth), format, ty, data.len())?;
+                data.as_ptr() as *const GLvoid
+            }
+            None => ptr::null(),
+        };
         unsafe {
-            let pdata = match opt_data {
-                Some(data) => mem::transmute(data.as_ptr()),
-                None => ptr::null(),
-            };
             self.ffi_gl_.TexImage3D(
                 target,
                 level,
@@ -648,6 +973,7 @@ impl Gl for GlFns {
                 pdata,
             );
         }
+        Ok(())
     }
 
     fn copy_tex_image_2d(
@@ -722,7 +1048,8 @@ impl Gl for GlFns {
         format: GLenum,
         ty: GLenum,
         data: &[u8],
-    ) {
+    ) -> Result<(), PixelDataError> {
+        self.pixel_store(false, false).check((width, height, 1), format, ty, data.len())?;
         unsafe {
             self.ffi_gl_.TexSubImage2D(
                 target,
@@ -736,6 +1063,7 @@ impl Gl for GlFns {
                 data.as_ptr() as *const c_void,
             );
         }
+        Ok(())
     }
 
     fn tex_sub_image_2d_pbo(
@@ -778,7 +1106,8 @@ impl Gl for GlFns {
         format: GLenum,
         ty: GLenum,
         data: &[u8],
-    ) {
+    ) -> Result<(), PixelDataError> {
+        self.pixel_store(false, true).check((width, height, depth), format, ty, data.len())?;
         unsafe {
             self.ffi_gl_.TexSubImage3D(
                 target,
@@ -794,6 +1123,7 @@ impl Gl for GlFns {
                 data.as_ptr() as *const c_void,
             );
         }
+        Ok(())
     }
 
     fn tex_sub_image_3d_pbo(
@@ -867,11 +1197,25 @@ impl Gl for GlFns {
         format: GLenum,
         ty: GLenum,
         output: &mut [u8],
-    ) {
+    ) -> Result<(), PixelDataError> {
+        let size = |name| {
+            let mut value = 0;
+            unsafe {
+                self.ffi_gl_.GetTexLevelParameteriv(target, level, name, &mut value);
+            }
+            value
+        };
+        let size = (
+            size(ffi::TEXTURE_WIDTH),
+            size(ffi::TEXTURE_HEIGHT),
+            size(ffi::TEXTURE_DEPTH),
+        );
+        self.pixel_store(true, true).check(size, format, ty, output.len())?;
         unsafe {
             self.ffi_gl_
                 .GetTexImage(target, level, format, ty, output.as_mut_ptr() as *mut _);
         }
+        Ok(())
     }
 
     unsafe fn copy_image_sub_data(
@@ -1642,6 +1986,94 @@ impl Gl for GlFns {
         String::from_utf8(name).unwrap()
     }
 
+    fn get_program_interface_i(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        if !self.ffi_gl_.GetProgramInterfaceiv.is_loaded() {
+            return 0;
+        }
+        let mut result = 0;
+        unsafe {
+            self.ffi_gl_
+                .GetProgramInterfaceiv(program, program_interface, pname, &mut result);
+        }
+        result
+    }
+
+    fn get_program_resource_name(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+    ) -> String {
+        if !self.ffi_gl_.GetProgramResourceName.is_loaded() {
+            return String::new();
+        }
+        let buf_size = self
+            .get_program_resource_iv(program, program_interface, index, &[ffi::NAME_LENGTH])
+            .first()
+            .cloned()
+            .unwrap_or(0);
+        let mut name = vec![0; buf_size.max(0) as usize];
+        let mut length: GLsizei = 0;
+        unsafe {
+            self.ffi_gl_.GetProgramResourceName(
+                program,
+                program_interface,
+                index,
+                buf_size,
+                &mut length,
+                name.as_mut_ptr() as *mut GLchar,
+            );
+        }
+        name.truncate(if length > 0 { length as usize } else { 0 });
+
+        String::from_utf8(name).unwrap()
+    }
+
+    fn get_program_resource_iv(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+        props: &[GLenum],
+    ) -> Vec<GLint> {
+        if !self.ffi_gl_.GetProgramResourceiv.is_loaded() {
+            return Vec::new();
+        }
+        let active_variables = if props.contains(&ffi::ACTIVE_VARIABLES) {
+            self.get_program_resource_iv(
+                program,
+                program_interface,
+                index,
+                &[ffi::NUM_ACTIVE_VARIABLES],
+            )
+            .first()
+            .map_or(0, |&count| count.max(0) as usize)
+        } else {
+            0
+        };
+        let mut result = vec![0; props.len() + active_variables];
+        let mut length: GLsizei = 0;
+        unsafe {
+            self.ffi_gl_.GetProgramResourceiv(
+                program,
+                program_interface,
+                index,
+                props.len() as GLsizei,
+                props.as_ptr(),
+                result.len() as GLsizei,
+                &mut length,
+                result.as_mut_ptr(),
+            );
+        }
+        result.truncate(length.max(0) as usize);
+        result
+    }
+
     fn get_attrib_location(&self, program: GLuint, name: &str) -> c_int {
         let name = CString::new(name).unwrap();
         unsafe { self.ffi_gl_.GetAttribLocation(program, name.as_ptr()) }
@@ -1833,26 +2265,45 @@ impl Gl for GlFns {
 
     fn get_shader_precision_format(
         &self,
-        _shader_type: GLuint,
+        shader_type: GLuint,
         precision_type: GLuint,
     ) -> (GLint, GLint, GLint) {
-        // gl.GetShaderPrecisionFormat is not available until OpenGL 4.1.
+        // gl.GetShaderPrecisionFormat is not available until OpenGL 4.1 or
+        // GL_ARB_ES2_compatibility.
+        let native = self.ffi_gl_.GetShaderPrecisionFormat.is_loaded()
+            && (self.capabilities.is_version_at_least(4, 1)
+                || self.capabilities.has_extension("GL_ARB_ES2_compatibility"));
         // Fallback to OpenGL standard precissions that most desktop hardware support.
-        match precision_type {
+        let (mut range, mut precision) = match precision_type {
             ffi::LOW_FLOAT | ffi::MEDIUM_FLOAT | ffi::HIGH_FLOAT => {
                 // Fallback to IEEE 754 single precision
                 // Range: from -2^127 to 2^127
                 // Significand precision: 23 bits
-                (127, 127, 23)
+                ([127, 127], 23)
             }
             ffi::LOW_INT | ffi::MEDIUM_INT | ffi::HIGH_INT => {
                 // Fallback to single precision integer
                 // Range: from -2^24 to 2^24
                 // Precision: For integer formats this value is always 0
-                (24, 24, 0)
+                ([24, 24], 0)
+            }
+            _ => return (0, 0, 0),
+        };
+        if native {
+            // Some drivers define this as a stub that leaves the results
+            // alone and raises an error, so start from the fallback and
+            // suppress the error.
+            unsafe {
+                self.ffi_gl_.GetShaderPrecisionFormat(
+                    shader_type,
+                    precision_type,
+                    range.as_mut_ptr(),
+                    &mut precision,
+                );
+                let _ = self.ffi_gl_.GetError();
             }
-            _ => (0, 0, 0),
         }
+        (range[0], range[1], precision)
     }
 
     fn compile_shader(&self, shader: GLuint) {
@@ -2012,6 +2463,7 @@ impl Gl for GlFns {
 
     fn push_debug_group_khr(&self, source: GLenum, id: GLuint, message: &str) {
         if self.ffi_gl_.PushDebugGroupKHR.is_loaded() {
+            self.debug_output.push_group(message);
             unsafe {
                 self.ffi_gl_
                     .PushDebugGroupKHR(source, id, message.len() as GLsizei, message.as_ptr() as *const _);
@@ -2024,6 +2476,7 @@ impl Gl for GlFns {
             unsafe {
                 self.ffi_gl_.PopDebugGroupKHR();
             }
+            self.debug_output.pop_group();
         }
     }
 
@@ -2185,13 +2638,14 @@ impl Gl for GlFns {
                 let len = lengths[i] as usize;
                 let slice = &msg_data[offset..offset + len];
                 offset += len;
-                DebugMessage {
-                    message: String::from_utf8_lossy(slice).to_string(),
-                    source: sources[i],
-                    ty: types[i],
-                    id: ids[i],
-                    severity: severities[i],
-                }
+                DebugMessage::from_gl(
+                    String::from_utf8_lossy(slice).to_string(),
+                    sources[i],
+                    types[i],
+                    ids[i],
+                    severities[i],
+                    Vec::new(),
+                )
             }));
 
             if (count as usize) < CAPACITY {
@@ -2200,6 +2654,82 @@ impl Gl for GlFns {
         }
     }
 
+    fn set_debug_callback(&self, callback: Option<DebugCallback>) {
+        if !self.ffi_gl_.DebugMessageCallback.is_loaded() {
+            return;
+        }
+        match self.debug_output.set_callback(callback) {
+            Some(user_param) => unsafe {
+                self.ffi_gl_.Enable(ffi::DEBUG_OUTPUT_SYNCHRONOUS);
+                self.ffi_gl_
+                    .DebugMessageCallback(Some(debug_message_callback), user_param);
+            },
+            None => unsafe {
+                self.ffi_gl_.DebugMessageCallback(None, ptr::null());
+            },
+        }
+    }
+
+    fn debug_message_control(
+        &self,
+        source: GLenum,
+        ty: GLenum,
+        severity: GLenum,
+        ids: &[GLuint],
+        enabled: bool,
+    ) {
+        if !self.ffi_gl_.DebugMessageControl.is_loaded() {
+            return;
+        }
+        unsafe {
+            self.ffi_gl_.DebugMessageControl(
+                source,
+                ty,
+                severity,
+                ids.len() as GLsizei,
+                ids.as_ptr(),
+                enabled as GLboolean,
+            );
+        }
+    }
+
+    fn object_label(&self, identifier: GLenum, name: GLuint, label: &str) {
+        if !self.ffi_gl_.ObjectLabel.is_loaded() {
+            return;
+        }
+        unsafe {
+            self.ffi_gl_.ObjectLabel(
+                identifier,
+                name,
+                label.len() as GLsizei,
+                label.as_ptr() as *const GLchar,
+            );
+        }
+    }
+
+    fn get_object_label(&self, identifier: GLenum, name: GLuint) -> String {
+        if !self.ffi_gl_.GetObjectLabel.is_loaded() {
+            return String::new();
+        }
+        read_object_label(|buf_size, length, label| unsafe {
+            self.ffi_gl_
+                .GetObjectLabel(identifier, name, buf_size, length, label);
+        })
+    }
+
+    fn object_ptr_label(&self, sync: GLsync, label: &str) {
+        if !self.ffi_gl_.ObjectPtrLabel.is_loaded() {
+            return;
+        }
+        unsafe {
+            self.ffi_gl_.ObjectPtrLabel(
+                sync as *const c_void,
+                label.len() as GLsizei,
+                label.as_ptr() as *const GLchar,
+            );
+        }
+    }
+
     fn provoking_vertex_angle(&self, _mode: GLenum) {
         unimplemented!("This extension is GLES only");
     }
@@ -9,19 +9,446 @@
 
 pub struct GlesFns {
     ffi_gl_: GlesFfi,
+    capabilities: GlCapabilities,
+    debug_output: DebugOutput,
+    fences: SyncFences,
 }
 
+// SAFETY: the loaded function pointers are the only fields that aren't
+// `Send`, and they are valid on any thread the context is current on, which
+// every `Gl` call already requires. Moving the context between threads is
+// left to `SendGl`. It isn't `Sync`, as two threads can't both have the
+// context current.
+unsafe impl Send for GlesFns {}
+
 impl GlesFns {
     pub unsafe fn load_with<'a, F>(loadfn: F) -> Rc<dyn Gl>
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        GlesFns::load_with_report(loadfn).0
+    }
+
+    /// Loads the context like `load_with`, also reporting every entry point
+    /// the loader couldn't find, so that fallbacks can be picked before any
+    /// call reaches a missing one.
+    ///
+    /// # Safety
+    ///
+    /// `loadfn` must return null or a pointer to the named GL function of
+    /// the context that will be current when this context is used.
+    pub unsafe fn load_with_report<F>(loadfn: F) -> (Rc<dyn Gl>, LoadReport)
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        let (fns, report) = GlesFns::load(loadfn);
+        (Rc::new(fns) as Rc<dyn Gl>, report)
+    }
+
+    /// Loads the context like `load_with`, from `loader`.
+    ///
+    /// # Safety
+    ///
+    /// See `load_with_report`.
+    pub unsafe fn load_from<L: GlLoader>(mut loader: L) -> Rc<dyn Gl> {
+        GlesFns::load_with(|symbol| loader.get_proc_address(symbol))
+    }
+
+    unsafe fn load<F>(loadfn: F) -> (GlesFns, LoadReport)
     where
         F: FnMut(&str) -> *const c_void,
     {
         let ffi_gl_ = GlesFfi::load_with(loadfn);
-        Rc::new(GlesFns { ffi_gl_: ffi_gl_ }) as Rc<dyn Gl>
+        let entry_points = GlesFns::entry_points(&ffi_gl_);
+        let mut fns = GlesFns {
+            ffi_gl_,
+            capabilities: GlCapabilities::default(),
+            debug_output: DebugOutput::default(),
+            fences: SyncFences::default(),
+        };
+        fns.capabilities = GlCapabilities::query_entry_points(&fns, &entry_points);
+        (fns, LoadReport::new(&entry_points))
+    }
+
+    /// Reads the `PACK_*` or `UNPACK_*` pixel store state.
+    fn pixel_store(&self, pack: bool, three_d: bool) -> PixelStore {
+        let get = |pack_name, unpack_name| {
+            let mut value = 0;
+            unsafe {
+                self.ffi_gl_
+                    .GetIntegerv(if pack { pack_name } else { unpack_name }, &mut value);
+            }
+            value
+        };
+        // The row length and skip parameters only exist in ES 2.0 with
+        // `GL_NV_pack_subimage` and `GL_EXT_unpack_subimage`.
+        let es3 = self.capabilities.is_version_at_least(3, 0);
+        let pack_subimage = es3 || self.capabilities.has_extension("GL_NV_pack_subimage");
+        let unpack_subimage = es3 || self.capabilities.has_extension("GL_EXT_unpack_subimage");
+        let mut store = PixelStore {
+            alignment: get(ffi::PACK_ALIGNMENT, ffi::UNPACK_ALIGNMENT),
+            ..PixelStore::default()
+        };
+        if (pack && pack_subimage) || (!pack && unpack_subimage) {
+            store.row_length = get(ffi::PACK_ROW_LENGTH, ffi::UNPACK_ROW_LENGTH);
+            store.skip_pixels = get(ffi::PACK_SKIP_PIXELS, ffi::UNPACK_SKIP_PIXELS);
+            store.skip_rows = get(ffi::PACK_SKIP_ROWS, ffi::UNPACK_SKIP_ROWS);
+        }
+        // ES has no 3D pack state, and 3D uploads need ES 3.0.
+        if three_d && !pack {
+            store.image_height = get(0, ffi::UNPACK_IMAGE_HEIGHT);
+            store.skip_images = get(0, ffi::UNPACK_SKIP_IMAGES);
+        }
+        store
+    }
+
+    /// The `Gl` methods this backend implements, with the entry points each
+    /// of them calls.
+    fn entry_points(ffi_gl_: &GlesFfi) -> EntryPoints {
+        entry_points!(ffi_gl_;
+            get_type: [];
+            buffer_data_untyped: [BufferData];
+            buffer_sub_data_untyped: [BufferSubData];
+            map_buffer: [GetBufferParameteriv, MapBufferRange];
+            map_buffer_range: [MapBufferRange];
+            unmap_buffer: [UnmapBuffer];
+            tex_buffer: [TexBufferEXT];
+            shader_source: [ShaderSource];
+            read_buffer: [ReadBuffer];
+            read_pixels_into_buffer: [PixelStorei, ReadPixels, GetIntegerv];
+            read_pixels: [PixelStorei, ReadPixels, GetIntegerv];
+            read_pixels_into_pbo: [ReadPixels];
+            sample_coverage: [SampleCoverage];
+            polygon_offset: [PolygonOffset];
+            pixel_store_i: [PixelStorei];
+            gen_buffers: [GenBuffers];
+            gen_renderbuffers: [GenRenderbuffers];
+            gen_framebuffers: [GenFramebuffers];
+            gen_textures: [GenTextures];
+            gen_vertex_arrays: [GenVertexArrays];
+            gen_vertex_arrays_apple: [GenVertexArrays];
+            gen_queries: [GenQueriesEXT];
+            begin_query: [BeginQueryEXT];
+            end_query: [EndQueryEXT];
+            query_counter: [QueryCounterEXT];
+            get_query_iv: [GetQueryivEXT];
+            get_query_object_iv: [GetQueryObjectivEXT];
+            get_query_object_uiv: [GetQueryObjectuivEXT];
+            get_query_object_i64v: [GetQueryObjecti64vEXT];
+            get_query_object_ui64v: [GetQueryObjectui64vEXT];
+            delete_queries: [DeleteQueriesEXT];
+            delete_vertex_arrays: [DeleteVertexArrays];
+            delete_vertex_arrays_apple: [DeleteVertexArrays];
+            delete_buffers: [DeleteBuffers];
+            delete_renderbuffers: [DeleteRenderbuffers];
+            delete_framebuffers: [DeleteFramebuffers];
+            delete_textures: [DeleteTextures];
+            framebuffer_renderbuffer: [FramebufferRenderbuffer];
+            renderbuffer_storage: [RenderbufferStorage];
+            depth_func: [DepthFunc];
+            active_texture: [ActiveTexture];
+            attach_shader: [AttachShader];
+            bind_attrib_location: [BindAttribLocation];
+            get_uniform_iv: [GetUniformiv];
+            get_uniform_fv: [GetUniformfv];
+            get_uniform_block_index: [GetUniformBlockIndex];
+            get_uniform_indices: [GetUniformIndices];
+            bind_buffer_base: [BindBufferBase];
+            bind_buffer_range: [BindBufferRange];
+            uniform_block_binding: [UniformBlockBinding];
+            bind_buffer: [BindBuffer];
+            bind_vertex_array: [BindVertexArray];
+            bind_vertex_array_apple: [BindVertexArray];
+            bind_renderbuffer: [BindRenderbuffer];
+            bind_framebuffer: [BindFramebuffer];
+            bind_texture: [BindTexture];
+            bind_vertex_buffer: [BindVertexBuffer];
+            draw_buffers: [DrawBuffers];
+            tex_image_2d: [TexImage2D, GetIntegerv];
+            compressed_tex_image_2d: [CompressedTexImage2D, GetIntegerv];
+            compressed_tex_sub_image_2d: [CompressedTexSubImage2D, GetIntegerv];
+            tex_image_3d: [TexImage3D, GetIntegerv];
+            copy_tex_image_2d: [CopyTexImage2D, GetIntegerv];
+            copy_tex_sub_image_2d: [CopyTexSubImage2D, GetIntegerv];
+            copy_tex_sub_image_3d: [CopyTexSubImage3D, GetIntegerv];
+            tex_sub_image_2d: [TexSubImage2D, GetIntegerv];
+            tex_sub_image_2d_pbo: [TexSubImage2D, GetIntegerv];
+            tex_sub_image_3d: [TexSubImage3D, GetIntegerv];
+            tex_sub_image_3d_pbo: [TexSubImage3D, GetIntegerv];
+            tex_storage_2d: [TexStorage2D];
+            tex_storage_3d: [TexStorage3D];
+            copy_image_sub_data: [CopyImageSubDataEXT];
+            invalidate_framebuffer: [InvalidateFramebuffer];
+            invalidate_sub_framebuffer: [InvalidateSubFramebuffer];
+            get_integer_v: [GetIntegerv];
+            get_integer_64v: [GetInteger64v];
+            get_integer_iv: [GetIntegeri_v];
+            get_integer_64iv: [GetInteger64i_v];
+            get_boolean_v: [GetBooleanv];
+            get_float_v: [GetFloatv];
+            get_framebuffer_attachment_parameter_iv: [GetFramebufferAttachmentParameteriv];
+            get_renderbuffer_parameter_iv: [GetRenderbufferParameteriv];
+            get_tex_parameter_iv: [GetTexParameteriv];
+            get_tex_parameter_fv: [GetTexParameterfv];
+            tex_parameter_i: [TexParameteri];
+            tex_parameter_f: [TexParameterf];
+            framebuffer_texture_2d: [FramebufferTexture2D];
+            framebuffer_texture_layer: [FramebufferTextureLayer];
+            blit_framebuffer: [BlitFramebuffer];
+            vertex_attrib_4f: [VertexAttrib4f];
+            vertex_attrib_binding: [VertexAttribBinding];
+            vertex_attrib_pointer_f32: [VertexAttribPointer];
+            vertex_attrib_pointer: [VertexAttribPointer];
+            vertex_attrib_i_pointer: [VertexAttribIPointer];
+            vertex_attrib_divisor: [VertexAttribDivisor];
+            vertex_attrib_format: [VertexAttribFormat];
+            vertex_attrib_i_format: [VertexAttribIFormat];
+            vertex_binding_divisor: [VertexBindingDivisor];
+            viewport: [Viewport];
+            scissor: [Scissor];
+            line_width: [LineWidth];
+            use_program: [UseProgram];
+            validate_program: [ValidateProgram];
+            draw_arrays: [DrawArrays];
+            draw_arrays_instanced: [DrawArraysInstanced];
+            draw_elements: [DrawElements];
+            draw_elements_instanced: [DrawElementsInstanced];
+            blend_color: [BlendColor];
+            blend_func: [BlendFunc];
+            blend_func_separate: [BlendFuncSeparate];
+            blend_equation: [BlendEquation];
+            blend_equation_separate: [BlendEquationSeparate];
+            color_mask: [ColorMask];
+            cull_face: [CullFace];
+            front_face: [FrontFace];
+            enable: [Enable];
+            disable: [Disable];
+            hint: [Hint];
+            is_enabled: [IsEnabled];
+            is_shader: [IsShader];
+            is_texture: [IsTexture];
+            is_framebuffer: [IsFramebuffer];
+            is_renderbuffer: [IsRenderbuffer];
+            check_frame_buffer_status: [CheckFramebufferStatus];
+            enable_vertex_attrib_array: [EnableVertexAttribArray];
+            disable_vertex_attrib_array: [DisableVertexAttribArray];
+            uniform_1f: [Uniform1f];
+            uniform_1fv: [Uniform1fv];
+            uniform_1i: [Uniform1i];
+            uniform_1iv: [Uniform1iv];
+            uniform_1ui: [Uniform1ui];
+            uniform_2f: [Uniform2f];
+            uniform_2fv: [Uniform2fv];
+            uniform_2i: [Uniform2i];
+            uniform_2iv: [Uniform2iv];
+            uniform_2ui: [Uniform2ui];
+            uniform_3f: [Uniform3f];
+            uniform_3fv: [Uniform3fv];
+            uniform_3i: [Uniform3i];
+            uniform_3iv: [Uniform3iv];
+            uniform_3ui: [Uniform3ui];
+            uniform_4f: [Uniform4f];
+            uniform_4i: [Uniform4i];
+            uniform_4iv: [Uniform4iv];
+            uniform_4ui: [Uniform4ui];
+            uniform_4fv: [Uniform4fv];
+            uniform_matrix_2fv: [UniformMatrix2fv];
+            uniform_matrix_3fv: [UniformMatrix3fv];
+            uniform_matrix_4fv: [UniformMatrix4fv];
+            depth_mask: [DepthMask];
+            depth_range: [DepthRangef];
+            get_active_attrib: [GetActiveAttrib, GetProgramiv];
+            get_active_uniform: [GetActiveUniform, GetProgramiv];
+            get_active_uniforms_iv: [GetActiveUniformsiv];
+            get_active_uniform_block_i: [GetActiveUniformBlockiv];
+            get_active_uniform_block_iv: [GetActiveUniformBlockiv];
+            get_active_uniform_block_name: [GetActiveUniformBlockName, GetActiveUniformBlockiv];
+            get_program_interface_i: [GetProgramInterfaceiv];
+            get_program_resource_name: [GetProgramResourceName, GetProgramResourceiv];
+            get_program_resource_iv: [GetProgramResourceiv];
+            get_attrib_location: [GetAttribLocation];
+            get_frag_data_location: [GetFragDataLocation];
+            get_uniform_location: [GetUniformLocation];
+            get_program_info_log: [GetProgramInfoLog, GetProgramiv];
+            get_program_iv: [GetProgramiv];
+            get_program_binary: [GetProgramBinary, GetProgramiv];
+            program_binary: [ProgramBinary];
+            program_parameter_i: [ProgramParameteri];
+            get_vertex_attrib_iv: [GetVertexAttribiv];
+            get_vertex_attrib_fv: [GetVertexAttribfv];
+            get_vertex_attrib_pointer_v: [GetVertexAttribPointerv];
+            get_buffer_parameter_iv: [GetBufferParameteriv];
+            get_shader_info_log: [GetShaderInfoLog, GetShaderiv];
+            get_string: [GetString];
+            get_string_i: [GetStringi];
+            get_shader_iv: [GetShaderiv];
+            get_shader_precision_format: [GetShaderPrecisionFormat, GetError];
+            compile_shader: [CompileShader];
+            create_program: [CreateProgram];
+            delete_program: [DeleteProgram];
+            create_shader: [CreateShader];
+            delete_shader: [DeleteShader];
+            detach_shader: [DetachShader];
+            link_program: [LinkProgram];
+            clear_color: [ClearColor];
+            clear: [Clear];
+            clear_depth: [ClearDepthf];
+            clear_stencil: [ClearStencil];
+            flush: [Flush];
+            finish: [Finish];
+            get_error: [GetError];
+            stencil_mask: [StencilMask];
+            stencil_mask_separate: [StencilMaskSeparate];
+            stencil_func: [StencilFunc];
+            stencil_func_separate: [StencilFuncSeparate];
+            stencil_op: [StencilOp];
+            stencil_op_separate: [StencilOpSeparate];
+            egl_image_target_texture2d_oes: [EGLImageTargetTexture2DOES];
+            egl_image_target_renderbuffer_storage_oes: [EGLImageTargetRenderbufferStorageOES];
+            generate_mipmap: [GenerateMipmap];
+            insert_event_marker_ext: [InsertEventMarkerEXT];
+            push_group_marker_ext: [PushGroupMarkerEXT];
+            pop_group_marker_ext: [PopGroupMarkerEXT];
+            debug_message_insert_khr: [DebugMessageInsertKHR];
+            push_debug_group_khr: [PushDebugGroupKHR];
+            pop_debug_group_khr: [PopDebugGroupKHR];
+            fence_sync: [FenceSync];
+            client_wait_sync: [ClientWaitSync];
+            wait_sync: [WaitSync];
+            delete_sync: [DeleteSync];
+            gen_fences_apple: [];
+            delete_fences_apple: [DeleteSync];
+            set_fence_apple: [FenceSync, DeleteSync];
+            finish_fence_apple: [ClientWaitSync, DeleteSync];
+            test_fence_apple: [ClientWaitSync, DeleteSync];
+            test_object_apple: [FenceSync, ClientWaitSync, DeleteSync];
+            finish_object_apple: [ClientWaitSync, DeleteSync, Finish];
+            bind_frag_data_location_indexed: [BindFragDataLocationIndexedEXT];
+            get_frag_data_index: [GetFragDataIndexEXT];
+            blend_barrier_khr: [BlendBarrierKHR];
+            get_debug_messages: [GetDebugMessageLog, GetIntegerv];
+            set_debug_callback: [DebugMessageCallback, Enable];
+            debug_message_control: [DebugMessageControl];
+            object_label: [ObjectLabel | LabelObjectEXT];
+            get_object_label: [GetObjectLabel | GetObjectLabelEXT];
+            object_ptr_label: [ObjectPtrLabel];
+            provoking_vertex_angle: [ProvokingVertexANGLE];
+            copy_texture_chromium: [CopyTextureCHROMIUM];
+            copy_sub_texture_chromium: [CopySubTextureCHROMIUM];
+            copy_texture_3d_angle: [CopyTexture3DANGLE];
+            copy_sub_texture_3d_angle: [CopySubTexture3DANGLE];
+            buffer_storage: [BufferStorageEXT];
+            flush_mapped_buffer_range: [FlushMappedBufferRange];
+            start_tiling_qcom: [StartTilingQCOM];
+            end_tiling_qcom: [EndTilingQCOM];
+        )
+    }
+}
+
+/// `GL_APPLE_fence` fences emulated with the sync objects of ES 3.0. Each
+/// fence name maps to the sync object of its last `set`, or to null once
+/// that has signaled or if it was never set.
+#[derive(Default)]
+struct SyncFences {
+    fences: Mutex<HashMap<GLuint, GLsync>>,
+}
+
+impl SyncFences {
+    fn gen(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut fences = self.fences.lock().unwrap();
+        let names: Vec<GLuint> = (1..)
+            .filter(|name| !fences.contains_key(name))
+            .take(n.max(0) as usize)
+            .collect();
+        for &name in &names {
+            fences.insert(name, ptr::null());
+        }
+        names
+    }
+
+    fn delete(&self, gl: &dyn Gl, names: &[GLuint]) {
+        let mut fences = self.fences.lock().unwrap();
+        for name in names {
+            match fences.remove(name) {
+                Some(sync) if !sync.is_null() => gl.delete_sync(sync),
+                _ => {}
+            }
+        }
+    }
+
+    fn set(&self, gl: &dyn Gl, name: GLuint) {
+        let sync = gl.fence_sync(SYNC_GPU_COMMANDS_COMPLETE, 0);
+        match self.fences.lock().unwrap().insert(name, sync) {
+            Some(old) if !old.is_null() => gl.delete_sync(old),
+            _ => {}
+        }
+    }
+
+    /// Returns whether the fence has signaled, first waiting for it if
+    /// `wait` is set. A fence that was never set has signaled.
+    fn test(&self, gl: &dyn Gl, name: GLuint, wait: bool) -> bool {
+        let mut fences = self.fences.lock().unwrap();
+        let sync = match fences.get(&name) {
+            Some(&sync) if !sync.is_null() => sync,
+            _ => return true,
+        };
+        if !client_wait(gl, sync, wait) {
+            return false;
+        }
+        gl.delete_sync(sync);
+        fences.insert(name, ptr::null());
+        true
+    }
+
+    /// Like `test` for a fence, and for any other object whether every
+    /// command issued so far has completed, which includes the ones using
+    /// it.
+    fn test_object(&self, gl: &dyn Gl, object: GLenum, name: GLuint, wait: bool) -> bool {
+        if object == FENCE_APPLE {
+            return self.test(gl, name, wait);
+        }
+        if wait {
+            gl.finish();
+            return true;
+        }
+        let sync = gl.fence_sync(SYNC_GPU_COMMANDS_COMPLETE, 0);
+        let signaled = client_wait(gl, sync, false);
+        gl.delete_sync(sync);
+        signaled
+    }
+}
+
+/// Flushes and returns whether `sync` has signaled, waiting for it if `wait`
+/// is set. A failed wait counts as signaled, so that callers polling for it
+/// don't spin forever.
+fn client_wait(gl: &dyn Gl, sync: GLsync, wait: bool) -> bool {
+    // ES doesn't allow `TIMEOUT_IGNORED` here, so wait a second at a time.
+    let timeout = if wait { 1_000_000_000 } else { 0 };
+    loop {
+        match gl.client_wait_sync(sync, SYNC_FLUSH_COMMANDS_BIT, timeout) {
+            TIMEOUT_EXPIRED if wait => continue,
+            TIMEOUT_EXPIRED => return false,
+            _ => return true,
+        }
+    }
+}
+
+impl Drop for GlesFns {
+    fn drop(&mut self) {
+        // Don't leave the driver with a pointer to the dropped callback.
+        if self.debug_output.has_callback() {
+            unsafe {
+                self.ffi_gl_.DebugMessageCallback(None, ptr::null());
+            }
+        }
     }
 }
 
 impl Gl for GlesFns {
+    fn capabilities(&self) -> &GlCapabilities {
+        &self.capabilities
+    }
+
     fn get_type(&self) -> GlType {
         GlType::Gles
     }
@@ -38,8 +465,10 @@ impl Gl for GlesFns {
         }
     }
 
-    fn tex_buffer(&self, _target: GLenum, _internal_format: GLenum, _buffer: GLuint) {
-        panic!("not supported")
+    fn tex_buffer(&self, target: GLenum, internal_format: GLenum, buffer: GLuint) {
+        unsafe {
+            self.ffi_gl_.TexBufferEXT(target, internal_format, buffer);
+        }
     }
 
     fn buffer_sub_data_untyped(
@@ -54,10 +483,20 @@ impl Gl for GlesFns {
         }
     }
 
-    fn map_buffer(&self,
-                  _target: GLenum,
-                  _access: GLbitfield) -> *mut c_void {
-        panic!("not supported")
+    fn map_buffer(&self, target: GLenum, access: GLbitfield) -> *mut c_void {
+        // ES only has `MapBufferRange`, which needs the size of the buffer.
+        let access = match access {
+            ffi::READ_ONLY => ffi::MAP_READ_BIT,
+            ffi::WRITE_ONLY => ffi::MAP_WRITE_BIT,
+            _ => ffi::MAP_READ_BIT | ffi::MAP_WRITE_BIT,
+        };
+        let mut size = 0;
+        unsafe {
+            self.ffi_gl_
+                .GetBufferParameteriv(target, ffi::BUFFER_SIZE, &mut size);
+            self.ffi_gl_
+                .MapBufferRange(target, 0, size as GLsizeiptr, access)
+        }
     }
 
     fn map_buffer_range(&self,
@@ -91,9 +530,10 @@ impl Gl for GlesFns {
         drop(pointers);
     }
 
-    #[allow(unused_variables)]
     fn read_buffer(&self, mode: GLenum) {
-        panic!("not supported")
+        unsafe {
+            self.ffi_gl_.ReadBuffer(mode);
+        }
     }
 
     fn read_pixels_into_buffer(
@@ -105,22 +545,16 @@ impl Gl for GlesFns {
         format: GLenum,
         pixel_type: GLenum,
         dst_buffer: &mut [u8],
-    ) {
-        // Assumes that the user properly allocated the size for dst_buffer.
-        let mut row_length = 0;
-        unsafe {
-            self.ffi_gl_.GetIntegerv(ffi::PACK_ROW_LENGTH, &mut row_length as _);
-        }
-        if row_length == 0 {
-            row_length = width;
-        } else {
-            assert!(row_length >= width);
-        }
-        assert_eq!(calculate_length(row_length, height, format, pixel_type), dst_buffer.len());
+    ) -> Result<(), PixelDataError> {
+        // We don't want any alignment padding on pixel rows.
+        let store = PixelStore {
+            alignment: 1,
+            ..self.pixel_store(true, false)
+        };
+        store.check((width, height, 1), format, pixel_type, dst_buffer.len())?;
 
+        self.pixel_store_i(ffi::PACK_ALIGNMENT, 1);
         unsafe {
-            // We don't want any alignment padding on pixel rows.
-            self.ffi_gl_.PixelStorei(ffi::PACK_ALIGNMENT, 1);
             self.ffi_gl_.ReadPixels(
                 x,
                 y,
@@ -131,6 +565,7 @@ impl Gl for GlesFns {
                 dst_buffer.as_mut_ptr() as *mut c_void,
             );
         }
+        Ok(())
     }
 
     fn read_pixels(
@@ -141,14 +576,14 @@ impl Gl for GlesFns {
         height: GLsizei,
         format: GLenum,
         pixel_type: GLenum,
-    ) -> Vec<u8> {
-        let len = calculate_length(width, height, format, pixel_type);
-        let mut pixels: Vec<u8> = Vec::new();
-        pixels.reserve(len);
-        unsafe {
-            pixels.set_len(len);
-        }
-
+    ) -> Result<Vec<u8>, PixelDataError> {
+        // Size the buffer for the pack state the read will use, which may
+        // skip pixels and rows or read rows longer than `width`.
+        let store = PixelStore {
+            alignment: 1,
+            ..self.pixel_store(true, false)
+        };
+        let mut pixels = vec![0; store.image_size((width, height, 1), format, pixel_type)?];
         self.read_pixels_into_buffer(
             x,
             y,
@@ -157,9 +592,9 @@ impl Gl for GlesFns {
             format,
             pixel_type,
             pixels.as_mut_slice(),
-        );
+        )?;
 
-        pixels
+        Ok(pixels)
     }
 
     unsafe fn read_pixels_into_pbo(&self,
@@ -230,8 +665,10 @@ impl Gl for GlesFns {
         result
     }
 
-    fn gen_vertex_arrays_apple(&self, _n: GLsizei) -> Vec<GLuint> {
-        panic!("not supported")
+    // GL_APPLE_vertex_array_object is core in ES 3.0, and falls back to
+    // GL_OES_vertex_array_object in ES 2.0.
+    fn gen_vertex_arrays_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        self.gen_vertex_arrays(n)
     }
 
     fn gen_queries(&self, n: GLsizei) -> Vec<GLuint> {
@@ -272,6 +709,17 @@ impl Gl for GlesFns {
         }
     }
 
+    fn get_query_iv(&self, target: GLenum, pname: GLenum) -> i32 {
+        if !self.ffi_gl_.GetQueryivEXT.is_loaded() {
+            return 0;
+        }
+        let mut result = 0;
+        unsafe {
+            self.ffi_gl_.GetQueryivEXT(target, pname, &mut result);
+        }
+        result
+    }
+
     fn get_query_object_iv(&self, id: GLuint, pname: GLenum) -> i32 {
         if !self.ffi_gl_.GetQueryObjectivEXT.is_loaded() {
             return 0;
@@ -333,8 +781,8 @@ impl Gl for GlesFns {
         }
     }
 
-    fn delete_vertex_arrays_apple(&self, _vertex_arrays: &[GLuint]) {
-        panic!("not supported")
+    fn delete_vertex_arrays_apple(&self, vertex_arrays: &[GLuint]) {
+        self.delete_vertex_arrays(vertex_arrays)
     }
 
     fn delete_buffers(&self, buffers: &[GLuint]) {
@@ -503,8 +951,8 @@ impl Gl for GlesFns {
         }
     }
 
-    fn bind_vertex_array_apple(&self, _vao: GLuint) {
-        panic!("not supported")
+    fn bind_vertex_array_apple(&self, vao: GLuint) {
+        self.bind_vertex_array(vao)
     }
 
     fn bind_renderbuffer(&self, target: GLenum, renderbuffer: GLuint) {
@@ -536,7 +984,6 @@ impl Gl for GlesFns {
         }
     }
 
-    // FIXME: Does not verify buffer size -- unsafe!
     fn tex_image_2d(
         &self,
         target: GLenum,
@@ -548,35 +995,28 @@ impl Gl for GlesFns {
         format: GLenum,
         ty: GLenum,
         opt_data: Option<&[u8]>,
-    ) {
-        match opt_data {
-            Some(data) => unsafe {
-                self.ffi_gl_.TexImage2D(
-                    target,
-                    level,
-                    internal_format,
-                    width,
-                    height,
-                    border,
-                    format,
-                    ty,
-                    data.as_ptr() as *const GLvoid,
-                );
-            },
-            None => unsafe {
-                self.ffi_gl_.TexImage2D(
-                    target,
-                    level,
-                    internal_format,
-                    width,
-                    height,
-                    border,
-                    format,
-                    ty,
-                    ptr::null(),
-                );
-            },
+    ) -> Result<(), PixelDataError> {
+        let pdata = match opt_data {
+            Some(data) => {
+                self.pixel_store(false, false).check((width, height, 1), format, ty, data.len())?;
+                data.as_ptr() as *const GLvoid
+            }
+            None => ptr::null(),
+        };
+        unsafe {
+            self.ffi_gl_.TexImage2D(
+                target,
+                level,
+                internal_format,
+                width,
+                height,
+                border,
+                format,
+                ty,
+                pdata,
+            );
         }
+        Ok(())
     }
 
     fn compressed_tex_image_2d(
@@ -629,7 +1069,6 @@ impl Gl for GlesFns {
         }
     }
 
-    // FIXME: Does not verify buffer size -- unsafe!
     fn tex_image_3d(
         &self,
         target: GLenum,
@@ -642,12 +1081,15 @@ impl Gl for GlesFns {
         format: GLenum,
         ty: GLenum,
         opt_data: Option<&[u8]>,
-    ) {
+    ) -> Result<(), PixelDataError> {
+        let pdata = match opt_data {
+            Some(data) => {
+                self.pixel_store(false, true).check((width, height, depth), format, ty, data.len())?;
+                data.as_ptr() as *const GLvoid
+            }
+            None => ptr::null(),
+        };
         unsafe {
-            let pdata = match opt_data {
-                Some(data) => mem::transmute(data.as_ptr()),
-                None => ptr::null(),
-            };
             self.ffi_gl_.TexImage3D(
                 target,
                 level,
@@ -661,6 +1103,7 @@ impl Gl for GlesFns {
                 pdata,
             );
         }
+        Ok(())
     }
 
     fn copy_tex_image_2d(
@@ -735,7 +1178,8 @@ impl Gl for GlesFns {
         format: GLenum,
         ty: GLenum,
         data: &[u8],
-    ) {
+    ) -> Result<(), PixelDataError> {
+        self.pixel_store(false, false).check((width, height, 1), format, ty, data.len())?;
         unsafe {
             self.ffi_gl_.TexSubImage2D(
                 target,
@@ -749,6 +1193,7 @@ impl Gl for GlesFns {
                 data.as_ptr() as *const c_void,
             );
         }
+        Ok(())
     }
 
     fn tex_sub_image_2d_pbo(
@@ -791,7 +1236,8 @@ impl Gl for GlesFns {
         format: GLenum,
         ty: GLenum,
         data: &[u8],
-    ) {
+    ) -> Result<(), PixelDataError> {
+        self.pixel_store(false, true).check((width, height, depth), format, ty, data.len())?;
         unsafe {
             self.ffi_gl_.TexSubImage3D(
                 target,
@@ -807,6 +1253,7 @@ impl Gl for GlesFns {
                 data.as_ptr() as *const c_void,
             );
         }
+        Ok(())
     }
 
     fn tex_sub_image_3d_pbo(
@@ -877,7 +1324,7 @@ impl Gl for GlesFns {
         format: GLenum,
         ty: GLenum,
         output: &mut [u8],
-    ) {
+    ) -> Result<(), PixelDataError> {
         panic!("not supported");
     }
 
@@ -1382,9 +1829,10 @@ impl Gl for GlesFns {
         }
     }
 
-    #[allow(unused_variables)]
     fn uniform_1ui(&self, location: GLint, v0: GLuint) {
-        panic!("not supported")
+        unsafe {
+            self.ffi_gl_.Uniform1ui(location, v0);
+        }
     }
 
     fn uniform_2f(&self, location: GLint, v0: GLfloat, v1: GLfloat) {
@@ -1413,9 +1861,10 @@ impl Gl for GlesFns {
         }
     }
 
-    #[allow(unused_variables)]
     fn uniform_2ui(&self, location: GLint, v0: GLuint, v1: GLuint) {
-        panic!("not supported")
+        unsafe {
+            self.ffi_gl_.Uniform2ui(location, v0, v1);
+        }
     }
 
     fn uniform_3f(&self, location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
@@ -1444,9 +1893,10 @@ impl Gl for GlesFns {
         }
     }
 
-    #[allow(unused_variables)]
     fn uniform_3ui(&self, location: GLint, v0: GLuint, v1: GLuint, v2: GLuint) {
-        panic!("not supported")
+        unsafe {
+            self.ffi_gl_.Uniform3ui(location, v0, v1, v2);
+        }
     }
 
     fn uniform_4f(&self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
@@ -1468,9 +1918,10 @@ impl Gl for GlesFns {
         }
     }
 
-    #[allow(unused_variables)]
     fn uniform_4ui(&self, location: GLint, x: GLuint, y: GLuint, z: GLuint, w: GLuint) {
-        panic!("not supported")
+        unsafe {
+            self.ffi_gl_.Uniform4ui(location, x, y, z, w);
+        }
     }
 
     fn uniform_4fv(&self, location: GLint, values: &[f32]) {
@@ -1641,14 +2092,102 @@ impl Gl for GlesFns {
         String::from_utf8(name).unwrap()
     }
 
+    fn get_program_interface_i(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        if !self.ffi_gl_.GetProgramInterfaceiv.is_loaded() {
+            return 0;
+        }
+        let mut result = 0;
+        unsafe {
+            self.ffi_gl_
+                .GetProgramInterfaceiv(program, program_interface, pname, &mut result);
+        }
+        result
+    }
+
+    fn get_program_resource_name(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+    ) -> String {
+        if !self.ffi_gl_.GetProgramResourceName.is_loaded() {
+            return String::new();
+        }
+        let buf_size = self
+            .get_program_resource_iv(program, program_interface, index, &[ffi::NAME_LENGTH])
+            .first()
+            .cloned()
+            .unwrap_or(0);
+        let mut name = vec![0; buf_size.max(0) as usize];
+        let mut length: GLsizei = 0;
+        unsafe {
+            self.ffi_gl_.GetProgramResourceName(
+                program,
+                program_interface,
+                index,
+                buf_size,
+                &mut length,
+                name.as_mut_ptr() as *mut GLchar,
+            );
+        }
+        name.truncate(if length > 0 { length as usize } else { 0 });
+
+        String::from_utf8(name).unwrap()
+    }
+
+    fn get_program_resource_iv(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+        props: &[GLenum],
+    ) -> Vec<GLint> {
+        if !self.ffi_gl_.GetProgramResourceiv.is_loaded() {
+            return Vec::new();
+        }
+        let active_variables = if props.contains(&ffi::ACTIVE_VARIABLES) {
+            self.get_program_resource_iv(
+                program,
+                program_interface,
+                index,
+                &[ffi::NUM_ACTIVE_VARIABLES],
+            )
+            .first()
+            .map_or(0, |&count| count.max(0) as usize)
+        } else {
+            0
+        };
+        let mut result = vec![0; props.len() + active_variables];
+        let mut length: GLsizei = 0;
+        unsafe {
+            self.ffi_gl_.GetProgramResourceiv(
+                program,
+                program_interface,
+                index,
+                props.len() as GLsizei,
+                props.as_ptr(),
+                result.len() as GLsizei,
+                &mut length,
+                result.as_mut_ptr(),
+            );
+        }
+        result.truncate(length.max(0) as usize);
+        result
+    }
+
     fn get_attrib_location(&self, program: GLuint, name: &str) -> c_int {
         let name = CString::new(name).unwrap();
         unsafe { self.ffi_gl_.GetAttribLocation(program, name.as_ptr()) }
     }
 
-    #[allow(unused_variables)]
     fn get_frag_data_location(&self, program: GLuint, name: &str) -> c_int {
-        panic!("not supported")
+        let name = CString::new(name).unwrap();
+        unsafe { self.ffi_gl_.GetFragDataLocation(program, name.as_ptr()) }
     }
 
     fn get_uniform_location(&self, program: GLuint, name: &str) -> c_int {
@@ -2010,6 +2549,7 @@ impl Gl for GlesFns {
 
     fn push_debug_group_khr(&self, source: GLenum, id: GLuint, message: &str) {
         if self.ffi_gl_.PushDebugGroupKHR.is_loaded() {
+            self.debug_output.push_group(message);
             unsafe {
                 self.ffi_gl_
                     .PushDebugGroupKHR(source, id, message.len() as GLsizei, message.as_ptr() as *const _);
@@ -2022,6 +2562,7 @@ impl Gl for GlesFns {
             unsafe {
                 self.ffi_gl_.PopDebugGroupKHR();
             }
+            self.debug_output.pop_group();
         }
     }
 
@@ -2052,49 +2593,67 @@ impl Gl for GlesFns {
         panic!("not supported")
     }
 
-    fn gen_fences_apple(&self, _n: GLsizei) -> Vec<GLuint> {
-        panic!("not supported")
+    // GL_APPLE_fence, emulated with sync objects
+    fn gen_fences_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        self.fences.gen(n)
     }
 
-    fn delete_fences_apple(&self, _fences: &[GLuint]) {
-        panic!("not supported")
+    fn delete_fences_apple(&self, fences: &[GLuint]) {
+        self.fences.delete(self, fences)
     }
 
-    fn set_fence_apple(&self, _fence: GLuint) {
-        panic!("not supported")
+    fn set_fence_apple(&self, fence: GLuint) {
+        self.fences.set(self, fence)
     }
 
-    fn finish_fence_apple(&self, _fence: GLuint) {
-        panic!("not supported")
+    fn finish_fence_apple(&self, fence: GLuint) {
+        self.fences.test(self, fence, true);
     }
 
-    fn test_fence_apple(&self, _fence: GLuint) {
-        panic!("not supported")
+    fn test_fence_apple(&self, fence: GLuint) {
+        self.fences.test(self, fence, false);
     }
 
-    fn test_object_apple(&self, _object: GLenum, _name: GLuint) -> GLboolean {
-        panic!("not supported")
+    fn test_object_apple(&self, object: GLenum, name: GLuint) -> GLboolean {
+        self.fences.test_object(self, object, name, false) as GLboolean
     }
 
-    fn finish_object_apple(&self, _object: GLenum, _name: GLuint) {
-        panic!("not supported")
+    fn finish_object_apple(&self, object: GLenum, name: GLuint) {
+        self.fences.test_object(self, object, name, true);
     }
 
-
-
-    // GL_ARB_blend_func_extended
+    // GL_EXT_blend_func_extended
     fn bind_frag_data_location_indexed(
         &self,
-        _program: GLuint,
-        _color_number: GLuint,
-        _index: GLuint,
-        _name: &str,
+        program: GLuint,
+        color_number: GLuint,
+        index: GLuint,
+        name: &str,
     ) {
-        panic!("not supported");
+        if !self.ffi_gl_.BindFragDataLocationIndexedEXT.is_loaded() {
+            return;
+        }
+
+        let c_string = CString::new(name).unwrap();
+
+        unsafe {
+            self.ffi_gl_.BindFragDataLocationIndexedEXT(
+                program,
+                color_number,
+                index,
+                c_string.as_ptr(),
+            )
+        }
     }
 
-    fn get_frag_data_index(&self, _program: GLuint, _name: &str) -> GLint {
-        panic!("not supported");
+    fn get_frag_data_index(&self, program: GLuint, name: &str) -> GLint {
+        if !self.ffi_gl_.GetFragDataIndexEXT.is_loaded() {
+            return -1;
+        }
+
+        let c_string = CString::new(name).unwrap();
+
+        unsafe { self.ffi_gl_.GetFragDataIndexEXT(program, c_string.as_ptr()) }
     }
 
     // GL_KHR_debug
@@ -2142,13 +2701,14 @@ impl Gl for GlesFns {
                 let len = lengths[i] as usize;
                 let slice = &msg_data[offset..offset + len];
                 offset += len;
-                DebugMessage {
-                    message: String::from_utf8_lossy(slice).to_string(),
-                    source: sources[i],
-                    ty: types[i],
-                    id: ids[i],
-                    severity: severities[i],
-                }
+                DebugMessage::from_gl(
+                    String::from_utf8_lossy(slice).to_string(),
+                    sources[i],
+                    types[i],
+                    ids[i],
+                    severities[i],
+                    Vec::new(),
+                )
             }));
 
             if (count as usize) < CAPACITY {
@@ -2157,6 +2717,96 @@ impl Gl for GlesFns {
         }
     }
 
+    fn set_debug_callback(&self, callback: Option<DebugCallback>) {
+        if !self.ffi_gl_.DebugMessageCallback.is_loaded() {
+            return;
+        }
+        match self.debug_output.set_callback(callback) {
+            Some(user_param) => unsafe {
+                self.ffi_gl_.Enable(ffi::DEBUG_OUTPUT_SYNCHRONOUS);
+                self.ffi_gl_
+                    .DebugMessageCallback(Some(debug_message_callback), user_param);
+            },
+            None => unsafe {
+                self.ffi_gl_.DebugMessageCallback(None, ptr::null());
+            },
+        }
+    }
+
+    fn debug_message_control(
+        &self,
+        source: GLenum,
+        ty: GLenum,
+        severity: GLenum,
+        ids: &[GLuint],
+        enabled: bool,
+    ) {
+        if !self.ffi_gl_.DebugMessageControl.is_loaded() {
+            return;
+        }
+        unsafe {
+            self.ffi_gl_.DebugMessageControl(
+                source,
+                ty,
+                severity,
+                ids.len() as GLsizei,
+                ids.as_ptr(),
+                enabled as GLboolean,
+            );
+        }
+    }
+
+    fn object_label(&self, identifier: GLenum, name: GLuint, label: &str) {
+        unsafe {
+            if self.ffi_gl_.ObjectLabel.is_loaded() {
+                self.ffi_gl_.ObjectLabel(
+                    identifier,
+                    name,
+                    label.len() as GLsizei,
+                    label.as_ptr() as *const GLchar,
+                );
+            } else if self.ffi_gl_.LabelObjectEXT.is_loaded() {
+                self.ffi_gl_.LabelObjectEXT(
+                    ext_debug_label_type(identifier),
+                    name,
+                    label.len() as GLsizei,
+                    label.as_ptr() as *const GLchar,
+                );
+            }
+        }
+    }
+
+    fn get_object_label(&self, identifier: GLenum, name: GLuint) -> String {
+        if self.ffi_gl_.GetObjectLabel.is_loaded() {
+            read_object_label(|buf_size, length, label| unsafe {
+                self.ffi_gl_
+                    .GetObjectLabel(identifier, name, buf_size, length, label);
+            })
+        } else if self.ffi_gl_.GetObjectLabelEXT.is_loaded() {
+            let ty = ext_debug_label_type(identifier);
+            read_object_label(|buf_size, length, label| unsafe {
+                self.ffi_gl_
+                    .GetObjectLabelEXT(ty, name, buf_size, length, label);
+            })
+        } else {
+            String::new()
+        }
+    }
+
+    // GL_EXT_debug_label has no way to label syncs.
+    fn object_ptr_label(&self, sync: GLsync, label: &str) {
+        if !self.ffi_gl_.ObjectPtrLabel.is_loaded() {
+            return;
+        }
+        unsafe {
+            self.ffi_gl_.ObjectPtrLabel(
+                sync as *const c_void,
+                label.len() as GLsizei,
+                label.as_ptr() as *const GLchar,
+            );
+        }
+    }
+
     fn provoking_vertex_angle(&self, mode: GLenum) {
         unsafe {
             self.ffi_gl_.ProvokingVertexANGLE(mode);
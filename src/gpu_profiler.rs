@@ -0,0 +1,276 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// How many ended frames `GpuProfiler` waits on the results of before it
+/// gives up on the oldest one.
+const GPU_PROFILER_MAX_PENDING_FRAMES: usize = 4;
+
+/// How `GpuProfiler` times scopes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum GpuTimerMode {
+    /// A `TIMESTAMP` counter at each end of every scope, so that scopes can
+    /// nest.
+    Timestamp,
+    /// A `TIME_ELAPSED` query around each outermost scope, for contexts that
+    /// can't write timestamps.
+    TimeElapsed,
+}
+
+/// A scope of a frame and the queries timing it. Both queries are 0 for a
+/// scope that isn't timed, and `end` is always 0 in `TimeElapsed` mode.
+struct GpuTimerScope {
+    name: String,
+    start: GLuint,
+    end: GLuint,
+}
+
+impl GpuTimerScope {
+    fn queries(&self) -> impl Iterator<Item = GLuint> {
+        vec![self.start, self.end].into_iter().filter(|&q| q != 0)
+    }
+}
+
+/// Times named scopes on the GPU with query objects, and passes the name and
+/// GPU duration of each scope to a callback once its results are available.
+///
+/// Scopes are timed with `query_counter(TIMESTAMP)` when the context has
+/// timestamps, and may then nest. Otherwise they are timed with
+/// `begin_query(TIME_ELAPSED)`, which only times the outermost scopes. On
+/// desktop GL timestamps need GL 3.3 or `GL_ARB_timer_query`, and
+/// `GL_EXT_timer_query` also allows `TIME_ELAPSED`. On GLES both come from
+/// `GL_EXT_disjoint_timer_query`, timestamps are only used if its
+/// `QUERY_COUNTER_BITS` isn't zero, and the results of every frame still
+/// pending when `GPU_DISJOINT_EXT` is set are dropped. The profiler does
+/// nothing on contexts that have neither.
+///
+/// Results are collected by `end_frame` a few frames later, without waiting
+/// on the GPU. When more than a few frames are still pending, the oldest one
+/// is dropped.
+///
+/// ```no_run
+/// # use std::rc::Rc;
+/// # fn render(gl: Rc<dyn gleam::gl::Gl>) {
+/// let mut profiler = gleam::gl::GpuProfiler::new(gl.clone(), |name: &str, duration| {
+///     println!("{}: {:?}", name, duration);
+/// });
+/// loop {
+///     profiler.begin_scope("opaque pass");
+///     gl.draw_arrays(gleam::gl::TRIANGLES, 0, 3);
+///     profiler.end_scope();
+///     profiler.end_frame();
+/// }
+/// # }
+/// ```
+pub struct GpuProfiler<F, G: SharedGl = Rc<dyn Gl>> {
+    gl: G,
+    mode: Option<GpuTimerMode>,
+    disjoint: bool,
+    callback: F,
+    free_queries: Vec<GLuint>,
+    frame: Vec<GpuTimerScope>,
+    /// The scopes of `frame` that haven't ended yet, innermost last.
+    open_scopes: Vec<usize>,
+    pending_frames: VecDeque<Vec<GpuTimerScope>>,
+}
+
+impl<F: FnMut(&str, Duration), G: SharedGl> GpuProfiler<F, G> {
+    pub fn new(gl: G, callback: F) -> GpuProfiler<F, G> {
+        let (mode, disjoint) = {
+            let context = gl.as_gl();
+            let capabilities = context.capabilities();
+            let disjoint = capabilities.has_extension("GL_EXT_disjoint_timer_query");
+            // Drivers may load the query entry points without the extension
+            // or version that makes them usable for timing, and GLES ones may
+            // have a timestamp counter of zero bits.
+            let (elapsed, timestamp) = match context.get_type() {
+                GlType::Gl => {
+                    let arb = capabilities.is_version_at_least(3, 3)
+                        || capabilities.has_extension("GL_ARB_timer_query");
+                    (
+                        arb || capabilities.has_extension("GL_EXT_timer_query"),
+                        arb,
+                    )
+                }
+                GlType::Gles => {
+                    let bits = || context.get_query_iv(ffi::TIMESTAMP, ffi::QUERY_COUNTER_BITS);
+                    (disjoint, disjoint && bits() > 0)
+                }
+            };
+            let mode = if timestamp && capabilities.is_loaded("query_counter") {
+                Some(GpuTimerMode::Timestamp)
+            } else if elapsed && capabilities.is_loaded("begin_query") {
+                Some(GpuTimerMode::TimeElapsed)
+            } else {
+                None
+            };
+            (mode, disjoint)
+        };
+        GpuProfiler {
+            gl,
+            mode,
+            disjoint,
+            callback,
+            free_queries: Vec::new(),
+            frame: Vec::new(),
+            open_scopes: Vec::new(),
+            pending_frames: VecDeque::new(),
+        }
+    }
+
+    /// Starts timing a scope, which lasts until the matching `end_scope`.
+    pub fn begin_scope(&mut self, name: &str) {
+        let start = match self.mode {
+            Some(GpuTimerMode::Timestamp) => {
+                let query = self.query();
+                if query != 0 {
+                    self.gl.as_gl().query_counter(query, ffi::TIMESTAMP);
+                }
+                query
+            }
+            Some(GpuTimerMode::TimeElapsed) if self.open_scopes.is_empty() => {
+                let query = self.query();
+                if query != 0 {
+                    self.gl.as_gl().begin_query(ffi::TIME_ELAPSED, query);
+                }
+                query
+            }
+            _ => 0,
+        };
+        self.open_scopes.push(self.frame.len());
+        self.frame.push(GpuTimerScope {
+            name: name.to_string(),
+            start,
+            end: 0,
+        });
+    }
+
+    /// Stops timing the innermost scope.
+    pub fn end_scope(&mut self) {
+        let index = match self.open_scopes.pop() {
+            Some(index) => index,
+            None => return,
+        };
+        if self.frame[index].start == 0 {
+            return;
+        }
+        match self.mode {
+            Some(GpuTimerMode::Timestamp) => {
+                let query = self.query();
+                if query != 0 {
+                    self.gl.as_gl().query_counter(query, ffi::TIMESTAMP);
+                }
+                self.frame[index].end = query;
+            }
+            Some(GpuTimerMode::TimeElapsed) => self.gl.as_gl().end_query(ffi::TIME_ELAPSED),
+            None => {}
+        }
+    }
+
+    /// Ends the scopes still open, queues the frame and passes the durations
+    /// of the frames whose results are available to the callback, oldest
+    /// first.
+    pub fn end_frame(&mut self) {
+        while !self.open_scopes.is_empty() {
+            self.end_scope();
+        }
+        let frame = mem::take(&mut self.frame);
+        if !frame.is_empty() {
+            self.pending_frames.push_back(frame);
+        }
+
+        if self.disjoint && self.is_disjoint() {
+            // Timings that span the disjoint operation, like a power state
+            // change, are meaningless.
+            while let Some(frame) = self.pending_frames.pop_front() {
+                self.drop_frame(frame);
+            }
+            return;
+        }
+
+        while let Some(frame) = self.pending_frames.pop_front() {
+            if !self.is_available(&frame) {
+                self.pending_frames.push_front(frame);
+                break;
+            }
+            self.report(&frame);
+            for scope in &frame {
+                self.free_queries.extend(scope.queries());
+            }
+        }
+        while self.pending_frames.len() > GPU_PROFILER_MAX_PENDING_FRAMES {
+            let frame = self.pending_frames.pop_front().unwrap();
+            self.drop_frame(frame);
+        }
+    }
+
+    /// Returns a free query, or 0 if the context failed to create one.
+    fn query(&mut self) -> GLuint {
+        if let Some(query) = self.free_queries.pop() {
+            return query;
+        }
+        self.gl.as_gl().gen_queries(1).first().cloned().unwrap_or(0)
+    }
+
+    /// Reads, and so resets, `GPU_DISJOINT_EXT`.
+    fn is_disjoint(&self) -> bool {
+        let mut disjoint = [0];
+        unsafe {
+            self.gl
+                .as_gl()
+                .get_integer_v(ffi::GPU_DISJOINT_EXT, &mut disjoint);
+        }
+        disjoint[0] != 0
+    }
+
+    fn is_available(&self, frame: &[GpuTimerScope]) -> bool {
+        let gl = self.gl.as_gl();
+        frame
+            .iter()
+            .flat_map(GpuTimerScope::queries)
+            .all(|query| gl.get_query_object_uiv(query, ffi::QUERY_RESULT_AVAILABLE) != 0)
+    }
+
+    fn report(&mut self, frame: &[GpuTimerScope]) {
+        let gl = self.gl.as_gl();
+        for scope in frame {
+            let nanoseconds = match self.mode {
+                Some(GpuTimerMode::Timestamp) if scope.start != 0 && scope.end != 0 => {
+                    let start = gl.get_query_object_ui64v(scope.start, ffi::QUERY_RESULT);
+                    let end = gl.get_query_object_ui64v(scope.end, ffi::QUERY_RESULT);
+                    end.saturating_sub(start)
+                }
+                Some(GpuTimerMode::TimeElapsed) if scope.start != 0 => {
+                    gl.get_query_object_ui64v(scope.start, ffi::QUERY_RESULT)
+                }
+                _ => continue,
+            };
+            (self.callback)(&scope.name, Duration::from_nanos(nanoseconds));
+        }
+    }
+
+    /// Deletes the queries of a frame whose results are never read, rather
+    /// than reusing queries the GPU may still be writing.
+    fn drop_frame(&mut self, frame: Vec<GpuTimerScope>) {
+        let queries: Vec<GLuint> = frame.iter().flat_map(GpuTimerScope::queries).collect();
+        if !queries.is_empty() {
+            self.gl.as_gl().delete_queries(&queries);
+        }
+    }
+}
+
+impl<F, G: SharedGl> Drop for GpuProfiler<F, G> {
+    fn drop(&mut self) {
+        let mut queries = mem::take(&mut self.free_queries);
+        let scopes = self.pending_frames.iter().flatten().chain(&self.frame);
+        queries.extend(scopes.flat_map(GpuTimerScope::queries));
+        if !queries.is_empty() {
+            self.gl.as_gl().delete_queries(&queries);
+        }
+    }
+}
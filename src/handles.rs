@@ -0,0 +1,329 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Owned handles to GL objects, which delete their object when dropped and
+//! can only be used where an object of their kind is expected.
+//!
+//! Each handle keeps the context it was created with alive. `raw` and
+//! `into_raw` give out the name to pass to the `Gl` methods these handles
+//! don't cover, and `from_raw` takes ownership of a name created elsewhere.
+
+use ffi;
+use gl::Gl;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+
+use ffi::types::{GLbitfield, GLenum, GLsync, GLuint, GLuint64};
+
+macro_rules! gl_handle {
+    ($(#[$attr:meta])* $name:ident, |$gl:ident, $raw:ident| $delete:expr) => {
+        $(#[$attr])*
+        pub struct $name {
+            gl: Rc<dyn Gl>,
+            name: GLuint,
+        }
+
+        impl $name {
+            /// Takes ownership of `name`, which is deleted when the handle is
+            /// dropped.
+            pub fn from_raw(gl: Rc<dyn Gl>, name: GLuint) -> $name {
+                $name { gl, name }
+            }
+
+            /// The name of the object, which stays owned by the handle.
+            pub fn raw(&self) -> GLuint {
+                self.name
+            }
+
+            /// Gives up ownership of the object, returning its name, which the
+            /// caller is now responsible for deleting.
+            pub fn into_raw(mut self) -> GLuint {
+                mem::take(&mut self.name)
+            }
+
+            pub fn gl(&self) -> &Rc<dyn Gl> {
+                &self.gl
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                if self.name != 0 {
+                    let $gl = &self.gl;
+                    let $raw = self.name;
+                    $delete;
+                }
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.name).finish()
+            }
+        }
+    };
+}
+
+/// The first name of a `gen_*` call, or 0 if the context failed to make one.
+fn first(names: Vec<GLuint>) -> GLuint {
+    names.first().cloned().unwrap_or(0)
+}
+
+gl_handle!(
+    /// A texture object.
+    Texture,
+    |gl, name| gl.delete_textures(&[name])
+);
+
+impl Texture {
+    pub fn new(gl: &Rc<dyn Gl>) -> Texture {
+        Texture::from_raw(gl.clone(), first(gl.gen_textures(1)))
+    }
+
+    pub fn bind(&self, target: GLenum) {
+        self.gl.bind_texture(target, self.name);
+    }
+}
+
+gl_handle!(
+    /// A buffer object.
+    Buffer,
+    |gl, name| gl.delete_buffers(&[name])
+);
+
+impl Buffer {
+    pub fn new(gl: &Rc<dyn Gl>) -> Buffer {
+        Buffer::from_raw(gl.clone(), first(gl.gen_buffers(1)))
+    }
+
+    pub fn bind(&self, target: GLenum) {
+        self.gl.bind_buffer(target, self.name);
+    }
+
+    pub fn bind_base(&self, target: GLenum, index: GLuint) {
+        self.gl.bind_buffer_base(target, index, self.name);
+    }
+}
+
+gl_handle!(
+    /// A framebuffer object.
+    Framebuffer,
+    |gl, name| gl.delete_framebuffers(&[name])
+);
+
+impl Framebuffer {
+    pub fn new(gl: &Rc<dyn Gl>) -> Framebuffer {
+        Framebuffer::from_raw(gl.clone(), first(gl.gen_framebuffers(1)))
+    }
+
+    pub fn bind(&self, target: GLenum) {
+        self.gl.bind_framebuffer(target, self.name);
+    }
+}
+
+gl_handle!(
+    /// A renderbuffer object.
+    Renderbuffer,
+    |gl, name| gl.delete_renderbuffers(&[name])
+);
+
+impl Renderbuffer {
+    pub fn new(gl: &Rc<dyn Gl>) -> Renderbuffer {
+        Renderbuffer::from_raw(gl.clone(), first(gl.gen_renderbuffers(1)))
+    }
+
+    pub fn bind(&self) {
+        self.gl.bind_renderbuffer(ffi::RENDERBUFFER, self.name);
+    }
+}
+
+gl_handle!(
+    /// A vertex array object.
+    VertexArray,
+    |gl, name| gl.delete_vertex_arrays(&[name])
+);
+
+impl VertexArray {
+    pub fn new(gl: &Rc<dyn Gl>) -> VertexArray {
+        VertexArray::from_raw(gl.clone(), first(gl.gen_vertex_arrays(1)))
+    }
+
+    pub fn bind(&self) {
+        self.gl.bind_vertex_array(self.name);
+    }
+}
+
+gl_handle!(
+    /// A query object.
+    Query,
+    |gl, name| gl.delete_queries(&[name])
+);
+
+impl Query {
+    pub fn new(gl: &Rc<dyn Gl>) -> Query {
+        Query::from_raw(gl.clone(), first(gl.gen_queries(1)))
+    }
+
+    /// Starts the query, which lasts until `end_query(target)`.
+    pub fn begin(&self, target: GLenum) {
+        self.gl.begin_query(target, self.name);
+    }
+
+    /// Records the GPU time once the previous commands have completed.
+    pub fn timestamp(&self) {
+        self.gl.query_counter(self.name, ffi::TIMESTAMP);
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.gl
+            .get_query_object_uiv(self.name, ffi::QUERY_RESULT_AVAILABLE)
+            != 0
+    }
+
+    /// The result of the query, waiting for it if it isn't available yet.
+    pub fn result(&self) -> u64 {
+        self.gl.get_query_object_ui64v(self.name, ffi::QUERY_RESULT)
+    }
+}
+
+gl_handle!(
+    /// A shader object.
+    Shader,
+    |gl, name| gl.delete_shader(name)
+);
+
+impl Shader {
+    /// Creates a shader of type `shader_type`, such as `VERTEX_SHADER`.
+    pub fn new(gl: &Rc<dyn Gl>, shader_type: GLenum) -> Shader {
+        Shader::from_raw(gl.clone(), gl.create_shader(shader_type))
+    }
+
+    pub fn source(&self, strings: &[&[u8]]) {
+        self.gl.shader_source(self.name, strings);
+    }
+
+    pub fn compile(&self) {
+        self.gl.compile_shader(self.name);
+    }
+
+    pub fn is_compiled(&self) -> bool {
+        let mut status = [0];
+        unsafe {
+            self.gl
+                .get_shader_iv(self.name, ffi::COMPILE_STATUS, &mut status);
+        }
+        status[0] != 0
+    }
+
+    pub fn info_log(&self) -> String {
+        self.gl.get_shader_info_log(self.name)
+    }
+}
+
+gl_handle!(
+    /// A program object.
+    Program,
+    |gl, name| gl.delete_program(name)
+);
+
+impl Program {
+    pub fn new(gl: &Rc<dyn Gl>) -> Program {
+        Program::from_raw(gl.clone(), gl.create_program())
+    }
+
+    pub fn attach(&self, shader: &Shader) {
+        self.gl.attach_shader(self.name, shader.name);
+    }
+
+    pub fn link(&self) {
+        self.gl.link_program(self.name);
+    }
+
+    pub fn is_linked(&self) -> bool {
+        let mut status = [0];
+        unsafe {
+            self.gl
+                .get_program_iv(self.name, ffi::LINK_STATUS, &mut status);
+        }
+        status[0] != 0
+    }
+
+    pub fn info_log(&self) -> String {
+        self.gl.get_program_info_log(self.name)
+    }
+
+    /// Makes the program part of the current rendering state.
+    pub fn use_program(&self) {
+        self.gl.use_program(self.name);
+    }
+}
+
+/// A sync object, signaled once the commands before it have completed.
+pub struct Sync {
+    gl: Rc<dyn Gl>,
+    sync: GLsync,
+}
+
+impl Sync {
+    /// Inserts a fence after the commands issued so far.
+    pub fn fence(gl: &Rc<dyn Gl>) -> Sync {
+        let sync = gl.fence_sync(ffi::SYNC_GPU_COMMANDS_COMPLETE, 0);
+        Sync::from_raw(gl.clone(), sync)
+    }
+
+    /// Takes ownership of `sync`, which is deleted when the handle is
+    /// dropped.
+    pub fn from_raw(gl: Rc<dyn Gl>, sync: GLsync) -> Sync {
+        Sync { gl, sync }
+    }
+
+    /// The sync object, which stays owned by the handle.
+    pub fn raw(&self) -> GLsync {
+        self.sync
+    }
+
+    /// Gives up ownership of the sync object, which the caller is now
+    /// responsible for deleting.
+    pub fn into_raw(mut self) -> GLsync {
+        mem::replace(&mut self.sync, ptr::null())
+    }
+
+    pub fn gl(&self) -> &Rc<dyn Gl> {
+        &self.gl
+    }
+
+    /// Waits on the client for up to `timeout` nanoseconds, returning
+    /// `ALREADY_SIGNALED`, `CONDITION_SATISFIED`, `TIMEOUT_EXPIRED` or
+    /// `WAIT_FAILED`.
+    pub fn client_wait(&self, flags: GLbitfield, timeout: GLuint64) -> GLenum {
+        self.gl.client_wait_sync(self.sync, flags, timeout)
+    }
+
+    /// Makes the server wait for the sync object before running the commands
+    /// issued after this call.
+    pub fn wait(&self) {
+        self.gl.wait_sync(self.sync, 0, ffi::TIMEOUT_IGNORED);
+    }
+}
+
+impl Drop for Sync {
+    fn drop(&mut self) {
+        if !self.sync.is_null() {
+            self.gl.delete_sync(self.sync);
+        }
+    }
+}
+
+impl fmt::Debug for Sync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Sync").field(&self.sync).finish()
+    }
+}
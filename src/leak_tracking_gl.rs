@@ -0,0 +1,228 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// An object created through a `LeakTrackingGl` and not deleted yet.
+#[derive(Clone, Debug)]
+pub struct LiveObject {
+    /// The name of the object, or the address of a sync object.
+    pub name: usize,
+    /// The label set with `LeakTrackingGl::set_label` when the object was
+    /// created, or given to it later with `object_label`.
+    pub label: Option<String>,
+    /// Where the object was created, if backtraces were being captured.
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
+/// The live objects of each kind, such as "texture" or "program", in the
+/// order of their names.
+pub type LiveObjects = BTreeMap<&'static str, Vec<LiveObject>>;
+
+/// The kind of object a method creates or deletes.
+fn tracked_kind(method: &str) -> Option<&'static str> {
+    Some(match method {
+        "gen_buffers" | "delete_buffers" => "buffer",
+        "gen_textures" | "delete_textures" => "texture",
+        "gen_framebuffers" | "delete_framebuffers" => "framebuffer",
+        "gen_renderbuffers" | "delete_renderbuffers" => "renderbuffer",
+        "gen_vertex_arrays"
+        | "gen_vertex_arrays_apple"
+        | "delete_vertex_arrays"
+        | "delete_vertex_arrays_apple" => "vertex array",
+        "gen_queries" | "delete_queries" => "query",
+        "gen_fences_apple" | "delete_fences_apple" => "fence",
+        "create_shader" | "delete_shader" => "shader",
+        "create_program" | "delete_program" => "program",
+        "fence_sync" | "delete_sync" => "sync",
+        _ => return None,
+    })
+}
+
+/// The kind of object an `object_label` identifier names.
+fn labeled_kind(identifier: GLenum) -> Option<&'static str> {
+    Some(match identifier {
+        ffi::BUFFER => "buffer",
+        ffi::TEXTURE => "texture",
+        ffi::FRAMEBUFFER => "framebuffer",
+        ffi::RENDERBUFFER => "renderbuffer",
+        ffi::VERTEX_ARRAY => "vertex array",
+        ffi::QUERY => "query",
+        ffi::SHADER => "shader",
+        ffi::PROGRAM => "program",
+        _ => return None,
+    })
+}
+
+#[derive(Default)]
+struct LeakTracker {
+    live: HashMap<(&'static str, usize), LiveObject>,
+    label: Option<String>,
+    capture_backtraces: bool,
+}
+
+impl LeakTracker {
+    fn created(&mut self, kind: &'static str, names: &[usize]) {
+        let backtrace = if self.capture_backtraces {
+            Some(Arc::new(Backtrace::force_capture()))
+        } else {
+            None
+        };
+        for &name in names.iter().filter(|&&name| name != 0) {
+            let object = LiveObject {
+                name,
+                label: self.label.clone(),
+                backtrace: backtrace.clone(),
+            };
+            self.live.insert((kind, name), object);
+        }
+    }
+
+    fn deleted(&mut self, kind: &'static str, names: &[usize]) {
+        for &name in names {
+            self.live.remove(&(kind, name));
+        }
+    }
+
+    fn labeled(&mut self, kind: &'static str, name: usize, label: &str) {
+        if let Some(object) = self.live.get_mut(&(kind, name)) {
+            object.label = Some(label.to_string()).filter(|label| !label.is_empty());
+        }
+    }
+
+    fn track_args(&mut self, call: &GlCall) {
+        let kind = match tracked_kind(call.method) {
+            Some(kind) if call.method.starts_with("delete_") => kind,
+            _ => return self.track_labels(call),
+        };
+        let names: Vec<usize> = match call.method {
+            "delete_sync" => vec![call.ptr_arg("sync") as usize],
+            "delete_shader" => vec![call.uint_arg("shader") as usize],
+            "delete_program" => vec![call.uint_arg("program") as usize],
+            // The slice of names is the only argument.
+            _ => match *call.args {
+                [(arg, _)] => call.names_arg(arg).iter().map(|&name| name as usize).collect(),
+                _ => unreachable!("{} takes a slice of names", call.method),
+            },
+        };
+        self.deleted(kind, &names);
+    }
+
+    fn track_labels(&mut self, call: &GlCall) {
+        match call.method {
+            "object_label" => {
+                if let Some(kind) = labeled_kind(call.enum_arg("identifier")) {
+                    let name = call.uint_arg("name");
+                    self.labeled(kind, name as usize, call.str_arg("label"));
+                }
+            }
+            "object_ptr_label" => {
+                let sync = call.ptr_arg("sync");
+                self.labeled("sync", sync as usize, call.str_arg("label"));
+            }
+            _ => {}
+        }
+    }
+
+    fn track_result(&mut self, method: &str, result: &dyn Any) {
+        let kind = match tracked_kind(method) {
+            Some(kind) if !method.starts_with("delete_") => kind,
+            _ => return,
+        };
+        if let Some(names) = result.downcast_ref::<Vec<GLuint>>() {
+            let names: Vec<usize> = names.iter().map(|&name| name as usize).collect();
+            self.created(kind, &names);
+        } else if let Some(&name) = result.downcast_ref::<GLuint>() {
+            self.created(kind, &[name as usize]);
+        } else if let Some(&sync) = result.downcast_ref::<GLsync>() {
+            self.created(kind, &[sync as usize]);
+        }
+    }
+
+    fn live_objects(&self) -> LiveObjects {
+        let mut live = LiveObjects::new();
+        for (&(kind, _), object) in &self.live {
+            live.entry(kind).or_default().push(object.clone());
+        }
+        for objects in live.values_mut() {
+            objects.sort_by_key(|object| object.name);
+        }
+        live
+    }
+}
+
+/// A wrapper around GL context that keeps track of the objects created
+/// through it with `gen_*`, `create_shader`, `create_program` and
+/// `fence_sync` until they are deleted, to find the ones that leak.
+///
+/// The live objects are returned by `live_objects`, and passed to the
+/// callback when the wrapper is dropped if there are any. Keep the returned
+/// wrapper to read them or label new objects, and pass it on as an
+/// `Rc<dyn Gl>`.
+pub struct LeakTrackingGl<F: Fn(&LiveObjects), G = Rc<dyn Gl>> {
+    gl: G,
+    tracker: Mutex<LeakTracker>,
+    callback: F,
+}
+
+impl<F: 'static + Fn(&LiveObjects)> LeakTrackingGl<F> {
+    pub fn wrap(fns: Rc<dyn Gl>, callback: F) -> Rc<LeakTrackingGl<F>> {
+        Rc::new(LeakTrackingGl {
+            gl: fns,
+            tracker: Mutex::default(),
+            callback,
+        })
+    }
+}
+
+impl<F: Fn(&LiveObjects), G: SharedGl> LeakTrackingGl<F, G> {
+    /// Wraps the functions of a `SendGl`, with `SendGl::wrap`. The live
+    /// objects are read through the guard of `SendGl::make_current`.
+    pub fn wrap_send(fns: G, callback: F) -> LeakTrackingGl<F, G> {
+        LeakTrackingGl {
+            gl: fns,
+            tracker: Mutex::default(),
+            callback,
+        }
+    }
+}
+
+impl<F: Fn(&LiveObjects), G> LeakTrackingGl<F, G> {
+    /// Labels the objects created from now on, until the label is replaced
+    /// or cleared with `None`.
+    pub fn set_label(&self, label: Option<&str>) {
+        lock(&self.tracker).label = label.map(str::to_string);
+    }
+
+    /// Captures a backtrace of each call creating objects from now on, which
+    /// is slow.
+    pub fn set_capture_backtraces(&self, capture: bool) {
+        lock(&self.tracker).capture_backtraces = capture;
+    }
+
+    /// The objects created through the wrapper and not deleted yet.
+    pub fn live_objects(&self) -> LiveObjects {
+        lock(&self.tracker).live_objects()
+    }
+
+    fn track_args(&self, call: &GlCall) {
+        lock(&self.tracker).track_args(call);
+    }
+
+    fn track_result(&self, method: &str, result: &dyn Any) {
+        lock(&self.tracker).track_result(method, result);
+    }
+}
+
+impl<F: Fn(&LiveObjects), G> Drop for LeakTrackingGl<F, G> {
+    fn drop(&mut self) {
+        let live = self.live_objects();
+        if !live.is_empty() {
+            (self.callback)(&live);
+        }
+    }
+}
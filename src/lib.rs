@@ -10,7 +10,13 @@
 #![crate_name = "gleam"]
 #![crate_type = "lib"]
 
+#[cfg(feature = "dlopen")]
+extern crate libloading;
+
 pub mod gl;
+pub mod handles;
+pub mod pixel_format;
+pub mod program;
 
 mod ffi {
     include!(concat!(env!("OUT_DIR"), "/gl_and_gles_bindings.rs"));
@@ -0,0 +1,137 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The signature shared by `eglGetProcAddress`, `glXGetProcAddressARB` and
+/// `wglGetProcAddress`.
+type GetProcAddress = unsafe extern "system" fn(*const c_char) -> *const c_void;
+
+/// An error opening a shared library.
+#[derive(Debug)]
+pub struct LibraryError {
+    pub path: String,
+    pub reason: String,
+}
+
+impl fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to open {}: {}", self.path, self.reason)
+    }
+}
+
+impl Error for LibraryError {}
+
+/// A `GlLoader` that opens GL shared libraries itself, resolving symbols
+/// through the platform's `*GetProcAddress` function and falling back to
+/// looking them up in each library.
+///
+/// The libraries are never closed, as the contexts loaded from them keep
+/// using their functions after the loader is dropped.
+pub struct LibraryLoader {
+    get_proc_address: Option<GetProcAddress>,
+    /// Searched in order for symbols `get_proc_address` doesn't know.
+    libraries: Vec<mem::ManuallyDrop<libloading::Library>>,
+}
+
+impl LibraryLoader {
+    /// Opens the libraries at `paths`, and looks up the function called
+    /// `get_proc_address` in them to resolve symbols with.
+    ///
+    /// # Safety
+    ///
+    /// Opening a library runs its initialization code.
+    pub unsafe fn open<P: AsRef<OsStr>>(
+        paths: &[P],
+        get_proc_address: Option<&str>,
+    ) -> Result<LibraryLoader, LibraryError> {
+        let mut libraries = Vec::with_capacity(paths.len());
+        for path in paths {
+            let library = libloading::Library::new(path).map_err(|error| LibraryError {
+                path: path.as_ref().to_string_lossy().into_owned(),
+                reason: error.to_string(),
+            })?;
+            libraries.push(mem::ManuallyDrop::new(library));
+        }
+        let get_proc_address = get_proc_address.and_then(|name| {
+            libraries
+                .iter()
+                .filter_map(|library| library.get::<GetProcAddress>(name.as_bytes()).ok())
+                .map(|symbol| *symbol)
+                .next()
+        });
+        Ok(LibraryLoader {
+            get_proc_address,
+            libraries,
+        })
+    }
+
+    /// Opens the system's desktop GL library.
+    ///
+    /// # Safety
+    ///
+    /// See `open`.
+    pub unsafe fn gl() -> Result<LibraryLoader, LibraryError> {
+        if cfg!(target_os = "windows") {
+            LibraryLoader::open(&["opengl32.dll"], Some("wglGetProcAddress"))
+        } else if cfg!(target_os = "macos") {
+            LibraryLoader::open(
+                &["/System/Library/Frameworks/OpenGL.framework/OpenGL"],
+                None,
+            )
+        } else {
+            LibraryLoader::open(&["libGL.so.1"], Some("glXGetProcAddressARB"))
+        }
+    }
+
+    /// Opens the system's EGL and GLES libraries.
+    ///
+    /// # Safety
+    ///
+    /// See `open`.
+    pub unsafe fn gles() -> Result<LibraryLoader, LibraryError> {
+        if cfg!(target_os = "windows") {
+            LibraryLoader::egl("libEGL.dll", "libGLESv2.dll")
+        } else {
+            LibraryLoader::egl("libEGL.so.1", "libGLESv2.so.2")
+        }
+    }
+
+    /// Opens an EGL and a GLES library by path, such as the ones shipped
+    /// with ANGLE or SwiftShader.
+    ///
+    /// # Safety
+    ///
+    /// See `open`.
+    pub unsafe fn egl<P: AsRef<OsStr>>(egl: P, gles: P) -> Result<LibraryLoader, LibraryError> {
+        LibraryLoader::open(&[egl, gles], Some("eglGetProcAddress"))
+    }
+}
+
+impl GlLoader for LibraryLoader {
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void {
+        let name = match CString::new(symbol) {
+            Ok(name) => name,
+            Err(_) => return ptr::null(),
+        };
+        if let Some(get_proc_address) = self.get_proc_address {
+            let address = unsafe { get_proc_address(name.as_ptr()) };
+            // `wglGetProcAddress` returns one of these instead of null for the
+            // functions that are only exported by opengl32.dll.
+            if !matches!(address as isize, -1..=3) {
+                return address;
+            }
+        }
+        for library in &self.libraries {
+            let address = unsafe { library.get::<*const c_void>(name.as_bytes_with_nul()) };
+            if let Ok(address) = address {
+                return *address;
+            }
+        }
+        ptr::null()
+    }
+}
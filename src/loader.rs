@@ -0,0 +1,22 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// A source of GL entry points, used by `GlFns::load_from` and
+/// `GlesFns::load_from`. Any `FnMut(&str) -> *const c_void` closure is one.
+pub trait GlLoader {
+    /// Returns the address of the function called `symbol`, such as
+    /// "glClear", or null if there's no such function.
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void;
+}
+
+impl<F: FnMut(&str) -> *const c_void> GlLoader for F {
+    fn get_proc_address(&mut self, symbol: &str) -> *const c_void {
+        self(symbol)
+    }
+}
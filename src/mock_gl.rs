@@ -0,0 +1,4158 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+const MOCK_MAX_TEXTURE_SIZE: GLint = 16384;
+const MOCK_MAX_3D_TEXTURE_SIZE: GLint = 2048;
+const MOCK_MAX_VERTEX_ATTRIBS: GLuint = 16;
+const MOCK_MAX_TEXTURE_UNITS: GLuint = 32;
+const MOCK_MAX_BUFFER_BINDINGS: GLuint = 36;
+const MOCK_MAX_COLOR_ATTACHMENTS: GLint = 8;
+const MOCK_MAX_DEBUG_GROUP_STACK_DEPTH: usize = 64;
+const MOCK_MAX_LABEL_LENGTH: usize = 256;
+
+const MOCK_BUFFER_TARGETS: &[(GLenum, GLenum)] = &[
+    (ffi::ARRAY_BUFFER, ffi::ARRAY_BUFFER_BINDING),
+    (ffi::ELEMENT_ARRAY_BUFFER, ffi::ELEMENT_ARRAY_BUFFER_BINDING),
+    (ffi::PIXEL_PACK_BUFFER, ffi::PIXEL_PACK_BUFFER_BINDING),
+    (ffi::PIXEL_UNPACK_BUFFER, ffi::PIXEL_UNPACK_BUFFER_BINDING),
+    (ffi::UNIFORM_BUFFER, ffi::UNIFORM_BUFFER_BINDING),
+    (ffi::COPY_READ_BUFFER, ffi::COPY_READ_BUFFER_BINDING),
+    (ffi::COPY_WRITE_BUFFER, ffi::COPY_WRITE_BUFFER_BINDING),
+    (
+        ffi::TRANSFORM_FEEDBACK_BUFFER,
+        ffi::TRANSFORM_FEEDBACK_BUFFER_BINDING,
+    ),
+    (ffi::TEXTURE_BUFFER, ffi::TEXTURE_BUFFER),
+    (
+        ffi::SHADER_STORAGE_BUFFER,
+        ffi::SHADER_STORAGE_BUFFER_BINDING,
+    ),
+    (ffi::DRAW_INDIRECT_BUFFER, ffi::DRAW_INDIRECT_BUFFER_BINDING),
+];
+
+const MOCK_TEXTURE_TARGETS: &[(GLenum, GLenum)] = &[
+    (ffi::TEXTURE_2D, ffi::TEXTURE_BINDING_2D),
+    (ffi::TEXTURE_3D, ffi::TEXTURE_BINDING_3D),
+    (ffi::TEXTURE_2D_ARRAY, ffi::TEXTURE_BINDING_2D_ARRAY),
+    (ffi::TEXTURE_CUBE_MAP, ffi::TEXTURE_BINDING_CUBE_MAP),
+    (ffi::TEXTURE_RECTANGLE, ffi::TEXTURE_BINDING_RECTANGLE),
+    (ffi::TEXTURE_EXTERNAL_OES, ffi::TEXTURE_BINDING_EXTERNAL_OES),
+    (ffi::TEXTURE_BUFFER, ffi::TEXTURE_BINDING_BUFFER),
+    (
+        ffi::TEXTURE_2D_MULTISAMPLE,
+        ffi::TEXTURE_BINDING_2D_MULTISAMPLE,
+    ),
+];
+
+const MOCK_CAPABILITIES: &[GLenum] = &[
+    ffi::BLEND,
+    ffi::CULL_FACE,
+    ffi::DEPTH_CLAMP,
+    ffi::DEPTH_TEST,
+    ffi::DITHER,
+    ffi::FRAMEBUFFER_SRGB,
+    ffi::MULTISAMPLE,
+    ffi::POLYGON_OFFSET_FILL,
+    ffi::PRIMITIVE_RESTART_FIXED_INDEX,
+    ffi::PROGRAM_POINT_SIZE,
+    ffi::RASTERIZER_DISCARD,
+    ffi::SAMPLE_ALPHA_TO_COVERAGE,
+    ffi::SAMPLE_COVERAGE,
+    ffi::SAMPLE_MASK,
+    ffi::SCISSOR_TEST,
+    ffi::STENCIL_TEST,
+    ffi::TEXTURE_CUBE_MAP_SEAMLESS,
+    ffi::DEBUG_OUTPUT,
+    ffi::DEBUG_OUTPUT_SYNCHRONOUS,
+];
+
+const MOCK_PIXEL_STORE_PARAMS: &[GLenum] = &[
+    ffi::PACK_ALIGNMENT,
+    ffi::PACK_ROW_LENGTH,
+    ffi::PACK_SKIP_PIXELS,
+    ffi::PACK_SKIP_ROWS,
+    ffi::UNPACK_ALIGNMENT,
+    ffi::UNPACK_ROW_LENGTH,
+    ffi::UNPACK_IMAGE_HEIGHT,
+    ffi::UNPACK_SKIP_PIXELS,
+    ffi::UNPACK_SKIP_ROWS,
+    ffi::UNPACK_SKIP_IMAGES,
+];
+
+/// The targets of `begin_query`.
+const MOCK_QUERY_TARGETS: &[GLenum] = &[
+    ffi::SAMPLES_PASSED,
+    ffi::ANY_SAMPLES_PASSED,
+    ffi::ANY_SAMPLES_PASSED_CONSERVATIVE,
+    ffi::PRIMITIVES_GENERATED,
+    ffi::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN,
+    ffi::TIME_ELAPSED,
+];
+
+fn mock_cube_face(target: GLenum) -> bool {
+    (ffi::TEXTURE_CUBE_MAP_POSITIVE_X..=ffi::TEXTURE_CUBE_MAP_NEGATIVE_Z).contains(&target)
+}
+
+/// The target a texture image target is bound through.
+fn mock_binding_target(target: GLenum) -> GLenum {
+    if mock_cube_face(target) {
+        ffi::TEXTURE_CUBE_MAP
+    } else {
+        target
+    }
+}
+
+#[derive(Default)]
+struct MockBuffer {
+    data: Vec<u8>,
+    usage: GLenum,
+    immutable: bool,
+    mapped: Option<(usize, usize, GLbitfield)>,
+}
+
+#[derive(Clone, Copy)]
+struct MockLevel {
+    internal_format: GLenum,
+    width: GLsizei,
+    height: GLsizei,
+    depth: GLsizei,
+}
+
+#[derive(Default)]
+struct MockTexture {
+    target: Option<GLenum>,
+    levels: HashMap<(GLenum, GLint), MockLevel>,
+    immutable_levels: Option<GLint>,
+    params: HashMap<GLenum, GLfloat>,
+}
+
+impl MockTexture {
+    fn level(&self, target: GLenum, level: GLint) -> Option<MockLevel> {
+        self.levels.get(&(target, level)).cloned()
+    }
+
+    fn param(&self, name: GLenum) -> Option<GLfloat> {
+        if let Some(&value) = self.params.get(&name) {
+            return Some(value);
+        }
+        Some(match name {
+            ffi::TEXTURE_MIN_FILTER => ffi::NEAREST_MIPMAP_LINEAR as GLfloat,
+            ffi::TEXTURE_MAG_FILTER => ffi::LINEAR as GLfloat,
+            ffi::TEXTURE_WRAP_S | ffi::TEXTURE_WRAP_T | ffi::TEXTURE_WRAP_R => {
+                ffi::REPEAT as GLfloat
+            }
+            ffi::TEXTURE_BASE_LEVEL => 0.0,
+            ffi::TEXTURE_MAX_LEVEL => 1000.0,
+            ffi::TEXTURE_MIN_LOD => -1000.0,
+            ffi::TEXTURE_MAX_LOD => 1000.0,
+            ffi::TEXTURE_COMPARE_MODE => ffi::NONE as GLfloat,
+            ffi::TEXTURE_COMPARE_FUNC => ffi::LEQUAL as GLfloat,
+            ffi::TEXTURE_MAX_ANISOTROPY_EXT => 1.0,
+            ffi::TEXTURE_IMMUTABLE_FORMAT => self.immutable_levels.is_some() as GLint as GLfloat,
+            ffi::TEXTURE_IMMUTABLE_LEVELS => self.immutable_levels.unwrap_or(0) as GLfloat,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct MockAttachment {
+    object_type: GLenum,
+    name: GLuint,
+    target: GLenum,
+    level: GLint,
+    layer: GLint,
+}
+
+#[derive(Default)]
+struct MockFramebuffer {
+    bound: bool,
+    attachments: HashMap<GLenum, MockAttachment>,
+}
+
+#[derive(Default)]
+struct MockRenderbuffer {
+    bound: bool,
+    internal_format: GLenum,
+    width: GLsizei,
+    height: GLsizei,
+}
+
+#[derive(Clone, Copy, Default)]
+struct MockVertexAttrib {
+    enabled: bool,
+    size: GLint,
+    type_: GLenum,
+    normalized: bool,
+    integer: bool,
+    stride: GLsizei,
+    offset: GLuint,
+    buffer: GLuint,
+    divisor: GLuint,
+}
+
+#[derive(Default)]
+struct MockVertexArray {
+    element_buffer: GLuint,
+    attribs: HashMap<GLuint, MockVertexAttrib>,
+}
+
+struct MockShader {
+    shader_type: GLenum,
+    source: String,
+    compiled: bool,
+    info_log: String,
+    delete_pending: bool,
+}
+
+/// A `debug_message_control` call, where `DONT_CARE` and empty `ids` match
+/// any message.
+struct MockDebugControl {
+    source: GLenum,
+    ty: GLenum,
+    severity: GLenum,
+    ids: Vec<GLuint>,
+    enabled: bool,
+}
+
+impl MockDebugControl {
+    fn matches(&self, message: &DebugMessage) -> bool {
+        let matches = |filter, value| filter == ffi::DONT_CARE || filter == value;
+        matches(self.source, message.source.to_gl())
+            && matches(self.ty, message.ty.to_gl())
+            && matches(self.severity, message.severity.to_gl())
+            && (self.ids.is_empty() || self.ids.contains(&message.id))
+    }
+}
+
+#[derive(Default)]
+struct MockProgram {
+    shaders: Vec<GLuint>,
+    linked: bool,
+    info_log: String,
+    delete_pending: bool,
+    attrib_bindings: HashMap<String, GLuint>,
+    frag_data_bindings: HashMap<String, (GLuint, GLuint)>,
+    attribs: Vec<(String, GLint)>,
+    uniforms: Vec<String>,
+    uniform_values: HashMap<GLint, Vec<f64>>,
+    uniform_blocks: Vec<(String, GLuint)>,
+}
+
+impl MockProgram {
+    fn attrib_location(&mut self, name: &str) -> GLint {
+        if let Some(&(_, location)) = self.attribs.iter().find(|a| a.0 == name) {
+            return location;
+        }
+        let location = match self.attrib_bindings.get(name) {
+            Some(&index) => index as GLint,
+            None => (0..)
+                .find(|l| self.attribs.iter().all(|a| a.1 != *l))
+                .unwrap(),
+        };
+        self.attribs.push((name.to_string(), location));
+        location
+    }
+
+    fn uniform_index(&mut self, name: &str) -> GLuint {
+        match self.uniforms.iter().position(|u| u == name) {
+            Some(index) => index as GLuint,
+            None => {
+                self.uniforms.push(name.to_string());
+                self.uniforms.len() as GLuint - 1
+            }
+        }
+    }
+
+    fn uniform_block_index(&mut self, name: &str) -> GLuint {
+        match self.uniform_blocks.iter().position(|b| b.0 == name) {
+            Some(index) => index as GLuint,
+            None => {
+                self.uniform_blocks.push((name.to_string(), 0));
+                self.uniform_blocks.len() as GLuint - 1
+            }
+        }
+    }
+}
+
+struct MockState {
+    error: GLenum,
+    next_name: GLuint,
+    buffers: HashMap<GLuint, MockBuffer>,
+    textures: HashMap<GLuint, MockTexture>,
+    default_textures: HashMap<GLenum, MockTexture>,
+    framebuffers: HashMap<GLuint, MockFramebuffer>,
+    renderbuffers: HashMap<GLuint, MockRenderbuffer>,
+    vertex_arrays: HashMap<GLuint, MockVertexArray>,
+    queries: HashMap<GLuint, Option<GLenum>>,
+    shaders: HashMap<GLuint, MockShader>,
+    programs: HashMap<GLuint, MockProgram>,
+    syncs: HashSet<usize>,
+    fences_apple: HashSet<GLuint>,
+    buffer_bindings: HashMap<GLenum, GLuint>,
+    indexed_buffer_bindings: HashMap<(GLenum, GLuint), GLuint>,
+    texture_bindings: HashMap<(GLuint, GLenum), GLuint>,
+    active_texture: GLuint,
+    program: GLuint,
+    vertex_array: GLuint,
+    draw_framebuffer: GLuint,
+    read_framebuffer: GLuint,
+    renderbuffer: GLuint,
+    active_queries: HashMap<GLenum, GLuint>,
+    enabled: HashSet<GLenum>,
+    integers: HashMap<GLenum, Vec<GLint>>,
+    floats: HashMap<GLenum, Vec<GLfloat>>,
+    current_attribs: HashMap<GLuint, [GLfloat; 4]>,
+    debug_messages: Vec<DebugMessage>,
+    debug_groups: Vec<String>,
+    debug_controls: Vec<MockDebugControl>,
+    debug_callback: Option<Rc<DebugCallback>>,
+    /// The messages logged since the last call that can log one, to pass to
+    /// `debug_callback` once the state isn't borrowed anymore.
+    debug_callback_messages: Vec<DebugMessage>,
+    object_labels: HashMap<(GLenum, GLuint), String>,
+    sync_labels: HashMap<usize, String>,
+    group_markers: Vec<String>,
+}
+
+impl MockState {
+    fn new(gl_type: GlType) -> MockState {
+        let mut integers = HashMap::new();
+        {
+            let mut int = |name: GLenum, value: &[GLint]| {
+                integers.insert(name, value.to_vec());
+            };
+            let one = ffi::ONE as GLint;
+            let zero = ffi::ZERO as GLint;
+            let add = ffi::FUNC_ADD as GLint;
+            let keep = ffi::KEEP as GLint;
+            let always = ffi::ALWAYS as GLint;
+            int(ffi::BLEND_SRC_RGB, &[one]);
+            int(ffi::BLEND_SRC_ALPHA, &[one]);
+            int(ffi::BLEND_DST_RGB, &[zero]);
+            int(ffi::BLEND_DST_ALPHA, &[zero]);
+            int(ffi::BLEND_EQUATION_RGB, &[add]);
+            int(ffi::BLEND_EQUATION_ALPHA, &[add]);
+            int(ffi::DEPTH_FUNC, &[ffi::LESS as GLint]);
+            int(ffi::DEPTH_WRITEMASK, &[1]);
+            int(ffi::COLOR_WRITEMASK, &[1, 1, 1, 1]);
+            int(ffi::CULL_FACE_MODE, &[ffi::BACK as GLint]);
+            int(ffi::FRONT_FACE, &[ffi::CCW as GLint]);
+            int(ffi::VIEWPORT, &[0, 0, 0, 0]);
+            int(ffi::SCISSOR_BOX, &[0, 0, 0, 0]);
+            int(ffi::STENCIL_FUNC, &[always]);
+            int(ffi::STENCIL_REF, &[0]);
+            int(ffi::STENCIL_VALUE_MASK, &[-1]);
+            int(ffi::STENCIL_WRITEMASK, &[-1]);
+            int(ffi::STENCIL_FAIL, &[keep]);
+            int(ffi::STENCIL_PASS_DEPTH_FAIL, &[keep]);
+            int(ffi::STENCIL_PASS_DEPTH_PASS, &[keep]);
+            int(ffi::STENCIL_BACK_FUNC, &[always]);
+            int(ffi::STENCIL_BACK_REF, &[0]);
+            int(ffi::STENCIL_BACK_VALUE_MASK, &[-1]);
+            int(ffi::STENCIL_BACK_WRITEMASK, &[-1]);
+            int(ffi::STENCIL_BACK_FAIL, &[keep]);
+            int(ffi::STENCIL_BACK_PASS_DEPTH_FAIL, &[keep]);
+            int(ffi::STENCIL_BACK_PASS_DEPTH_PASS, &[keep]);
+            int(ffi::STENCIL_CLEAR_VALUE, &[0]);
+            int(ffi::SAMPLE_COVERAGE_INVERT, &[0]);
+            int(ffi::GENERATE_MIPMAP_HINT, &[ffi::DONT_CARE as GLint]);
+            int(ffi::READ_BUFFER, &[ffi::BACK as GLint]);
+            int(ffi::DRAW_BUFFER0, &[ffi::BACK as GLint]);
+            int(ffi::PACK_ALIGNMENT, &[4]);
+            int(ffi::UNPACK_ALIGNMENT, &[4]);
+            for &param in &[
+                ffi::PACK_ROW_LENGTH,
+                ffi::PACK_SKIP_PIXELS,
+                ffi::PACK_SKIP_ROWS,
+                ffi::UNPACK_ROW_LENGTH,
+                ffi::UNPACK_IMAGE_HEIGHT,
+                ffi::UNPACK_SKIP_PIXELS,
+                ffi::UNPACK_SKIP_ROWS,
+                ffi::UNPACK_SKIP_IMAGES,
+            ] {
+                int(param, &[0]);
+            }
+
+            int(ffi::MAX_TEXTURE_SIZE, &[MOCK_MAX_TEXTURE_SIZE]);
+            int(ffi::MAX_CUBE_MAP_TEXTURE_SIZE, &[MOCK_MAX_TEXTURE_SIZE]);
+            int(ffi::MAX_RENDERBUFFER_SIZE, &[MOCK_MAX_TEXTURE_SIZE]);
+            int(
+                ffi::MAX_VIEWPORT_DIMS,
+                &[MOCK_MAX_TEXTURE_SIZE, MOCK_MAX_TEXTURE_SIZE],
+            );
+            int(ffi::MAX_3D_TEXTURE_SIZE, &[MOCK_MAX_3D_TEXTURE_SIZE]);
+            int(ffi::MAX_ARRAY_TEXTURE_LAYERS, &[MOCK_MAX_3D_TEXTURE_SIZE]);
+            int(ffi::MAX_TEXTURE_IMAGE_UNITS, &[16]);
+            int(
+                ffi::MAX_COMBINED_TEXTURE_IMAGE_UNITS,
+                &[MOCK_MAX_TEXTURE_UNITS as GLint],
+            );
+            int(ffi::MAX_VERTEX_ATTRIBS, &[MOCK_MAX_VERTEX_ATTRIBS as GLint]);
+            int(ffi::MAX_UNIFORM_BLOCK_SIZE, &[65536]);
+            int(
+                ffi::MAX_UNIFORM_BUFFER_BINDINGS,
+                &[MOCK_MAX_BUFFER_BINDINGS as GLint],
+            );
+            int(ffi::MAX_SHADER_STORAGE_BUFFER_BINDINGS, &[8]);
+            int(ffi::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &[256]);
+            int(ffi::MAX_COLOR_ATTACHMENTS, &[MOCK_MAX_COLOR_ATTACHMENTS]);
+            int(ffi::MAX_DRAW_BUFFERS, &[MOCK_MAX_COLOR_ATTACHMENTS]);
+            int(ffi::MAX_SAMPLES, &[4]);
+            int(ffi::MAX_DEBUG_MESSAGE_LENGTH, &[1024]);
+            int(ffi::MAX_LABEL_LENGTH, &[MOCK_MAX_LABEL_LENGTH as GLint]);
+            int(
+                ffi::MAX_DEBUG_GROUP_STACK_DEPTH,
+                &[MOCK_MAX_DEBUG_GROUP_STACK_DEPTH as GLint],
+            );
+            int(ffi::NUM_EXTENSIONS, &[0]);
+            int(ffi::MAJOR_VERSION, &[3]);
+            int(
+                ffi::MINOR_VERSION,
+                &[match gl_type {
+                    GlType::Gl => 3,
+                    GlType::Gles => 1,
+                }],
+            );
+        }
+
+        let mut floats = HashMap::new();
+        floats.insert(ffi::COLOR_CLEAR_VALUE, vec![0.0; 4]);
+        floats.insert(ffi::BLEND_COLOR, vec![0.0; 4]);
+        floats.insert(ffi::DEPTH_CLEAR_VALUE, vec![1.0]);
+        floats.insert(ffi::DEPTH_RANGE, vec![0.0, 1.0]);
+        floats.insert(ffi::LINE_WIDTH, vec![1.0]);
+        floats.insert(ffi::POLYGON_OFFSET_FACTOR, vec![0.0]);
+        floats.insert(ffi::POLYGON_OFFSET_UNITS, vec![0.0]);
+        floats.insert(ffi::SAMPLE_COVERAGE_VALUE, vec![1.0]);
+
+        let mut vertex_arrays = HashMap::new();
+        vertex_arrays.insert(0, MockVertexArray::default());
+        let mut enabled = HashSet::new();
+        enabled.insert(ffi::DITHER);
+        enabled.insert(ffi::MULTISAMPLE);
+
+        MockState {
+            error: ffi::NO_ERROR,
+            next_name: 1,
+            buffers: HashMap::new(),
+            textures: HashMap::new(),
+            default_textures: HashMap::new(),
+            framebuffers: HashMap::new(),
+            renderbuffers: HashMap::new(),
+            vertex_arrays,
+            queries: HashMap::new(),
+            shaders: HashMap::new(),
+            programs: HashMap::new(),
+            syncs: HashSet::new(),
+            fences_apple: HashSet::new(),
+            buffer_bindings: HashMap::new(),
+            indexed_buffer_bindings: HashMap::new(),
+            texture_bindings: HashMap::new(),
+            active_texture: 0,
+            program: 0,
+            vertex_array: 0,
+            draw_framebuffer: 0,
+            read_framebuffer: 0,
+            renderbuffer: 0,
+            active_queries: HashMap::new(),
+            enabled,
+            integers,
+            floats,
+            current_attribs: HashMap::new(),
+            debug_messages: Vec::new(),
+            debug_groups: Vec::new(),
+            debug_controls: Vec::new(),
+            debug_callback: None,
+            debug_callback_messages: Vec::new(),
+            object_labels: HashMap::new(),
+            sync_labels: HashMap::new(),
+            group_markers: Vec::new(),
+        }
+    }
+
+    /// Checks that `name` is an object of the kind `identifier` names, for
+    /// `object_label` and `get_object_label`. Samplers, program pipelines
+    /// and transform feedbacks aren't mocked, so none of them exist.
+    fn check_object_label(&self, identifier: GLenum, name: GLuint) -> Result<(), GLenum> {
+        let exists = match identifier {
+            ffi::BUFFER => self.buffers.contains_key(&name),
+            ffi::TEXTURE => self.textures.contains_key(&name),
+            ffi::FRAMEBUFFER => self.framebuffers.contains_key(&name),
+            ffi::RENDERBUFFER => self.renderbuffers.contains_key(&name),
+            ffi::VERTEX_ARRAY => self.vertex_arrays.contains_key(&name),
+            ffi::QUERY => self.queries.contains_key(&name),
+            ffi::SHADER => self.shaders.contains_key(&name),
+            ffi::PROGRAM => self.programs.contains_key(&name),
+            ffi::SAMPLER | ffi::PROGRAM_PIPELINE | ffi::TRANSFORM_FEEDBACK => false,
+            _ => return Err(ffi::INVALID_ENUM),
+        };
+        if exists {
+            Ok(())
+        } else {
+            Err(ffi::INVALID_VALUE)
+        }
+    }
+
+    /// Logs a debug message tagged with the current debug groups, unless
+    /// `debug_message_control` disabled it. Like a driver, messages of low
+    /// severity are disabled until they are enabled explicitly.
+    fn log_debug_message(
+        &mut self,
+        message: String,
+        source: DebugSource,
+        ty: DebugType,
+        id: GLuint,
+        severity: DebugSeverity,
+    ) {
+        let message = DebugMessage {
+            message,
+            source,
+            ty,
+            id,
+            severity,
+            groups: self.debug_groups.clone(),
+        };
+        let enabled = self
+            .debug_controls
+            .iter()
+            .rev()
+            .find(|control| control.matches(&message))
+            .map_or(severity != DebugSeverity::Low, |control| control.enabled);
+        if !enabled {
+            return;
+        }
+        if self.debug_callback.is_some() {
+            self.debug_callback_messages.push(message);
+        } else {
+            self.debug_messages.push(message);
+        }
+    }
+
+    /// Like a driver, only the first error is kept until `get_error`.
+    fn error(&mut self, error: GLenum) {
+        if self.error == ffi::NO_ERROR {
+            self.error = error;
+        }
+    }
+
+    fn gen_names(&mut self, n: GLsizei) -> Vec<GLuint> {
+        if n < 0 {
+            self.error(ffi::INVALID_VALUE);
+            return Vec::new();
+        }
+        let start = self.next_name;
+        match start.checked_add(n as GLuint) {
+            Some(end) => {
+                self.next_name = end;
+                (start..end).collect()
+            }
+            None => {
+                self.error(ffi::OUT_OF_MEMORY);
+                Vec::new()
+            }
+        }
+    }
+
+    fn buffer_binding(&self, target: GLenum) -> Option<GLuint> {
+        if !MOCK_BUFFER_TARGETS.iter().any(|t| t.0 == target) {
+            return None;
+        }
+        Some(if target == ffi::ELEMENT_ARRAY_BUFFER {
+            self.vertex_arrays
+                .get(&self.vertex_array)
+                .map_or(0, |vao| vao.element_buffer)
+        } else {
+            self.buffer_bindings.get(&target).cloned().unwrap_or(0)
+        })
+    }
+
+    /// Returns the buffer bound to `target`, raising the error a driver would
+    /// if there is none.
+    fn bound_buffer(&mut self, target: GLenum) -> Option<&mut MockBuffer> {
+        match self.buffer_binding(target) {
+            None => {
+                self.error(ffi::INVALID_ENUM);
+                None
+            }
+            Some(0) => {
+                self.error(ffi::INVALID_OPERATION);
+                None
+            }
+            Some(name) => self.buffers.get_mut(&name),
+        }
+    }
+
+    fn texture_binding(&self, target: GLenum) -> GLuint {
+        self.texture_bindings
+            .get(&(self.active_texture, target))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Returns the texture bound for `target`, which may be a cube map face.
+    fn bound_texture(&mut self, target: GLenum) -> Option<&mut MockTexture> {
+        let binding = mock_binding_target(target);
+        if !MOCK_TEXTURE_TARGETS.iter().any(|t| t.0 == binding) {
+            self.error(ffi::INVALID_ENUM);
+            return None;
+        }
+        match self.texture_binding(binding) {
+            0 => Some(self.default_textures.entry(binding).or_default()),
+            name => self.textures.get_mut(&name),
+        }
+    }
+
+    fn framebuffer_binding(&self, target: GLenum) -> Option<GLuint> {
+        match target {
+            ffi::FRAMEBUFFER | ffi::DRAW_FRAMEBUFFER => Some(self.draw_framebuffer),
+            ffi::READ_FRAMEBUFFER => Some(self.read_framebuffer),
+            _ => None,
+        }
+    }
+
+    fn framebuffer_status(&mut self, framebuffer: GLuint) -> GLenum {
+        if framebuffer == 0 {
+            return ffi::FRAMEBUFFER_COMPLETE;
+        }
+        let attachments = match self.framebuffers.get(&framebuffer) {
+            Some(framebuffer) => &framebuffer.attachments,
+            None => {
+                self.error(ffi::INVALID_OPERATION);
+                return 0;
+            }
+        };
+        if attachments.is_empty() {
+            return ffi::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT;
+        }
+        let mut size = None;
+        for attachment in attachments.values() {
+            let attachment_size = if attachment.object_type == ffi::RENDERBUFFER {
+                self.renderbuffers
+                    .get(&attachment.name)
+                    .filter(|r| r.width > 0 && r.height > 0)
+                    .map(|r| (r.width, r.height))
+            } else {
+                self.textures
+                    .get(&attachment.name)
+                    .and_then(|t| t.level(attachment.target, attachment.level))
+                    .filter(|l| attachment.layer < l.depth)
+                    .map(|l| (l.width, l.height))
+            };
+            match attachment_size {
+                None => return ffi::FRAMEBUFFER_INCOMPLETE_ATTACHMENT,
+                Some(s) if size.is_some_and(|size| size != s) => {
+                    return ffi::FRAMEBUFFER_INCOMPLETE_DIMENSIONS
+                }
+                Some(s) => size = Some(s),
+            }
+        }
+        ffi::FRAMEBUFFER_COMPLETE
+    }
+
+    fn check_draw_framebuffer(&mut self) -> bool {
+        if self.framebuffer_status(self.draw_framebuffer) != ffi::FRAMEBUFFER_COMPLETE {
+            self.error(ffi::INVALID_FRAMEBUFFER_OPERATION);
+            return false;
+        }
+        true
+    }
+
+    fn check_read_framebuffer(&mut self) -> bool {
+        if self.framebuffer_status(self.read_framebuffer) != ffi::FRAMEBUFFER_COMPLETE {
+            self.error(ffi::INVALID_FRAMEBUFFER_OPERATION);
+            return false;
+        }
+        true
+    }
+
+    fn attach(&mut self, target: GLenum, attachment: GLenum, value: Option<MockAttachment>) {
+        let framebuffer = match self.framebuffer_binding(target) {
+            Some(framebuffer) => framebuffer,
+            None => return self.error(ffi::INVALID_ENUM),
+        };
+        if framebuffer == 0 {
+            return self.error(ffi::INVALID_OPERATION);
+        }
+        let valid_attachment = attachment == ffi::DEPTH_ATTACHMENT
+            || attachment == ffi::STENCIL_ATTACHMENT
+            || attachment == ffi::DEPTH_STENCIL_ATTACHMENT
+            || (attachment >= ffi::COLOR_ATTACHMENT0
+                && attachment < ffi::COLOR_ATTACHMENT0 + MOCK_MAX_COLOR_ATTACHMENTS as GLenum);
+        if !valid_attachment {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        let attachments = &mut self.framebuffers.get_mut(&framebuffer).unwrap().attachments;
+        match value {
+            Some(value) => {
+                attachments.insert(attachment, value);
+            }
+            None => {
+                attachments.remove(&attachment);
+            }
+        }
+    }
+
+    fn detach_everywhere(&mut self, object_type: GLenum, name: GLuint) {
+        // Only the currently bound framebuffers lose the attachment.
+        for &framebuffer in &[self.draw_framebuffer, self.read_framebuffer] {
+            if let Some(framebuffer) = self.framebuffers.get_mut(&framebuffer) {
+                framebuffer
+                    .attachments
+                    .retain(|_, a| a.object_type != object_type || a.name != name);
+            }
+        }
+    }
+
+    fn current_program(&mut self) -> Option<&mut MockProgram> {
+        if self.program == 0 {
+            self.error(ffi::INVALID_OPERATION);
+            return None;
+        }
+        self.programs.get_mut(&self.program)
+    }
+
+    fn program(&mut self, program: GLuint) -> Option<&mut MockProgram> {
+        if !self.programs.contains_key(&program) {
+            let error = if self.shaders.contains_key(&program) {
+                ffi::INVALID_OPERATION
+            } else {
+                ffi::INVALID_VALUE
+            };
+            self.error(error);
+        }
+        self.programs.get_mut(&program)
+    }
+
+    fn linked_program(&mut self, program: GLuint) -> Option<&mut MockProgram> {
+        let linked = match self.program(program) {
+            Some(p) => p.linked,
+            None => return None,
+        };
+        if !linked {
+            self.error(ffi::INVALID_OPERATION);
+            return None;
+        }
+        self.programs.get_mut(&program)
+    }
+
+    fn shader(&mut self, shader: GLuint) -> Option<&mut MockShader> {
+        if !self.shaders.contains_key(&shader) {
+            let error = if self.programs.contains_key(&shader) {
+                ffi::INVALID_OPERATION
+            } else {
+                ffi::INVALID_VALUE
+            };
+            self.error(error);
+        }
+        self.shaders.get_mut(&shader)
+    }
+
+    fn release_shader(&mut self, shader: GLuint) {
+        let attached = self.programs.values().any(|p| p.shaders.contains(&shader));
+        if !attached && self.shaders.get(&shader).is_some_and(|s| s.delete_pending) {
+            self.shaders.remove(&shader);
+        }
+    }
+
+    fn release_program(&mut self, program: GLuint) {
+        if program == self.program
+            || !self
+                .programs
+                .get(&program)
+                .is_some_and(|p| p.delete_pending)
+        {
+            return;
+        }
+        if let Some(program) = self.programs.remove(&program) {
+            for shader in program.shaders {
+                self.release_shader(shader);
+            }
+        }
+    }
+
+    fn uniform(&mut self, location: GLint, values: &[f64]) {
+        if let Some(program) = self.current_program() {
+            if location == -1 {
+                return;
+            }
+            if !program.linked {
+                return self.error(ffi::INVALID_OPERATION);
+            }
+            program.uniform_values.insert(location, values.to_vec());
+        }
+    }
+
+    fn vertex_attrib(&mut self, index: GLuint) -> Option<&mut MockVertexAttrib> {
+        if index >= MOCK_MAX_VERTEX_ATTRIBS {
+            self.error(ffi::INVALID_VALUE);
+            return None;
+        }
+        let vao = self.vertex_arrays.get_mut(&self.vertex_array).unwrap();
+        Some(vao.attribs.entry(index).or_default())
+    }
+
+    fn set_attrib_pointer(&mut self, index: GLuint, attrib: MockVertexAttrib) {
+        let buffer = self
+            .buffer_bindings
+            .get(&ffi::ARRAY_BUFFER)
+            .cloned()
+            .unwrap_or(0);
+        if buffer == 0 && self.vertex_array != 0 && attrib.offset != 0 {
+            return self.error(ffi::INVALID_OPERATION);
+        }
+        if let Some(slot) = self.vertex_attrib(index) {
+            *slot = MockVertexAttrib {
+                enabled: slot.enabled,
+                divisor: slot.divisor,
+                buffer,
+                ..attrib
+            };
+        }
+    }
+
+    fn check_draw(&mut self, count: GLsizei, primcount: GLsizei) -> bool {
+        if count < 0 || primcount < 0 {
+            self.error(ffi::INVALID_VALUE);
+            return false;
+        }
+        let linked = self.programs.get(&self.program).is_some_and(|p| p.linked);
+        if !linked {
+            self.error(ffi::INVALID_OPERATION);
+            return false;
+        }
+        self.check_draw_framebuffer()
+    }
+
+    fn define_level(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        (width, height, depth): (GLsizei, GLsizei, GLsizei),
+        border: GLint,
+    ) {
+        let max = if depth > 1 {
+            MOCK_MAX_3D_TEXTURE_SIZE
+        } else {
+            MOCK_MAX_TEXTURE_SIZE
+        };
+        if level < 0
+            || width < 0
+            || height < 0
+            || depth < 0
+            || border != 0
+            || width > max
+            || height > max
+            || depth > max
+        {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        let error = match self.bound_texture(target) {
+            Some(ref texture) if texture.immutable_levels.is_some() => ffi::INVALID_OPERATION,
+            Some(texture) => {
+                texture.levels.insert(
+                    (target, level),
+                    MockLevel {
+                        internal_format,
+                        width,
+                        height,
+                        depth,
+                    },
+                );
+                return;
+            }
+            None => return,
+        };
+        self.error(error);
+    }
+
+    fn define_storage(
+        &mut self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+    ) {
+        if levels < 1 || width < 1 || height < 1 || depth < 1 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        let largest = if target == ffi::TEXTURE_3D {
+            width.max(height).max(depth)
+        } else {
+            width.max(height)
+        };
+        if levels > 32 - (largest as u32).leading_zeros() as GLint {
+            return self.error(ffi::INVALID_OPERATION);
+        }
+        let faces: Vec<GLenum> = if target == ffi::TEXTURE_CUBE_MAP {
+            (ffi::TEXTURE_CUBE_MAP_POSITIVE_X..ffi::TEXTURE_CUBE_MAP_NEGATIVE_Z + 1).collect()
+        } else {
+            vec![target]
+        };
+        let error = match self.bound_texture(target) {
+            Some(ref texture) if texture.immutable_levels.is_some() => ffi::INVALID_OPERATION,
+            Some(texture) => {
+                texture.levels.clear();
+                for &face in &faces {
+                    for level in 0..levels {
+                        let depth = if face == ffi::TEXTURE_3D {
+                            (depth >> level).max(1)
+                        } else {
+                            depth
+                        };
+                        texture.levels.insert(
+                            (face, level),
+                            MockLevel {
+                                internal_format,
+                                width: (width >> level).max(1),
+                                height: (height >> level).max(1),
+                                depth,
+                            },
+                        );
+                    }
+                }
+                texture.immutable_levels = Some(levels);
+                return;
+            }
+            None => return,
+        };
+        self.error(error);
+    }
+
+    fn check_sub_image(
+        &mut self,
+        target: GLenum,
+        level: GLint,
+        offset: (GLint, GLint, GLint),
+        size: (GLsizei, GLsizei, GLsizei),
+    ) -> bool {
+        let level = match self.bound_texture(target) {
+            Some(texture) => texture.level(target, level),
+            None => return false,
+        };
+        let level = match level {
+            Some(level) => level,
+            None => {
+                self.error(ffi::INVALID_OPERATION);
+                return false;
+            }
+        };
+        let fits = |offset: GLint, size: GLsizei, extent: GLsizei| {
+            offset >= 0 && size >= 0 && offset.checked_add(size).is_some_and(|end| end <= extent)
+        };
+        if !fits(offset.0, size.0, level.width)
+            || !fits(offset.1, size.1, level.height)
+            || !fits(offset.2, size.2, level.depth)
+        {
+            self.error(ffi::INVALID_VALUE);
+            return false;
+        }
+        true
+    }
+
+    fn check_pixel_transfer(&mut self, required: usize, available: usize) -> bool {
+        if available < required {
+            self.error(ffi::INVALID_OPERATION);
+            return false;
+        }
+        true
+    }
+
+    fn integer(&mut self, name: GLenum) -> Option<Vec<GLint>> {
+        if let Some(value) = self.integers.get(&name) {
+            return Some(value.clone());
+        }
+        if MOCK_CAPABILITIES.contains(&name) {
+            return Some(vec![self.enabled.contains(&name) as GLint]);
+        }
+        if let Some(&(target, _)) = MOCK_BUFFER_TARGETS
+            .iter()
+            .find(|t| t.1 == name && t.0 != t.1)
+        {
+            return self.buffer_binding(target).map(|b| vec![b as GLint]);
+        }
+        if let Some(&(target, _)) = MOCK_TEXTURE_TARGETS.iter().find(|t| t.1 == name) {
+            return Some(vec![self.texture_binding(target) as GLint]);
+        }
+        Some(vec![match name {
+            ffi::ACTIVE_TEXTURE => (ffi::TEXTURE0 + self.active_texture) as GLint,
+            ffi::CURRENT_PROGRAM => self.program as GLint,
+            ffi::VERTEX_ARRAY_BINDING => self.vertex_array as GLint,
+            ffi::DRAW_FRAMEBUFFER_BINDING => self.draw_framebuffer as GLint,
+            ffi::READ_FRAMEBUFFER_BINDING => self.read_framebuffer as GLint,
+            ffi::RENDERBUFFER_BINDING => self.renderbuffer as GLint,
+            ffi::DEBUG_GROUP_STACK_DEPTH => self.debug_groups.len() as GLint + 1,
+            _ => {
+                return self
+                    .floats
+                    .get(&name)
+                    .map(|v| v.iter().map(|f| *f as GLint).collect())
+            }
+        }])
+    }
+
+    fn float(&mut self, name: GLenum) -> Option<Vec<GLfloat>> {
+        match self.floats.get(&name) {
+            Some(value) => Some(value.clone()),
+            None => self
+                .integer(name)
+                .map(|v| v.iter().map(|i| *i as GLfloat).collect()),
+        }
+    }
+
+    fn indexed_integer(&mut self, name: GLenum, index: GLuint) -> Option<GLint> {
+        let target = match name {
+            ffi::UNIFORM_BUFFER_BINDING => ffi::UNIFORM_BUFFER,
+            ffi::SHADER_STORAGE_BUFFER_BINDING => ffi::SHADER_STORAGE_BUFFER,
+            ffi::TRANSFORM_FEEDBACK_BUFFER_BINDING => ffi::TRANSFORM_FEEDBACK_BUFFER,
+            _ => {
+                self.error(ffi::INVALID_ENUM);
+                return None;
+            }
+        };
+        if index >= MOCK_MAX_BUFFER_BINDINGS {
+            self.error(ffi::INVALID_VALUE);
+            return None;
+        }
+        Some(
+            self.indexed_buffer_bindings
+                .get(&(target, index))
+                .cloned()
+                .unwrap_or(0) as GLint,
+        )
+    }
+}
+
+/// The bytes `offset..offset + size` of something `len` bytes long, if they
+/// are all within it.
+fn mock_range(offset: isize, size: isize, len: usize) -> Option<Range<usize>> {
+    if offset < 0 || size < 0 {
+        return None;
+    }
+    let end = (offset as usize).checked_add(size as usize)?;
+    Some(offset as usize..end).filter(|_| end <= len)
+}
+
+fn mock_fill<T: Copy>(result: &mut [T], values: &[T]) {
+    for (dst, src) in result.iter_mut().zip(values) {
+        *dst = *src;
+    }
+}
+
+/// A headless `Gl` implementation for tests. It hands out object names,
+/// tracks bindings, capabilities, blend/depth/stencil state, buffer contents
+/// and texture storage, and reports errors through `get_error` the way a
+/// driver would. Nothing is ever rasterized and pixel reads return zeros.
+pub struct MockGl {
+    gl_type: GlType,
+    state: RefCell<MockState>,
+    capabilities: GlCapabilities,
+}
+
+impl MockGl {
+    pub fn new(gl_type: GlType) -> Rc<MockGl> {
+        Rc::new(MockGl::with_type(gl_type))
+    }
+
+    fn with_type(gl_type: GlType) -> MockGl {
+        let mut gl = MockGl {
+            gl_type,
+            state: RefCell::new(MockState::new(gl_type)),
+            capabilities: GlCapabilities::default(),
+        };
+        let loaded = GL_METHOD_NAMES.iter().cloned().collect();
+        gl.capabilities = GlCapabilities::query(&gl, loaded);
+        gl
+    }
+
+    /// Reports the error of querying a resource of `program`, which never
+    /// exists.
+    fn check_program_resource(&self, program: GLuint, program_interface: GLenum) {
+        let mut state = self.state.borrow_mut();
+        if state.program(program).is_none() {
+            return;
+        }
+        match program_interface {
+            ffi::SHADER_STORAGE_BLOCK | ffi::BUFFER_VARIABLE => state.error(ffi::INVALID_VALUE),
+            _ => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    fn set_integers(&self, name: GLenum, values: &[GLint]) {
+        self.state
+            .borrow_mut()
+            .integers
+            .insert(name, values.to_vec());
+    }
+
+    fn set_floats(&self, name: GLenum, values: &[GLfloat]) {
+        self.state.borrow_mut().floats.insert(name, values.to_vec());
+    }
+
+    fn error(&self, error: GLenum) {
+        self.state.borrow_mut().error(error);
+    }
+
+    /// Passes the messages logged since the last call to the debug callback.
+    /// It's called once the state isn't borrowed, so that the callback may
+    /// use the context.
+    fn deliver_debug_messages(&self) {
+        let (callback, messages) = {
+            let mut state = self.state.borrow_mut();
+            match state.debug_callback.clone() {
+                Some(callback) => (callback, mem::take(&mut state.debug_callback_messages)),
+                None => return,
+            }
+        };
+        for message in &messages {
+            callback(message);
+        }
+    }
+
+    /// Returns the `PACK_*` or `UNPACK_*` pixel store state.
+    fn pixel_store(&self, pack: bool, three_d: bool) -> PixelStore {
+        let state = self.state.borrow();
+        let get = |pack_name, unpack_name| {
+            let name = if pack { pack_name } else { unpack_name };
+            state.integers.get(&name).map_or(0, |value| value[0])
+        };
+        PixelStore {
+            alignment: get(ffi::PACK_ALIGNMENT, ffi::UNPACK_ALIGNMENT),
+            row_length: get(ffi::PACK_ROW_LENGTH, ffi::UNPACK_ROW_LENGTH),
+            image_height: if three_d {
+                get(0, ffi::UNPACK_IMAGE_HEIGHT)
+            } else {
+                0
+            },
+            skip_pixels: get(ffi::PACK_SKIP_PIXELS, ffi::UNPACK_SKIP_PIXELS),
+            skip_rows: get(ffi::PACK_SKIP_ROWS, ffi::UNPACK_SKIP_ROWS),
+            skip_images: if three_d {
+                get(0, ffi::UNPACK_SKIP_IMAGES)
+            } else {
+                0
+            },
+        }
+    }
+
+    /// Returns the bytes a transfer to or from a pixel buffer accesses,
+    /// flagging `INVALID_ENUM` if the format and type don't go together.
+    fn buffer_transfer_size(
+        &self,
+        (pack, three_d): (bool, bool),
+        size: (GLsizei, GLsizei, GLsizei),
+        format: GLenum,
+        ty: GLenum,
+    ) -> Option<usize> {
+        let store = self.pixel_store(pack, three_d);
+        match store.image_size(size, format, ty) {
+            Ok(required) => Some(required),
+            Err(_) => {
+                self.error(ffi::INVALID_ENUM);
+                None
+            }
+        }
+    }
+
+    fn gen_vertex_array_names(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        for &name in &names {
+            state.vertex_arrays.insert(name, MockVertexArray::default());
+        }
+        names
+    }
+
+    fn delete_vertex_array_names(&self, vertex_arrays: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for &name in vertex_arrays {
+            if name != 0
+                && state.vertex_arrays.remove(&name).is_some()
+                && state.vertex_array == name
+            {
+                state.vertex_array = 0;
+            }
+        }
+    }
+
+    fn bind_vertex_array_name(&self, vao: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if !state.vertex_arrays.contains_key(&vao) {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        state.vertex_array = vao;
+    }
+
+    fn upload_buffer(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        immutable: bool,
+        usage: GLenum,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if size < 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        let error = match state.bound_buffer(target) {
+            Some(ref buffer) if buffer.immutable => ffi::INVALID_OPERATION,
+            Some(buffer) => {
+                buffer.data = if data.is_null() {
+                    vec![0; size as usize]
+                } else {
+                    unsafe { slice::from_raw_parts(data as *const u8, size as usize).to_vec() }
+                };
+                buffer.usage = usage;
+                buffer.immutable = immutable;
+                buffer.mapped = None;
+                return;
+            }
+            None => return,
+        };
+        state.error(error);
+    }
+
+    fn draw(&self, count: GLsizei, primcount: GLsizei, indexed: bool) {
+        let mut state = self.state.borrow_mut();
+        if !state.check_draw(count, primcount) {
+            return;
+        }
+        if indexed && state.buffer_binding(ffi::ELEMENT_ARRAY_BUFFER) == Some(0) {
+            state.error(ffi::INVALID_OPERATION);
+        }
+    }
+
+    fn get_uniform_values(&self, program: GLuint, location: GLint) -> Option<Vec<f64>> {
+        let mut state = self.state.borrow_mut();
+        let values = match state.linked_program(program) {
+            Some(program) => program.uniform_values.get(&location).cloned(),
+            None => return None,
+        };
+        if values.is_none() {
+            state.error(ffi::INVALID_OPERATION);
+        }
+        values
+    }
+}
+
+impl Gl for MockGl {
+    fn capabilities(&self) -> &GlCapabilities {
+        &self.capabilities
+    }
+
+    fn get_type(&self) -> GlType {
+        self.gl_type
+    }
+
+    fn buffer_data_untyped(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        usage: GLenum,
+    ) {
+        self.upload_buffer(target, size, data, false, usage);
+    }
+
+    fn buffer_sub_data_untyped(
+        &self,
+        target: GLenum,
+        offset: isize,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let error = match state.bound_buffer(target) {
+            Some(ref buffer) if buffer.mapped.is_some() => ffi::INVALID_OPERATION,
+            Some(buffer) => match mock_range(offset, size, buffer.data.len()) {
+                Some(range) => {
+                    if !range.is_empty() {
+                        let src = unsafe { slice::from_raw_parts(data as *const u8, range.len()) };
+                        buffer.data[range].copy_from_slice(src);
+                    }
+                    return;
+                }
+                None => ffi::INVALID_VALUE,
+            },
+            None => return,
+        };
+        state.error(error);
+    }
+
+    fn map_buffer(&self, target: GLenum, access: GLbitfield) -> *mut c_void {
+        let len = {
+            let mut state = self.state.borrow_mut();
+            match state.bound_buffer(target) {
+                Some(buffer) => buffer.data.len(),
+                None => return ptr::null_mut(),
+            }
+        };
+        let access = match access {
+            ffi::READ_ONLY => ffi::MAP_READ_BIT,
+            ffi::WRITE_ONLY => ffi::MAP_WRITE_BIT,
+            _ => ffi::MAP_READ_BIT | ffi::MAP_WRITE_BIT,
+        };
+        self.map_buffer_range(target, 0, len as GLsizeiptr, access)
+    }
+
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: GLintptr,
+        length: GLsizeiptr,
+        access: GLbitfield,
+    ) -> *mut c_void {
+        let mut state = self.state.borrow_mut();
+        let error = match state.bound_buffer(target) {
+            Some(ref buffer) if buffer.mapped.is_some() => ffi::INVALID_OPERATION,
+            Some(buffer) => {
+                if length <= 0 || mock_range(offset, length, buffer.data.len()).is_none() {
+                    ffi::INVALID_VALUE
+                } else if access & (ffi::MAP_READ_BIT | ffi::MAP_WRITE_BIT) == 0 {
+                    ffi::INVALID_OPERATION
+                } else {
+                    buffer.mapped = Some((offset as usize, length as usize, access));
+                    return unsafe { buffer.data.as_mut_ptr().offset(offset) as *mut c_void };
+                }
+            }
+            None => return ptr::null_mut(),
+        };
+        state.error(error);
+        ptr::null_mut()
+    }
+
+    fn unmap_buffer(&self, target: GLenum) -> GLboolean {
+        let mut state = self.state.borrow_mut();
+        let was_mapped = match state.bound_buffer(target) {
+            Some(buffer) => buffer.mapped.take().is_some(),
+            None => return ffi::FALSE,
+        };
+        if !was_mapped {
+            state.error(ffi::INVALID_OPERATION);
+            return ffi::FALSE;
+        }
+        ffi::TRUE
+    }
+
+    fn tex_buffer(&self, target: GLenum, _internal_format: GLenum, buffer: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::TEXTURE_BUFFER {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if buffer != 0 && !state.buffers.contains_key(&buffer) {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        state.bound_texture(target);
+    }
+
+    fn shader_source(&self, shader: GLuint, strings: &[&[u8]]) {
+        let mut state = self.state.borrow_mut();
+        if let Some(shader) = state.shader(shader) {
+            shader.source = strings
+                .iter()
+                .map(|s| String::from_utf8_lossy(s))
+                .collect::<Vec<_>>()
+                .concat();
+        }
+    }
+
+    fn read_buffer(&self, mode: GLenum) {
+        self.set_integers(ffi::READ_BUFFER, &[mode as GLint]);
+    }
+
+    fn read_pixels_into_buffer(
+        &self,
+        _x: GLint,
+        _y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+        dst_buffer: &mut [u8],
+    ) -> Result<(), PixelDataError> {
+        // Like the real backends, read rows without alignment padding.
+        self.set_integers(ffi::PACK_ALIGNMENT, &[1]);
+        let store = self.pixel_store(true, false);
+        let required = store.image_size((width, height, 1), format, pixel_type)?;
+        store.check((width, height, 1), format, pixel_type, dst_buffer.len())?;
+        let mut state = self.state.borrow_mut();
+        if !state.check_read_framebuffer() {
+            return Ok(());
+        }
+        for byte in &mut dst_buffer[..required] {
+            *byte = 0;
+        }
+        Ok(())
+    }
+
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+    ) -> Result<Vec<u8>, PixelDataError> {
+        let store = PixelStore {
+            alignment: 1,
+            ..self.pixel_store(true, false)
+        };
+        let mut pixels = vec![0; store.image_size((width, height, 1), format, pixel_type)?];
+        self.read_pixels_into_buffer(x, y, width, height, format, pixel_type, &mut pixels)?;
+        Ok(pixels)
+    }
+
+    unsafe fn read_pixels_into_pbo(
+        &self,
+        _x: GLint,
+        _y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+    ) {
+        let required = match self.buffer_transfer_size(
+            (true, false),
+            (width, height, 1),
+            format,
+            pixel_type,
+        ) {
+            Some(required) => required,
+            None => return,
+        };
+        let mut state = self.state.borrow_mut();
+        let available = match state.bound_buffer(ffi::PIXEL_PACK_BUFFER) {
+            Some(buffer) => buffer.data.len(),
+            None => return,
+        };
+        state.check_pixel_transfer(required, available);
+    }
+
+    fn sample_coverage(&self, value: GLclampf, invert: bool) {
+        self.set_floats(ffi::SAMPLE_COVERAGE_VALUE, &[value.clamp(0.0, 1.0)]);
+        self.set_integers(ffi::SAMPLE_COVERAGE_INVERT, &[invert as GLint]);
+    }
+
+    fn polygon_offset(&self, factor: GLfloat, units: GLfloat) {
+        self.set_floats(ffi::POLYGON_OFFSET_FACTOR, &[factor]);
+        self.set_floats(ffi::POLYGON_OFFSET_UNITS, &[units]);
+    }
+
+    fn pixel_store_i(&self, name: GLenum, param: GLint) {
+        if !MOCK_PIXEL_STORE_PARAMS.contains(&name) {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        let valid = match name {
+            ffi::PACK_ALIGNMENT | ffi::UNPACK_ALIGNMENT => [1, 2, 4, 8].contains(&param),
+            _ => param >= 0,
+        };
+        if !valid {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.set_integers(name, &[param]);
+    }
+
+    fn gen_buffers(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        for &name in &names {
+            state.buffers.insert(name, MockBuffer::default());
+        }
+        names
+    }
+
+    fn gen_renderbuffers(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        for &name in &names {
+            state
+                .renderbuffers
+                .insert(name, MockRenderbuffer::default());
+        }
+        names
+    }
+
+    fn gen_framebuffers(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        for &name in &names {
+            state.framebuffers.insert(name, MockFramebuffer::default());
+        }
+        names
+    }
+
+    fn gen_textures(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        for &name in &names {
+            state.textures.insert(name, MockTexture::default());
+        }
+        names
+    }
+
+    fn gen_vertex_arrays(&self, n: GLsizei) -> Vec<GLuint> {
+        self.gen_vertex_array_names(n)
+    }
+
+    fn gen_vertex_arrays_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        self.gen_vertex_array_names(n)
+    }
+
+    fn gen_queries(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        for &name in &names {
+            state.queries.insert(name, None);
+        }
+        names
+    }
+
+    fn begin_query(&self, target: GLenum, id: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if !MOCK_QUERY_TARGETS.contains(&target) {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        let query_target = state.queries.get(&id).cloned();
+        let active = state.active_queries.values().any(|&q| q == id);
+        match query_target {
+            Some(Some(t)) if t != target => state.error(ffi::INVALID_OPERATION),
+            Some(_) if !active && !state.active_queries.contains_key(&target) => {
+                state.queries.insert(id, Some(target));
+                state.active_queries.insert(target, id);
+            }
+            _ => state.error(ffi::INVALID_OPERATION),
+        }
+    }
+
+    fn end_query(&self, target: GLenum) {
+        let mut state = self.state.borrow_mut();
+        if state.active_queries.remove(&target).is_none() {
+            state.error(ffi::INVALID_OPERATION);
+        }
+    }
+
+    fn query_counter(&self, id: GLuint, target: GLenum) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::TIMESTAMP {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        let active = state.active_queries.values().any(|&q| q == id);
+        match state.queries.get(&id).cloned() {
+            Some(Some(t)) if t != target => state.error(ffi::INVALID_OPERATION),
+            Some(_) if !active => {
+                state.queries.insert(id, Some(target));
+            }
+            _ => state.error(ffi::INVALID_OPERATION),
+        }
+    }
+
+    fn get_query_iv(&self, target: GLenum, pname: GLenum) -> i32 {
+        let mut state = self.state.borrow_mut();
+        match pname {
+            ffi::QUERY_COUNTER_BITS if target == ffi::TIMESTAMP || target == ffi::TIME_ELAPSED => 64,
+            ffi::QUERY_COUNTER_BITS if MOCK_QUERY_TARGETS.contains(&target) => 32,
+            ffi::CURRENT_QUERY if MOCK_QUERY_TARGETS.contains(&target) => {
+                state.active_queries.get(&target).map_or(0, |&id| id as GLint)
+            }
+            _ => {
+                state.error(ffi::INVALID_ENUM);
+                0
+            }
+        }
+    }
+
+    fn get_query_object_iv(&self, id: GLuint, pname: GLenum) -> i32 {
+        self.get_query_object_ui64v(id, pname) as i32
+    }
+
+    fn get_query_object_uiv(&self, id: GLuint, pname: GLenum) -> u32 {
+        self.get_query_object_ui64v(id, pname) as u32
+    }
+
+    fn get_query_object_i64v(&self, id: GLuint, pname: GLenum) -> i64 {
+        self.get_query_object_ui64v(id, pname) as i64
+    }
+
+    fn get_query_object_ui64v(&self, id: GLuint, pname: GLenum) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let active = state.active_queries.values().any(|&q| q == id);
+        if active || state.queries.get(&id).is_none_or(|t| t.is_none()) {
+            state.error(ffi::INVALID_OPERATION);
+            return 0;
+        }
+        match pname {
+            ffi::QUERY_RESULT_AVAILABLE => 1,
+            ffi::QUERY_RESULT => 0,
+            _ => {
+                state.error(ffi::INVALID_ENUM);
+                0
+            }
+        }
+    }
+
+    fn delete_queries(&self, queries: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for query in queries {
+            state.queries.remove(query);
+            state.active_queries.retain(|_, q| q != query);
+        }
+    }
+
+    fn delete_vertex_arrays(&self, vertex_arrays: &[GLuint]) {
+        self.delete_vertex_array_names(vertex_arrays)
+    }
+
+    fn delete_vertex_arrays_apple(&self, vertex_arrays: &[GLuint]) {
+        self.delete_vertex_array_names(vertex_arrays)
+    }
+
+    fn delete_buffers(&self, buffers: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for &name in buffers {
+            if name == 0 || state.buffers.remove(&name).is_none() {
+                continue;
+            }
+            state.buffer_bindings.retain(|_, b| *b != name);
+            state.indexed_buffer_bindings.retain(|_, b| *b != name);
+            let vao = state.vertex_array;
+            let vao = state.vertex_arrays.get_mut(&vao).unwrap();
+            if vao.element_buffer == name {
+                vao.element_buffer = 0;
+            }
+            for attrib in vao.attribs.values_mut() {
+                if attrib.buffer == name {
+                    attrib.buffer = 0;
+                }
+            }
+        }
+    }
+
+    fn delete_renderbuffers(&self, renderbuffers: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for &name in renderbuffers {
+            if name == 0 || state.renderbuffers.remove(&name).is_none() {
+                continue;
+            }
+            if state.renderbuffer == name {
+                state.renderbuffer = 0;
+            }
+            state.detach_everywhere(ffi::RENDERBUFFER, name);
+        }
+    }
+
+    fn delete_framebuffers(&self, framebuffers: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for &name in framebuffers {
+            if name == 0 || state.framebuffers.remove(&name).is_none() {
+                continue;
+            }
+            if state.draw_framebuffer == name {
+                state.draw_framebuffer = 0;
+            }
+            if state.read_framebuffer == name {
+                state.read_framebuffer = 0;
+            }
+        }
+    }
+
+    fn delete_textures(&self, textures: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for &name in textures {
+            if name == 0 || state.textures.remove(&name).is_none() {
+                continue;
+            }
+            state.texture_bindings.retain(|_, t| *t != name);
+            state.detach_everywhere(ffi::TEXTURE, name);
+        }
+    }
+
+    fn framebuffer_renderbuffer(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if renderbuffertarget != ffi::RENDERBUFFER {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if renderbuffer != 0 && !state.renderbuffers.contains_key(&renderbuffer) {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        let value = if renderbuffer == 0 {
+            None
+        } else {
+            Some(MockAttachment {
+                object_type: ffi::RENDERBUFFER,
+                name: renderbuffer,
+                target: ffi::RENDERBUFFER,
+                level: 0,
+                layer: 0,
+            })
+        };
+        state.attach(target, attachment, value);
+    }
+
+    fn renderbuffer_storage(
+        &self,
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::RENDERBUFFER {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if width < 0
+            || height < 0
+            || width > MOCK_MAX_TEXTURE_SIZE
+            || height > MOCK_MAX_TEXTURE_SIZE
+        {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        let renderbuffer = state.renderbuffer;
+        match state.renderbuffers.get_mut(&renderbuffer) {
+            Some(renderbuffer) => {
+                renderbuffer.internal_format = internalformat;
+                renderbuffer.width = width;
+                renderbuffer.height = height;
+            }
+            None => state.error(ffi::INVALID_OPERATION),
+        }
+    }
+
+    fn depth_func(&self, func: GLenum) {
+        if !(ffi::NEVER..=ffi::ALWAYS).contains(&func) {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        self.set_integers(ffi::DEPTH_FUNC, &[func as GLint]);
+    }
+
+    fn active_texture(&self, texture: GLenum) {
+        let mut state = self.state.borrow_mut();
+        if !(ffi::TEXTURE0..ffi::TEXTURE0 + MOCK_MAX_TEXTURE_UNITS).contains(&texture) {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        state.active_texture = texture - ffi::TEXTURE0;
+    }
+
+    fn attach_shader(&self, program: GLuint, shader: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if state.shader(shader).is_none() {
+            return;
+        }
+        let error = match state.program(program) {
+            Some(ref program) if program.shaders.contains(&shader) => ffi::INVALID_OPERATION,
+            Some(program) => {
+                program.shaders.push(shader);
+                return;
+            }
+            None => return,
+        };
+        state.error(error);
+    }
+
+    fn bind_attrib_location(&self, program: GLuint, index: GLuint, name: &str) {
+        let mut state = self.state.borrow_mut();
+        if index >= MOCK_MAX_VERTEX_ATTRIBS {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if name.starts_with("gl_") {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        if let Some(program) = state.program(program) {
+            program.attrib_bindings.insert(name.to_string(), index);
+        }
+    }
+
+    unsafe fn get_uniform_iv(&self, program: GLuint, location: GLint, result: &mut [GLint]) {
+        if let Some(values) = self.get_uniform_values(program, location) {
+            let values: Vec<GLint> = values.iter().map(|v| *v as GLint).collect();
+            mock_fill(result, &values);
+        }
+    }
+
+    unsafe fn get_uniform_fv(&self, program: GLuint, location: GLint, result: &mut [GLfloat]) {
+        if let Some(values) = self.get_uniform_values(program, location) {
+            let values: Vec<GLfloat> = values.iter().map(|v| *v as GLfloat).collect();
+            mock_fill(result, &values);
+        }
+    }
+
+    fn get_uniform_block_index(&self, program: GLuint, name: &str) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        match state.program(program) {
+            Some(program) => program.uniform_block_index(name),
+            None => ffi::INVALID_INDEX,
+        }
+    }
+
+    fn get_uniform_indices(&self, program: GLuint, names: &[&str]) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        match state.program(program) {
+            Some(program) => names
+                .iter()
+                .map(|name| program.uniform_index(name))
+                .collect(),
+            None => vec![ffi::INVALID_INDEX; names.len()],
+        }
+    }
+
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, buffer: GLuint) {
+        self.bind_buffer_range(target, index, buffer, 0, 0);
+    }
+
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        size: GLsizeiptr,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::UNIFORM_BUFFER
+            && target != ffi::SHADER_STORAGE_BUFFER
+            && target != ffi::TRANSFORM_FEEDBACK_BUFFER
+        {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if index >= MOCK_MAX_BUFFER_BINDINGS || offset < 0 || size < 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if buffer != 0 && !state.buffers.contains_key(&buffer) {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        state
+            .indexed_buffer_bindings
+            .insert((target, index), buffer);
+        state.buffer_bindings.insert(target, buffer);
+    }
+
+    fn uniform_block_binding(
+        &self,
+        program: GLuint,
+        uniform_block_index: GLuint,
+        uniform_block_binding: GLuint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if uniform_block_binding >= MOCK_MAX_BUFFER_BINDINGS {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        let valid = match state.program(program) {
+            Some(program) => match program.uniform_blocks.get_mut(uniform_block_index as usize) {
+                Some(block) => {
+                    block.1 = uniform_block_binding;
+                    true
+                }
+                None => false,
+            },
+            None => return,
+        };
+        if !valid {
+            state.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn bind_buffer(&self, target: GLenum, buffer: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if state.buffer_binding(target).is_none() {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if buffer != 0 && !state.buffers.contains_key(&buffer) {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        if target == ffi::ELEMENT_ARRAY_BUFFER {
+            let vao = state.vertex_array;
+            if let Some(vao) = state.vertex_arrays.get_mut(&vao) {
+                vao.element_buffer = buffer;
+            }
+        } else {
+            state.buffer_bindings.insert(target, buffer);
+        }
+    }
+
+    fn bind_vertex_array(&self, vao: GLuint) {
+        self.bind_vertex_array_name(vao)
+    }
+
+    fn bind_vertex_array_apple(&self, vao: GLuint) {
+        self.bind_vertex_array_name(vao)
+    }
+
+    fn bind_renderbuffer(&self, target: GLenum, renderbuffer: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::RENDERBUFFER {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if renderbuffer != 0 {
+            match state.renderbuffers.get_mut(&renderbuffer) {
+                Some(r) => r.bound = true,
+                None => return state.error(ffi::INVALID_OPERATION),
+            }
+        }
+        state.renderbuffer = renderbuffer;
+    }
+
+    fn bind_framebuffer(&self, target: GLenum, framebuffer: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if state.framebuffer_binding(target).is_none() {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if framebuffer != 0 {
+            match state.framebuffers.get_mut(&framebuffer) {
+                Some(f) => f.bound = true,
+                None => return state.error(ffi::INVALID_OPERATION),
+            }
+        }
+        if target != ffi::READ_FRAMEBUFFER {
+            state.draw_framebuffer = framebuffer;
+        }
+        if target != ffi::DRAW_FRAMEBUFFER {
+            state.read_framebuffer = framebuffer;
+        }
+    }
+
+    fn bind_texture(&self, target: GLenum, texture: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if !MOCK_TEXTURE_TARGETS.iter().any(|t| t.0 == target) {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if texture != 0 {
+            let error = match state.textures.get_mut(&texture) {
+                Some(ref t) if t.target.is_some_and(|t| t != target) => ffi::INVALID_OPERATION,
+                Some(t) => {
+                    t.target = Some(target);
+                    ffi::NO_ERROR
+                }
+                None => ffi::INVALID_OPERATION,
+            };
+            if error != ffi::NO_ERROR {
+                return state.error(error);
+            }
+        }
+        let unit = state.active_texture;
+        state.texture_bindings.insert((unit, target), texture);
+    }
+
+    fn bind_vertex_buffer(
+        &self,
+        binding_index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        stride: GLint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if binding_index >= MOCK_MAX_VERTEX_ATTRIBS || offset < 0 || stride < 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if buffer != 0 && !state.buffers.contains_key(&buffer) {
+            state.error(ffi::INVALID_OPERATION);
+        }
+    }
+
+    fn draw_buffers(&self, bufs: &[GLenum]) {
+        if bufs.len() > MOCK_MAX_COLOR_ATTACHMENTS as usize {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        for (i, &buf) in bufs.iter().enumerate() {
+            self.set_integers(ffi::DRAW_BUFFER0 + i as GLenum, &[buf as GLint]);
+        }
+    }
+
+    fn tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ty: GLenum,
+        opt_data: Option<&[u8]>,
+    ) -> Result<(), PixelDataError> {
+        if let Some(data) = opt_data {
+            self.pixel_store(false, false)
+                .check((width, height, 1), format, ty, data.len())?;
+        }
+        let mut state = self.state.borrow_mut();
+        if target == ffi::TEXTURE_3D
+            || target == ffi::TEXTURE_2D_ARRAY
+            || target == ffi::TEXTURE_CUBE_MAP
+        {
+            state.error(ffi::INVALID_ENUM);
+            return Ok(());
+        }
+        state.define_level(
+            target,
+            level,
+            internal_format as GLenum,
+            (width, height, 1),
+            border,
+        );
+        Ok(())
+    }
+
+    fn compressed_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        _data: &[u8],
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.define_level(target, level, internal_format, (width, height, 1), border);
+    }
+
+    fn compressed_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        _data: &[u8],
+    ) {
+        let mut state = self.state.borrow_mut();
+        if !state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1)) {
+            return;
+        }
+        let defined_format = state
+            .bound_texture(target)
+            .and_then(|t| t.level(target, level))
+            .map(|l| l.internal_format);
+        if defined_format != Some(format) {
+            state.error(ffi::INVALID_OPERATION);
+        }
+    }
+
+    fn tex_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ty: GLenum,
+        opt_data: Option<&[u8]>,
+    ) -> Result<(), PixelDataError> {
+        if let Some(data) = opt_data {
+            self.pixel_store(false, true)
+                .check((width, height, depth), format, ty, data.len())?;
+        }
+        let mut state = self.state.borrow_mut();
+        if target != ffi::TEXTURE_3D && target != ffi::TEXTURE_2D_ARRAY {
+            state.error(ffi::INVALID_ENUM);
+            return Ok(());
+        }
+        state.define_level(
+            target,
+            level,
+            internal_format as GLenum,
+            (width, height, depth),
+            border,
+        );
+        Ok(())
+    }
+
+    fn copy_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        _x: GLint,
+        _y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if !state.check_read_framebuffer() {
+            return;
+        }
+        state.define_level(target, level, internal_format, (width, height, 1), border);
+    }
+
+    fn copy_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        _x: GLint,
+        _y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if !state.check_read_framebuffer() {
+            return;
+        }
+        state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1));
+    }
+
+    fn copy_tex_sub_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        _x: GLint,
+        _y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if !state.check_read_framebuffer() {
+            return;
+        }
+        state.check_sub_image(
+            target,
+            level,
+            (xoffset, yoffset, zoffset),
+            (width, height, 1),
+        );
+    }
+
+    fn tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        data: &[u8],
+    ) -> Result<(), PixelDataError> {
+        self.pixel_store(false, false)
+            .check((width, height, 1), format, ty, data.len())?;
+        let mut state = self.state.borrow_mut();
+        state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1));
+        Ok(())
+    }
+
+    fn tex_sub_image_2d_pbo(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        offset: usize,
+    ) {
+        let required =
+            match self.buffer_transfer_size((false, false), (width, height, 1), format, ty) {
+                Some(required) => required,
+                None => return,
+            };
+        let mut state = self.state.borrow_mut();
+        let available = match state.bound_buffer(ffi::PIXEL_UNPACK_BUFFER) {
+            Some(buffer) => buffer.data.len().saturating_sub(offset),
+            None => return,
+        };
+        if state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1)) {
+            state.check_pixel_transfer(required, available);
+        }
+    }
+
+    fn tex_sub_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        data: &[u8],
+    ) -> Result<(), PixelDataError> {
+        self.pixel_store(false, true)
+            .check((width, height, depth), format, ty, data.len())?;
+        let mut state = self.state.borrow_mut();
+        state.check_sub_image(
+            target,
+            level,
+            (xoffset, yoffset, zoffset),
+            (width, height, depth),
+        );
+        Ok(())
+    }
+
+    fn tex_sub_image_3d_pbo(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        offset: usize,
+    ) {
+        let required =
+            match self.buffer_transfer_size((false, true), (width, height, depth), format, ty) {
+                Some(required) => required,
+                None => return,
+            };
+        let mut state = self.state.borrow_mut();
+        let available = match state.bound_buffer(ffi::PIXEL_UNPACK_BUFFER) {
+            Some(buffer) => buffer.data.len().saturating_sub(offset),
+            None => return,
+        };
+        if state.check_sub_image(
+            target,
+            level,
+            (xoffset, yoffset, zoffset),
+            (width, height, depth),
+        ) {
+            state.check_pixel_transfer(required, available);
+        }
+    }
+
+    fn tex_storage_2d(
+        &self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::TEXTURE_2D
+            && target != ffi::TEXTURE_CUBE_MAP
+            && target != ffi::TEXTURE_RECTANGLE
+        {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        state.define_storage(target, levels, internal_format, width, height, 1);
+    }
+
+    fn tex_storage_3d(
+        &self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::TEXTURE_3D && target != ffi::TEXTURE_2D_ARRAY {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        state.define_storage(target, levels, internal_format, width, height, depth);
+    }
+
+    fn get_tex_image_into_buffer(
+        &self,
+        target: GLenum,
+        level: GLint,
+        format: GLenum,
+        ty: GLenum,
+        output: &mut [u8],
+    ) -> Result<(), PixelDataError> {
+        let store = self.pixel_store(true, true);
+        let mut state = self.state.borrow_mut();
+        let level = match state.bound_texture(target) {
+            Some(texture) => texture.level(target, level),
+            None => return Ok(()),
+        };
+        let level = match level {
+            Some(level) => level,
+            None => {
+                state.error(ffi::INVALID_OPERATION);
+                return Ok(());
+            }
+        };
+        let size = (level.width, level.height, level.depth);
+        let required = store.image_size(size, format, ty)?;
+        store.check(size, format, ty, output.len())?;
+        for byte in &mut output[..required] {
+            *byte = 0;
+        }
+        Ok(())
+    }
+
+    unsafe fn copy_image_sub_data(
+        &self,
+        src_name: GLuint,
+        src_target: GLenum,
+        src_level: GLint,
+        src_x: GLint,
+        src_y: GLint,
+        src_z: GLint,
+        dst_name: GLuint,
+        dst_target: GLenum,
+        dst_level: GLint,
+        dst_x: GLint,
+        dst_y: GLint,
+        dst_z: GLint,
+        src_width: GLsizei,
+        src_height: GLsizei,
+        src_depth: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let fits = |state: &MockState,
+                    name: GLuint,
+                    target: GLenum,
+                    level: GLint,
+                    x: GLint,
+                    y: GLint,
+                    z: GLint| {
+            let level = if target == ffi::RENDERBUFFER {
+                state.renderbuffers.get(&name).map(|r| MockLevel {
+                    internal_format: r.internal_format,
+                    width: r.width,
+                    height: r.height,
+                    depth: 1,
+                })
+            } else {
+                let target = if target == ffi::TEXTURE_CUBE_MAP {
+                    ffi::TEXTURE_CUBE_MAP_POSITIVE_X + z as GLenum
+                } else {
+                    target
+                };
+                state
+                    .textures
+                    .get(&name)
+                    .and_then(|t| t.level(target, level))
+            };
+            match level {
+                None => Err(ffi::INVALID_VALUE),
+                Some(l)
+                    if x < 0
+                        || y < 0
+                        || z < 0
+                        || x + src_width > l.width
+                        || y + src_height > l.height
+                        || (target != ffi::TEXTURE_CUBE_MAP && z + src_depth > l.depth) =>
+                {
+                    Err(ffi::INVALID_VALUE)
+                }
+                Some(_) => Ok(()),
+            }
+        };
+        let result = fits(&state, src_name, src_target, src_level, src_x, src_y, src_z)
+            .and_then(|_| fits(&state, dst_name, dst_target, dst_level, dst_x, dst_y, dst_z));
+        if let Err(error) = result {
+            state.error(error);
+        }
+    }
+
+    fn invalidate_framebuffer(&self, target: GLenum, _attachments: &[GLenum]) {
+        let mut state = self.state.borrow_mut();
+        if state.framebuffer_binding(target).is_none() {
+            state.error(ffi::INVALID_ENUM);
+        }
+    }
+
+    fn invalidate_sub_framebuffer(
+        &self,
+        target: GLenum,
+        attachments: &[GLenum],
+        _xoffset: GLint,
+        _yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        if width < 0 || height < 0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.invalidate_framebuffer(target, attachments);
+    }
+
+    unsafe fn get_integer_v(&self, name: GLenum, result: &mut [GLint]) {
+        let mut state = self.state.borrow_mut();
+        match state.integer(name) {
+            Some(values) => mock_fill(result, &values),
+            None => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    unsafe fn get_integer_64v(&self, name: GLenum, result: &mut [GLint64]) {
+        let mut state = self.state.borrow_mut();
+        match state.integer(name) {
+            Some(values) => {
+                let values: Vec<GLint64> = values.iter().map(|v| *v as GLint64).collect();
+                mock_fill(result, &values);
+            }
+            None => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    unsafe fn get_integer_iv(&self, name: GLenum, index: GLuint, result: &mut [GLint]) {
+        let mut state = self.state.borrow_mut();
+        if let Some(value) = state.indexed_integer(name, index) {
+            mock_fill(result, &[value]);
+        }
+    }
+
+    unsafe fn get_integer_64iv(&self, name: GLenum, index: GLuint, result: &mut [GLint64]) {
+        let mut state = self.state.borrow_mut();
+        if let Some(value) = state.indexed_integer(name, index) {
+            mock_fill(result, &[value as GLint64]);
+        }
+    }
+
+    unsafe fn get_boolean_v(&self, name: GLenum, result: &mut [GLboolean]) {
+        let mut state = self.state.borrow_mut();
+        match state.integer(name) {
+            Some(values) => {
+                let values: Vec<GLboolean> =
+                    values.iter().map(|v| (*v != 0) as GLboolean).collect();
+                mock_fill(result, &values);
+            }
+            None => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    unsafe fn get_float_v(&self, name: GLenum, result: &mut [GLfloat]) {
+        let mut state = self.state.borrow_mut();
+        match state.float(name) {
+            Some(values) => mock_fill(result, &values),
+            None => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    fn get_framebuffer_attachment_parameter_iv(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        let mut state = self.state.borrow_mut();
+        let framebuffer = match state.framebuffer_binding(target) {
+            Some(framebuffer) => framebuffer,
+            None => {
+                state.error(ffi::INVALID_ENUM);
+                return 0;
+            }
+        };
+        let value = state
+            .framebuffers
+            .get(&framebuffer)
+            .and_then(|f| f.attachments.get(&attachment).cloned());
+        match (pname, value) {
+            (ffi::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE, None) => ffi::NONE as GLint,
+            (ffi::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE, Some(a)) => a.object_type as GLint,
+            (ffi::FRAMEBUFFER_ATTACHMENT_OBJECT_NAME, Some(a)) => a.name as GLint,
+            (ffi::FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL, Some(a))
+                if a.object_type == ffi::TEXTURE =>
+            {
+                a.level
+            }
+            (ffi::FRAMEBUFFER_ATTACHMENT_TEXTURE_LAYER, Some(a))
+                if a.object_type == ffi::TEXTURE =>
+            {
+                a.layer
+            }
+            (ffi::FRAMEBUFFER_ATTACHMENT_TEXTURE_CUBE_MAP_FACE, Some(a))
+                if mock_cube_face(a.target) =>
+            {
+                a.target as GLint
+            }
+            _ => {
+                state.error(ffi::INVALID_OPERATION);
+                0
+            }
+        }
+    }
+
+    fn get_renderbuffer_parameter_iv(&self, target: GLenum, pname: GLenum) -> GLint {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::RENDERBUFFER {
+            state.error(ffi::INVALID_ENUM);
+            return 0;
+        }
+        let renderbuffer = state.renderbuffer;
+        let value = match state.renderbuffers.get(&renderbuffer) {
+            Some(r) => match pname {
+                ffi::RENDERBUFFER_WIDTH => Some(r.width),
+                ffi::RENDERBUFFER_HEIGHT => Some(r.height),
+                ffi::RENDERBUFFER_INTERNAL_FORMAT => Some(r.internal_format as GLint),
+                ffi::RENDERBUFFER_SAMPLES => Some(0),
+                _ => None,
+            },
+            None => {
+                state.error(ffi::INVALID_OPERATION);
+                return 0;
+            }
+        };
+        value.unwrap_or_else(|| {
+            state.error(ffi::INVALID_ENUM);
+            0
+        })
+    }
+
+    fn get_tex_parameter_iv(&self, target: GLenum, name: GLenum) -> GLint {
+        self.get_tex_parameter_fv(target, name) as GLint
+    }
+
+    fn get_tex_parameter_fv(&self, target: GLenum, name: GLenum) -> GLfloat {
+        let mut state = self.state.borrow_mut();
+        let value = match state.bound_texture(target) {
+            Some(texture) => texture.param(name),
+            None => return 0.0,
+        };
+        value.unwrap_or_else(|| {
+            state.error(ffi::INVALID_ENUM);
+            0.0
+        })
+    }
+
+    fn tex_parameter_i(&self, target: GLenum, pname: GLenum, param: GLint) {
+        self.tex_parameter_f(target, pname, param as GLfloat);
+    }
+
+    fn tex_parameter_f(&self, target: GLenum, pname: GLenum, param: GLfloat) {
+        let mut state = self.state.borrow_mut();
+        let error = match state.bound_texture(target) {
+            Some(texture) => {
+                if pname == ffi::TEXTURE_IMMUTABLE_FORMAT
+                    || pname == ffi::TEXTURE_IMMUTABLE_LEVELS
+                    || texture.param(pname).is_none()
+                {
+                    ffi::INVALID_ENUM
+                } else {
+                    texture.params.insert(pname, param);
+                    return;
+                }
+            }
+            None => return,
+        };
+        state.error(error);
+    }
+
+    fn framebuffer_texture_2d(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if texture != 0 {
+            let texture_target = state.textures.get(&texture).map(|t| t.target);
+            match texture_target {
+                Some(Some(t)) if t == mock_binding_target(textarget) => {}
+                _ => return state.error(ffi::INVALID_OPERATION),
+            }
+            if level < 0 {
+                return state.error(ffi::INVALID_VALUE);
+            }
+        }
+        let value = if texture == 0 {
+            None
+        } else {
+            Some(MockAttachment {
+                object_type: ffi::TEXTURE,
+                name: texture,
+                target: textarget,
+                level,
+                layer: 0,
+            })
+        };
+        state.attach(target, attachment, value);
+    }
+
+    fn framebuffer_texture_layer(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        texture: GLuint,
+        level: GLint,
+        layer: GLint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let value = if texture == 0 {
+            None
+        } else {
+            let texture_target = state.textures.get(&texture).and_then(|t| t.target);
+            match texture_target {
+                Some(t) if t == ffi::TEXTURE_3D || t == ffi::TEXTURE_2D_ARRAY => {}
+                _ => return state.error(ffi::INVALID_OPERATION),
+            }
+            if level < 0 || layer < 0 {
+                return state.error(ffi::INVALID_VALUE);
+            }
+            Some(MockAttachment {
+                object_type: ffi::TEXTURE,
+                name: texture,
+                target: texture_target.unwrap(),
+                level,
+                layer,
+            })
+        };
+        state.attach(target, attachment, value);
+    }
+
+    fn blit_framebuffer(
+        &self,
+        _src_x0: GLint,
+        _src_y0: GLint,
+        _src_x1: GLint,
+        _src_y1: GLint,
+        _dst_x0: GLint,
+        _dst_y0: GLint,
+        _dst_x1: GLint,
+        _dst_y1: GLint,
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let all = ffi::COLOR_BUFFER_BIT | ffi::DEPTH_BUFFER_BIT | ffi::STENCIL_BUFFER_BIT;
+        if mask & !all != 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if filter != ffi::NEAREST && filter != ffi::LINEAR {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if filter == ffi::LINEAR && mask & !ffi::COLOR_BUFFER_BIT != 0 {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        if !state.check_read_framebuffer() {
+            return;
+        }
+        state.check_draw_framebuffer();
+    }
+
+    fn vertex_attrib_4f(&self, index: GLuint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+        let mut state = self.state.borrow_mut();
+        if index >= MOCK_MAX_VERTEX_ATTRIBS {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        state.current_attribs.insert(index, [x, y, z, w]);
+    }
+
+    fn vertex_attrib_binding(&self, attrib_index: GLuint, binding_index: GLuint) {
+        if attrib_index >= MOCK_MAX_VERTEX_ATTRIBS || binding_index >= MOCK_MAX_VERTEX_ATTRIBS {
+            self.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn vertex_attrib_pointer_f32(
+        &self,
+        index: GLuint,
+        size: GLint,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        self.vertex_attrib_pointer(index, size, ffi::FLOAT, normalized, stride, offset)
+    }
+
+    fn vertex_attrib_pointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if !(1..=4).contains(&size) || stride < 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        state.set_attrib_pointer(
+            index,
+            MockVertexAttrib {
+                size,
+                type_,
+                normalized,
+                stride,
+                offset,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn vertex_attrib_i_pointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if !(1..=4).contains(&size) || stride < 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if type_ == ffi::FLOAT || type_ == ffi::HALF_FLOAT {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        state.set_attrib_pointer(
+            index,
+            MockVertexAttrib {
+                size,
+                type_,
+                integer: true,
+                stride,
+                offset,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn vertex_attrib_divisor(&self, index: GLuint, divisor: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if let Some(attrib) = state.vertex_attrib(index) {
+            attrib.divisor = divisor;
+        }
+    }
+
+    fn vertex_attrib_format(
+        &self,
+        attrib_index: GLuint,
+        size: GLint,
+        _type_: GLenum,
+        _normalized: bool,
+        _relative_offset: GLuint,
+    ) {
+        if attrib_index >= MOCK_MAX_VERTEX_ATTRIBS || !(1..=4).contains(&size) {
+            self.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn vertex_attrib_i_format(
+        &self,
+        attrib_index: GLuint,
+        size: GLint,
+        _type_: GLenum,
+        _relative_offset: GLuint,
+    ) {
+        if attrib_index >= MOCK_MAX_VERTEX_ATTRIBS || !(1..=4).contains(&size) {
+            self.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn vertex_binding_divisor(&self, binding_index: GLuint, _divisor: GLuint) {
+        if binding_index >= MOCK_MAX_VERTEX_ATTRIBS {
+            self.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        if width < 0 || height < 0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.set_integers(ffi::VIEWPORT, &[x, y, width, height]);
+    }
+
+    fn scissor(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        if width < 0 || height < 0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.set_integers(ffi::SCISSOR_BOX, &[x, y, width, height]);
+    }
+
+    fn line_width(&self, width: GLfloat) {
+        if width <= 0.0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.set_floats(ffi::LINE_WIDTH, &[width]);
+    }
+
+    fn use_program(&self, program: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if program != 0 {
+            let error = match state.program(program) {
+                Some(ref p) if !p.linked => ffi::INVALID_OPERATION,
+                Some(_) => ffi::NO_ERROR,
+                None => return,
+            };
+            if error != ffi::NO_ERROR {
+                return state.error(error);
+            }
+        }
+        let previous = mem::replace(&mut state.program, program);
+        state.release_program(previous);
+    }
+
+    fn validate_program(&self, program: GLuint) {
+        self.state.borrow_mut().program(program);
+    }
+
+    fn draw_arrays(&self, _mode: GLenum, first: GLint, count: GLsizei) {
+        if first < 0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.draw(count, 1, false);
+    }
+
+    fn draw_arrays_instanced(
+        &self,
+        _mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        primcount: GLsizei,
+    ) {
+        if first < 0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.draw(count, primcount, false);
+    }
+
+    fn draw_elements(
+        &self,
+        _mode: GLenum,
+        count: GLsizei,
+        _element_type: GLenum,
+        _indices_offset: GLuint,
+    ) {
+        self.draw(count, 1, true);
+    }
+
+    fn draw_elements_instanced(
+        &self,
+        _mode: GLenum,
+        count: GLsizei,
+        _element_type: GLenum,
+        _indices_offset: GLuint,
+        primcount: GLsizei,
+    ) {
+        self.draw(count, primcount, true);
+    }
+
+    fn blend_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.set_floats(ffi::BLEND_COLOR, &[r, g, b, a]);
+    }
+
+    fn blend_func(&self, sfactor: GLenum, dfactor: GLenum) {
+        self.blend_func_separate(sfactor, dfactor, sfactor, dfactor);
+    }
+
+    fn blend_func_separate(
+        &self,
+        src_rgb: GLenum,
+        dest_rgb: GLenum,
+        src_alpha: GLenum,
+        dest_alpha: GLenum,
+    ) {
+        self.set_integers(ffi::BLEND_SRC_RGB, &[src_rgb as GLint]);
+        self.set_integers(ffi::BLEND_DST_RGB, &[dest_rgb as GLint]);
+        self.set_integers(ffi::BLEND_SRC_ALPHA, &[src_alpha as GLint]);
+        self.set_integers(ffi::BLEND_DST_ALPHA, &[dest_alpha as GLint]);
+    }
+
+    fn blend_equation(&self, mode: GLenum) {
+        self.blend_equation_separate(mode, mode);
+    }
+
+    fn blend_equation_separate(&self, mode_rgb: GLenum, mode_alpha: GLenum) {
+        self.set_integers(ffi::BLEND_EQUATION_RGB, &[mode_rgb as GLint]);
+        self.set_integers(ffi::BLEND_EQUATION_ALPHA, &[mode_alpha as GLint]);
+    }
+
+    fn color_mask(&self, r: bool, g: bool, b: bool, a: bool) {
+        self.set_integers(
+            ffi::COLOR_WRITEMASK,
+            &[r as GLint, g as GLint, b as GLint, a as GLint],
+        );
+    }
+
+    fn cull_face(&self, mode: GLenum) {
+        if mode != ffi::FRONT && mode != ffi::BACK && mode != ffi::FRONT_AND_BACK {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        self.set_integers(ffi::CULL_FACE_MODE, &[mode as GLint]);
+    }
+
+    fn front_face(&self, mode: GLenum) {
+        if mode != ffi::CW && mode != ffi::CCW {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        self.set_integers(ffi::FRONT_FACE, &[mode as GLint]);
+    }
+
+    fn enable(&self, cap: GLenum) {
+        let mut state = self.state.borrow_mut();
+        if !MOCK_CAPABILITIES.contains(&cap) {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        state.enabled.insert(cap);
+    }
+
+    fn disable(&self, cap: GLenum) {
+        let mut state = self.state.borrow_mut();
+        if !MOCK_CAPABILITIES.contains(&cap) {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        state.enabled.remove(&cap);
+    }
+
+    fn hint(&self, param_name: GLenum, param_val: GLenum) {
+        if param_val != ffi::FASTEST && param_val != ffi::NICEST && param_val != ffi::DONT_CARE {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        self.set_integers(param_name, &[param_val as GLint]);
+    }
+
+    fn is_enabled(&self, cap: GLenum) -> GLboolean {
+        let mut state = self.state.borrow_mut();
+        if !MOCK_CAPABILITIES.contains(&cap) {
+            state.error(ffi::INVALID_ENUM);
+            return ffi::FALSE;
+        }
+        state.enabled.contains(&cap) as GLboolean
+    }
+
+    fn is_shader(&self, shader: GLuint) -> GLboolean {
+        self.state.borrow().shaders.contains_key(&shader) as GLboolean
+    }
+
+    fn is_texture(&self, texture: GLenum) -> GLboolean {
+        let state = self.state.borrow();
+        state
+            .textures
+            .get(&texture)
+            .is_some_and(|t| t.target.is_some()) as GLboolean
+    }
+
+    fn is_framebuffer(&self, framebuffer: GLenum) -> GLboolean {
+        let state = self.state.borrow();
+        state
+            .framebuffers
+            .get(&framebuffer)
+            .is_some_and(|f| f.bound) as GLboolean
+    }
+
+    fn is_renderbuffer(&self, renderbuffer: GLenum) -> GLboolean {
+        let state = self.state.borrow();
+        state
+            .renderbuffers
+            .get(&renderbuffer)
+            .is_some_and(|r| r.bound) as GLboolean
+    }
+
+    fn check_frame_buffer_status(&self, target: GLenum) -> GLenum {
+        let mut state = self.state.borrow_mut();
+        match state.framebuffer_binding(target) {
+            Some(framebuffer) => state.framebuffer_status(framebuffer),
+            None => {
+                state.error(ffi::INVALID_ENUM);
+                0
+            }
+        }
+    }
+
+    fn enable_vertex_attrib_array(&self, index: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if let Some(attrib) = state.vertex_attrib(index) {
+            attrib.enabled = true;
+        }
+    }
+
+    fn disable_vertex_attrib_array(&self, index: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if let Some(attrib) = state.vertex_attrib(index) {
+            attrib.enabled = false;
+        }
+    }
+
+    fn uniform_1f(&self, location: GLint, v0: GLfloat) {
+        self.state.borrow_mut().uniform(location, &[v0 as f64]);
+    }
+
+    fn uniform_1fv(&self, location: GLint, values: &[f32]) {
+        let values: Vec<f64> = values.iter().map(|v| *v as f64).collect();
+        self.state.borrow_mut().uniform(location, &values);
+    }
+
+    fn uniform_1i(&self, location: GLint, v0: GLint) {
+        self.state.borrow_mut().uniform(location, &[v0 as f64]);
+    }
+
+    fn uniform_1iv(&self, location: GLint, values: &[i32]) {
+        let values: Vec<f64> = values.iter().map(|v| *v as f64).collect();
+        self.state.borrow_mut().uniform(location, &values);
+    }
+
+    fn uniform_1ui(&self, location: GLint, v0: GLuint) {
+        self.state.borrow_mut().uniform(location, &[v0 as f64]);
+    }
+
+    fn uniform_2f(&self, location: GLint, v0: GLfloat, v1: GLfloat) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[v0 as f64, v1 as f64]);
+    }
+
+    fn uniform_2fv(&self, location: GLint, values: &[f32]) {
+        self.uniform_1fv(location, values);
+    }
+
+    fn uniform_2i(&self, location: GLint, v0: GLint, v1: GLint) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[v0 as f64, v1 as f64]);
+    }
+
+    fn uniform_2iv(&self, location: GLint, values: &[i32]) {
+        self.uniform_1iv(location, values);
+    }
+
+    fn uniform_2ui(&self, location: GLint, v0: GLuint, v1: GLuint) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[v0 as f64, v1 as f64]);
+    }
+
+    fn uniform_3f(&self, location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[v0 as f64, v1 as f64, v2 as f64]);
+    }
+
+    fn uniform_3fv(&self, location: GLint, values: &[f32]) {
+        self.uniform_1fv(location, values);
+    }
+
+    fn uniform_3i(&self, location: GLint, v0: GLint, v1: GLint, v2: GLint) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[v0 as f64, v1 as f64, v2 as f64]);
+    }
+
+    fn uniform_3iv(&self, location: GLint, values: &[i32]) {
+        self.uniform_1iv(location, values);
+    }
+
+    fn uniform_3ui(&self, location: GLint, v0: GLuint, v1: GLuint, v2: GLuint) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[v0 as f64, v1 as f64, v2 as f64]);
+    }
+
+    fn uniform_4f(&self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[x as f64, y as f64, z as f64, w as f64]);
+    }
+
+    fn uniform_4i(&self, location: GLint, x: GLint, y: GLint, z: GLint, w: GLint) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[x as f64, y as f64, z as f64, w as f64]);
+    }
+
+    fn uniform_4iv(&self, location: GLint, values: &[i32]) {
+        self.uniform_1iv(location, values);
+    }
+
+    fn uniform_4ui(&self, location: GLint, x: GLuint, y: GLuint, z: GLuint, w: GLuint) {
+        self.state
+            .borrow_mut()
+            .uniform(location, &[x as f64, y as f64, z as f64, w as f64]);
+    }
+
+    fn uniform_4fv(&self, location: GLint, values: &[f32]) {
+        self.uniform_1fv(location, values);
+    }
+
+    fn uniform_matrix_2fv(&self, location: GLint, _transpose: bool, value: &[f32]) {
+        self.uniform_1fv(location, value);
+    }
+
+    fn uniform_matrix_3fv(&self, location: GLint, _transpose: bool, value: &[f32]) {
+        self.uniform_1fv(location, value);
+    }
+
+    fn uniform_matrix_4fv(&self, location: GLint, _transpose: bool, value: &[f32]) {
+        self.uniform_1fv(location, value);
+    }
+
+    fn depth_mask(&self, flag: bool) {
+        self.set_integers(ffi::DEPTH_WRITEMASK, &[flag as GLint]);
+    }
+
+    fn depth_range(&self, near: f64, far: f64) {
+        self.set_floats(ffi::DEPTH_RANGE, &[near as GLfloat, far as GLfloat]);
+    }
+
+    fn get_active_attrib(&self, program: GLuint, index: GLuint) -> (i32, u32, String) {
+        let mut state = self.state.borrow_mut();
+        let attrib = match state.program(program) {
+            Some(program) => program.attribs.get(index as usize).map(|a| a.0.clone()),
+            None => return (0, 0, String::new()),
+        };
+        match attrib {
+            Some(name) => (1, ffi::FLOAT_VEC4, name),
+            None => {
+                state.error(ffi::INVALID_VALUE);
+                (0, 0, String::new())
+            }
+        }
+    }
+
+    fn get_active_uniform(&self, program: GLuint, index: GLuint) -> (i32, u32, String) {
+        let mut state = self.state.borrow_mut();
+        let uniform = match state.program(program) {
+            Some(program) => program.uniforms.get(index as usize).cloned(),
+            None => return (0, 0, String::new()),
+        };
+        match uniform {
+            Some(name) => (1, ffi::FLOAT_VEC4, name),
+            None => {
+                state.error(ffi::INVALID_VALUE);
+                (0, 0, String::new())
+            }
+        }
+    }
+
+    fn get_active_uniforms_iv(
+        &self,
+        program: GLuint,
+        indices: Vec<GLuint>,
+        pname: GLenum,
+    ) -> Vec<GLint> {
+        let mut state = self.state.borrow_mut();
+        let count = match state.program(program) {
+            Some(program) => program.uniforms.len(),
+            None => return Vec::new(),
+        };
+        if indices.iter().any(|&i| i as usize >= count) {
+            state.error(ffi::INVALID_VALUE);
+            return Vec::new();
+        }
+        let value = match pname {
+            ffi::UNIFORM_TYPE => ffi::FLOAT_VEC4 as GLint,
+            ffi::UNIFORM_SIZE => 1,
+            ffi::UNIFORM_BLOCK_INDEX
+            | ffi::UNIFORM_OFFSET
+            | ffi::UNIFORM_ARRAY_STRIDE
+            | ffi::UNIFORM_MATRIX_STRIDE => -1,
+            ffi::UNIFORM_NAME_LENGTH | ffi::UNIFORM_IS_ROW_MAJOR => 0,
+            _ => {
+                state.error(ffi::INVALID_ENUM);
+                return Vec::new();
+            }
+        };
+        vec![value; indices.len()]
+    }
+
+    fn get_active_uniform_block_i(&self, program: GLuint, index: GLuint, pname: GLenum) -> GLint {
+        self.get_active_uniform_block_iv(program, index, pname)
+            .first()
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    fn get_active_uniform_block_iv(
+        &self,
+        program: GLuint,
+        index: GLuint,
+        pname: GLenum,
+    ) -> Vec<GLint> {
+        let mut state = self.state.borrow_mut();
+        let block = match state.program(program) {
+            Some(program) => program.uniform_blocks.get(index as usize).cloned(),
+            None => return Vec::new(),
+        };
+        let (name, binding) = match block {
+            Some(block) => block,
+            None => {
+                state.error(ffi::INVALID_VALUE);
+                return Vec::new();
+            }
+        };
+        match pname {
+            ffi::UNIFORM_BLOCK_BINDING => vec![binding as GLint],
+            ffi::UNIFORM_BLOCK_DATA_SIZE | ffi::UNIFORM_BLOCK_ACTIVE_UNIFORMS => vec![0],
+            ffi::UNIFORM_BLOCK_NAME_LENGTH => vec![name.len() as GLint + 1],
+            ffi::UNIFORM_BLOCK_ACTIVE_UNIFORM_INDICES => Vec::new(),
+            ffi::UNIFORM_BLOCK_REFERENCED_BY_VERTEX_SHADER
+            | ffi::UNIFORM_BLOCK_REFERENCED_BY_FRAGMENT_SHADER => vec![1],
+            _ => {
+                state.error(ffi::INVALID_ENUM);
+                Vec::new()
+            }
+        }
+    }
+
+    fn get_active_uniform_block_name(&self, program: GLuint, index: GLuint) -> String {
+        let mut state = self.state.borrow_mut();
+        let block = match state.program(program) {
+            Some(program) => program.uniform_blocks.get(index as usize).cloned(),
+            None => return String::new(),
+        };
+        match block {
+            Some((name, _)) => name,
+            None => {
+                state.error(ffi::INVALID_VALUE);
+                String::new()
+            }
+        }
+    }
+
+    /// Only storage blocks and buffer variables can be queried, and programs
+    /// never have any.
+    fn get_program_interface_i(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        let mut state = self.state.borrow_mut();
+        if state.program(program).is_none() {
+            return 0;
+        }
+        match (program_interface, pname) {
+            (ffi::SHADER_STORAGE_BLOCK, ffi::ACTIVE_RESOURCES)
+            | (ffi::SHADER_STORAGE_BLOCK, ffi::MAX_NAME_LENGTH)
+            | (ffi::BUFFER_VARIABLE, ffi::ACTIVE_RESOURCES)
+            | (ffi::BUFFER_VARIABLE, ffi::MAX_NAME_LENGTH) => 0,
+            _ => {
+                state.error(ffi::INVALID_ENUM);
+                0
+            }
+        }
+    }
+
+    fn get_program_resource_name(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        _index: GLuint,
+    ) -> String {
+        self.check_program_resource(program, program_interface);
+        String::new()
+    }
+
+    fn get_program_resource_iv(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        _index: GLuint,
+        _props: &[GLenum],
+    ) -> Vec<GLint> {
+        self.check_program_resource(program, program_interface);
+        Vec::new()
+    }
+
+    /// Every name queried on a linked program is treated as active and gets
+    /// a stable location, honoring `bind_attrib_location`.
+    fn get_attrib_location(&self, program: GLuint, name: &str) -> c_int {
+        let mut state = self.state.borrow_mut();
+        match state.linked_program(program) {
+            Some(_) if name.starts_with("gl_") => -1,
+            Some(program) => program.attrib_location(name),
+            None => -1,
+        }
+    }
+
+    fn get_frag_data_location(&self, program: GLuint, name: &str) -> c_int {
+        let mut state = self.state.borrow_mut();
+        match state.linked_program(program) {
+            Some(program) => program
+                .frag_data_bindings
+                .get(name)
+                .map_or(-1, |b| b.0 as c_int),
+            None => -1,
+        }
+    }
+
+    fn get_uniform_location(&self, program: GLuint, name: &str) -> c_int {
+        let mut state = self.state.borrow_mut();
+        match state.linked_program(program) {
+            Some(_) if name.starts_with("gl_") => -1,
+            Some(program) => program.uniform_index(name) as c_int,
+            None => -1,
+        }
+    }
+
+    fn get_program_info_log(&self, program: GLuint) -> String {
+        let mut state = self.state.borrow_mut();
+        state
+            .program(program)
+            .map(|p| p.info_log.clone())
+            .unwrap_or_default()
+    }
+
+    unsafe fn get_program_iv(&self, program: GLuint, pname: GLenum, result: &mut [GLint]) {
+        let mut state = self.state.borrow_mut();
+        let value = match state.program(program) {
+            Some(p) => match pname {
+                ffi::LINK_STATUS | ffi::VALIDATE_STATUS => Some(p.linked as GLint),
+                ffi::DELETE_STATUS => Some(p.delete_pending as GLint),
+                ffi::ATTACHED_SHADERS => Some(p.shaders.len() as GLint),
+                ffi::INFO_LOG_LENGTH => Some(if p.info_log.is_empty() {
+                    0
+                } else {
+                    p.info_log.len() as GLint + 1
+                }),
+                ffi::ACTIVE_ATTRIBUTES => Some(p.attribs.len() as GLint),
+                ffi::ACTIVE_UNIFORMS => Some(p.uniforms.len() as GLint),
+                ffi::ACTIVE_UNIFORM_BLOCKS => Some(p.uniform_blocks.len() as GLint),
+                ffi::PROGRAM_BINARY_LENGTH => Some(0),
+                _ => None,
+            },
+            None => return,
+        };
+        match value {
+            Some(value) => mock_fill(result, &[value]),
+            None => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    fn get_program_binary(&self, program: GLuint) -> (Vec<u8>, GLenum) {
+        self.state.borrow_mut().linked_program(program);
+        (Vec::new(), NONE)
+    }
+
+    /// No binary formats are supported, so this always fails to link.
+    fn program_binary(&self, program: GLuint, _format: GLenum, _binary: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        state.error(ffi::INVALID_ENUM);
+        if let Some(program) = state.program(program) {
+            program.linked = false;
+        }
+    }
+
+    fn program_parameter_i(&self, program: GLuint, pname: GLenum, _value: GLint) {
+        let mut state = self.state.borrow_mut();
+        if state.program(program).is_some()
+            && pname != ffi::PROGRAM_BINARY_RETRIEVABLE_HINT
+            && pname != ffi::PROGRAM_SEPARABLE
+        {
+            state.error(ffi::INVALID_ENUM);
+        }
+    }
+
+    unsafe fn get_vertex_attrib_iv(&self, index: GLuint, pname: GLenum, result: &mut [GLint]) {
+        let mut state = self.state.borrow_mut();
+        let attrib = match state.vertex_attrib(index) {
+            Some(attrib) => *attrib,
+            None => return,
+        };
+        let value = match pname {
+            ffi::VERTEX_ATTRIB_ARRAY_ENABLED => attrib.enabled as GLint,
+            ffi::VERTEX_ATTRIB_ARRAY_SIZE => {
+                if attrib.size == 0 {
+                    4
+                } else {
+                    attrib.size
+                }
+            }
+            ffi::VERTEX_ATTRIB_ARRAY_STRIDE => attrib.stride,
+            ffi::VERTEX_ATTRIB_ARRAY_TYPE => {
+                if attrib.type_ == 0 {
+                    ffi::FLOAT as GLint
+                } else {
+                    attrib.type_ as GLint
+                }
+            }
+            ffi::VERTEX_ATTRIB_ARRAY_NORMALIZED => attrib.normalized as GLint,
+            ffi::VERTEX_ATTRIB_ARRAY_INTEGER => attrib.integer as GLint,
+            ffi::VERTEX_ATTRIB_ARRAY_DIVISOR => attrib.divisor as GLint,
+            ffi::VERTEX_ATTRIB_ARRAY_BUFFER_BINDING => attrib.buffer as GLint,
+            _ => return state.error(ffi::INVALID_ENUM),
+        };
+        mock_fill(result, &[value]);
+    }
+
+    unsafe fn get_vertex_attrib_fv(&self, index: GLuint, pname: GLenum, result: &mut [GLfloat]) {
+        if pname == ffi::CURRENT_VERTEX_ATTRIB {
+            let mut state = self.state.borrow_mut();
+            if index >= MOCK_MAX_VERTEX_ATTRIBS {
+                return state.error(ffi::INVALID_VALUE);
+            }
+            let value = state
+                .current_attribs
+                .get(&index)
+                .cloned()
+                .unwrap_or([0.0, 0.0, 0.0, 1.0]);
+            return mock_fill(result, &value);
+        }
+        let mut values = vec![0; result.len()];
+        self.get_vertex_attrib_iv(index, pname, &mut values);
+        let values: Vec<GLfloat> = values.iter().map(|v| *v as GLfloat).collect();
+        mock_fill(result, &values);
+    }
+
+    fn get_vertex_attrib_pointer_v(&self, index: GLuint, pname: GLenum) -> GLsizeiptr {
+        let mut state = self.state.borrow_mut();
+        if pname != ffi::VERTEX_ATTRIB_ARRAY_POINTER {
+            state.error(ffi::INVALID_ENUM);
+            return 0;
+        }
+        state
+            .vertex_attrib(index)
+            .map_or(0, |a| a.offset as GLsizeiptr)
+    }
+
+    fn get_buffer_parameter_iv(&self, target: GLuint, pname: GLenum) -> GLint {
+        let mut state = self.state.borrow_mut();
+        let value = match state.bound_buffer(target) {
+            Some(buffer) => match pname {
+                ffi::BUFFER_SIZE => Some(buffer.data.len() as GLint),
+                ffi::BUFFER_USAGE => Some(buffer.usage as GLint),
+                ffi::BUFFER_MAPPED => Some(buffer.mapped.is_some() as GLint),
+                ffi::BUFFER_ACCESS_FLAGS => Some(buffer.mapped.map_or(0, |m| m.2 as GLint)),
+                ffi::BUFFER_MAP_OFFSET => Some(buffer.mapped.map_or(0, |m| m.0 as GLint)),
+                ffi::BUFFER_MAP_LENGTH => Some(buffer.mapped.map_or(0, |m| m.1 as GLint)),
+                ffi::BUFFER_IMMUTABLE_STORAGE => Some(buffer.immutable as GLint),
+                _ => None,
+            },
+            None => return 0,
+        };
+        value.unwrap_or_else(|| {
+            state.error(ffi::INVALID_ENUM);
+            0
+        })
+    }
+
+    fn get_shader_info_log(&self, shader: GLuint) -> String {
+        let mut state = self.state.borrow_mut();
+        state
+            .shader(shader)
+            .map(|s| s.info_log.clone())
+            .unwrap_or_default()
+    }
+
+    fn get_string(&self, which: GLenum) -> String {
+        let (version, glsl) = match self.gl_type {
+            GlType::Gl => ("3.3.0 MockGl", "3.30"),
+            GlType::Gles => ("OpenGL ES 3.1 MockGl", "OpenGL ES GLSL ES 3.10"),
+        };
+        match which {
+            ffi::VENDOR => "Servo".to_string(),
+            ffi::RENDERER => "gleam MockGl".to_string(),
+            ffi::VERSION => version.to_string(),
+            ffi::SHADING_LANGUAGE_VERSION => glsl.to_string(),
+            ffi::EXTENSIONS => String::new(),
+            _ => {
+                self.error(ffi::INVALID_ENUM);
+                String::new()
+            }
+        }
+    }
+
+    fn get_string_i(&self, which: GLenum, _index: GLuint) -> String {
+        // There are no extensions to enumerate.
+        self.error(if which == ffi::EXTENSIONS {
+            ffi::INVALID_VALUE
+        } else {
+            ffi::INVALID_ENUM
+        });
+        String::new()
+    }
+
+    unsafe fn get_shader_iv(&self, shader: GLuint, pname: GLenum, result: &mut [GLint]) {
+        let mut state = self.state.borrow_mut();
+        let value = match state.shader(shader) {
+            Some(s) => match pname {
+                ffi::SHADER_TYPE => Some(s.shader_type as GLint),
+                ffi::COMPILE_STATUS => Some(s.compiled as GLint),
+                ffi::DELETE_STATUS => Some(s.delete_pending as GLint),
+                ffi::INFO_LOG_LENGTH => Some(if s.info_log.is_empty() {
+                    0
+                } else {
+                    s.info_log.len() as GLint + 1
+                }),
+                ffi::SHADER_SOURCE_LENGTH => Some(if s.source.is_empty() {
+                    0
+                } else {
+                    s.source.len() as GLint + 1
+                }),
+                _ => None,
+            },
+            None => return,
+        };
+        match value {
+            Some(value) => mock_fill(result, &[value]),
+            None => state.error(ffi::INVALID_ENUM),
+        }
+    }
+
+    fn get_shader_precision_format(
+        &self,
+        _shader_type: GLuint,
+        precision_type: GLuint,
+    ) -> (GLint, GLint, GLint) {
+        match precision_type {
+            ffi::LOW_FLOAT | ffi::MEDIUM_FLOAT | ffi::HIGH_FLOAT => (127, 127, 23),
+            ffi::LOW_INT | ffi::MEDIUM_INT | ffi::HIGH_INT => (31, 30, 0),
+            _ => {
+                self.error(ffi::INVALID_ENUM);
+                (0, 0, 0)
+            }
+        }
+    }
+
+    /// Compilation succeeds for any non-empty source.
+    fn compile_shader(&self, shader: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if let Some(shader) = state.shader(shader) {
+            shader.compiled = !shader.source.trim().is_empty();
+            shader.info_log = if shader.compiled {
+                String::new()
+            } else {
+                "ERROR: 0:1: '' : empty shader source\n".to_string()
+            };
+        }
+    }
+
+    fn create_program(&self) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let name = state.gen_names(1)[0];
+        state.programs.insert(name, MockProgram::default());
+        name
+    }
+
+    fn delete_program(&self, program: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if program == 0 {
+            return;
+        }
+        if let Some(p) = state.program(program) {
+            p.delete_pending = true;
+        }
+        state.release_program(program);
+    }
+
+    fn create_shader(&self, shader_type: GLenum) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        if shader_type != ffi::VERTEX_SHADER
+            && shader_type != ffi::FRAGMENT_SHADER
+            && shader_type != ffi::COMPUTE_SHADER
+        {
+            state.error(ffi::INVALID_ENUM);
+            return 0;
+        }
+        let name = state.gen_names(1)[0];
+        state.shaders.insert(
+            name,
+            MockShader {
+                shader_type,
+                source: String::new(),
+                compiled: false,
+                info_log: String::new(),
+                delete_pending: false,
+            },
+        );
+        name
+    }
+
+    fn delete_shader(&self, shader: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if shader == 0 {
+            return;
+        }
+        if let Some(s) = state.shader(shader) {
+            s.delete_pending = true;
+        }
+        state.release_shader(shader);
+    }
+
+    fn detach_shader(&self, program: GLuint, shader: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if state.shader(shader).is_none() {
+            return;
+        }
+        let attached = match state.program(program) {
+            Some(program) => {
+                let count = program.shaders.len();
+                program.shaders.retain(|&s| s != shader);
+                program.shaders.len() != count
+            }
+            None => return,
+        };
+        if !attached {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        state.release_shader(shader);
+    }
+
+    /// Linking succeeds when every attached shader compiled and either a
+    /// vertex and fragment shader pair or a compute shader is attached.
+    fn link_program(&self, program: GLuint) {
+        let mut state = self.state.borrow_mut();
+        let shaders = match state.program(program) {
+            Some(p) => p.shaders.clone(),
+            None => return,
+        };
+        let types: Vec<(GLenum, bool)> = shaders
+            .iter()
+            .filter_map(|s| state.shaders.get(s))
+            .map(|s| (s.shader_type, s.compiled))
+            .collect();
+        let has = |ty| types.iter().any(|t| t.0 == ty);
+        let info_log = if types.iter().any(|t| !t.1) {
+            "error: linking with uncompiled shader\n"
+        } else if has(ffi::COMPUTE_SHADER) && types.len() == 1 {
+            ""
+        } else if !has(ffi::VERTEX_SHADER) || !has(ffi::FRAGMENT_SHADER) {
+            "error: program lacks a vertex or fragment shader\n"
+        } else {
+            ""
+        };
+        let p = state.programs.get_mut(&program).unwrap();
+        p.linked = info_log.is_empty();
+        p.info_log = info_log.to_string();
+        p.attribs.clear();
+        p.uniforms.clear();
+        p.uniform_values.clear();
+        p.uniform_blocks.clear();
+    }
+
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.set_floats(ffi::COLOR_CLEAR_VALUE, &[r, g, b, a]);
+    }
+
+    fn clear(&self, buffer_mask: GLbitfield) {
+        let mut state = self.state.borrow_mut();
+        let all = ffi::COLOR_BUFFER_BIT | ffi::DEPTH_BUFFER_BIT | ffi::STENCIL_BUFFER_BIT;
+        if buffer_mask & !all != 0 {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        state.check_draw_framebuffer();
+    }
+
+    fn clear_depth(&self, depth: f64) {
+        self.set_floats(ffi::DEPTH_CLEAR_VALUE, &[depth.clamp(0.0, 1.0) as GLfloat]);
+    }
+
+    fn clear_stencil(&self, s: GLint) {
+        self.set_integers(ffi::STENCIL_CLEAR_VALUE, &[s]);
+    }
+
+    fn flush(&self) {}
+
+    fn finish(&self) {}
+
+    fn get_error(&self) -> GLenum {
+        mem::replace(&mut self.state.borrow_mut().error, ffi::NO_ERROR)
+    }
+
+    fn stencil_mask(&self, mask: GLuint) {
+        self.stencil_mask_separate(ffi::FRONT_AND_BACK, mask);
+    }
+
+    fn stencil_mask_separate(&self, face: GLenum, mask: GLuint) {
+        if face != ffi::FRONT && face != ffi::BACK && face != ffi::FRONT_AND_BACK {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        if face != ffi::BACK {
+            self.set_integers(ffi::STENCIL_WRITEMASK, &[mask as GLint]);
+        }
+        if face != ffi::FRONT {
+            self.set_integers(ffi::STENCIL_BACK_WRITEMASK, &[mask as GLint]);
+        }
+    }
+
+    fn stencil_func(&self, func: GLenum, ref_: GLint, mask: GLuint) {
+        self.stencil_func_separate(ffi::FRONT_AND_BACK, func, ref_, mask);
+    }
+
+    fn stencil_func_separate(&self, face: GLenum, func: GLenum, ref_: GLint, mask: GLuint) {
+        if face != ffi::FRONT && face != ffi::BACK && face != ffi::FRONT_AND_BACK {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        if !(ffi::NEVER..=ffi::ALWAYS).contains(&func) {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        if face != ffi::BACK {
+            self.set_integers(ffi::STENCIL_FUNC, &[func as GLint]);
+            self.set_integers(ffi::STENCIL_REF, &[ref_]);
+            self.set_integers(ffi::STENCIL_VALUE_MASK, &[mask as GLint]);
+        }
+        if face != ffi::FRONT {
+            self.set_integers(ffi::STENCIL_BACK_FUNC, &[func as GLint]);
+            self.set_integers(ffi::STENCIL_BACK_REF, &[ref_]);
+            self.set_integers(ffi::STENCIL_BACK_VALUE_MASK, &[mask as GLint]);
+        }
+    }
+
+    fn stencil_op(&self, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        self.stencil_op_separate(ffi::FRONT_AND_BACK, sfail, dpfail, dppass);
+    }
+
+    fn stencil_op_separate(&self, face: GLenum, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        if face != ffi::FRONT && face != ffi::BACK && face != ffi::FRONT_AND_BACK {
+            return self.error(ffi::INVALID_ENUM);
+        }
+        if face != ffi::BACK {
+            self.set_integers(ffi::STENCIL_FAIL, &[sfail as GLint]);
+            self.set_integers(ffi::STENCIL_PASS_DEPTH_FAIL, &[dpfail as GLint]);
+            self.set_integers(ffi::STENCIL_PASS_DEPTH_PASS, &[dppass as GLint]);
+        }
+        if face != ffi::FRONT {
+            self.set_integers(ffi::STENCIL_BACK_FAIL, &[sfail as GLint]);
+            self.set_integers(ffi::STENCIL_BACK_PASS_DEPTH_FAIL, &[dpfail as GLint]);
+            self.set_integers(ffi::STENCIL_BACK_PASS_DEPTH_PASS, &[dppass as GLint]);
+        }
+    }
+
+    fn egl_image_target_texture2d_oes(&self, target: GLenum, _image: GLeglImageOES) {
+        let mut state = self.state.borrow_mut();
+        if target != ffi::TEXTURE_2D && target != ffi::TEXTURE_EXTERNAL_OES {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        if let Some(texture) = state.bound_texture(target) {
+            texture.levels.insert(
+                (target, 0),
+                MockLevel {
+                    internal_format: ffi::RGBA8,
+                    width: 1,
+                    height: 1,
+                    depth: 1,
+                },
+            );
+        }
+    }
+
+    fn egl_image_target_renderbuffer_storage_oes(&self, target: GLenum, _image: GLeglImageOES) {
+        self.renderbuffer_storage(target, ffi::RGBA8, 1, 1);
+    }
+
+    fn generate_mipmap(&self, target: GLenum) {
+        let mut state = self.state.borrow_mut();
+        let error = match state.bound_texture(target) {
+            Some(texture) => match texture.level(target, 0) {
+                Some(base) => {
+                    let (mut width, mut height, mut depth) = (base.width, base.height, base.depth);
+                    let mut level = 0;
+                    while width > 1 || height > 1 || (target == ffi::TEXTURE_3D && depth > 1) {
+                        level += 1;
+                        width = (width / 2).max(1);
+                        height = (height / 2).max(1);
+                        if target == ffi::TEXTURE_3D {
+                            depth = (depth / 2).max(1);
+                        }
+                        texture.levels.insert(
+                            (target, level),
+                            MockLevel {
+                                width,
+                                height,
+                                depth,
+                                ..base
+                            },
+                        );
+                    }
+                    return;
+                }
+                None => ffi::INVALID_OPERATION,
+            },
+            None => return,
+        };
+        state.error(error);
+    }
+
+    fn insert_event_marker_ext(&self, _message: &str) {}
+
+    fn push_group_marker_ext(&self, message: &str) {
+        self.state
+            .borrow_mut()
+            .group_markers
+            .push(message.to_string());
+    }
+
+    fn pop_group_marker_ext(&self) {
+        self.state.borrow_mut().group_markers.pop();
+    }
+
+    fn debug_message_insert_khr(
+        &self,
+        source: GLenum,
+        type_: GLenum,
+        id: GLuint,
+        severity: GLenum,
+        message: &str,
+    ) {
+        {
+            let mut state = self.state.borrow_mut();
+            match (
+                DebugSource::from_gl(source),
+                DebugType::from_gl(type_),
+                DebugSeverity::from_gl(severity),
+            ) {
+                (Some(source), Some(ty), Some(severity)) => {
+                    state.log_debug_message(message.to_string(), source, ty, id, severity)
+                }
+                _ => state.error(ffi::INVALID_ENUM),
+            }
+        }
+        self.deliver_debug_messages();
+    }
+
+    fn push_debug_group_khr(&self, source: GLenum, id: GLuint, message: &str) {
+        {
+            let mut state = self.state.borrow_mut();
+            let source = match DebugSource::from_gl(source) {
+                Some(source) => source,
+                None => return state.error(ffi::INVALID_ENUM),
+            };
+            if state.debug_groups.len() + 1 >= MOCK_MAX_DEBUG_GROUP_STACK_DEPTH {
+                return state.error(ffi::STACK_OVERFLOW);
+            }
+            state.debug_groups.push(message.to_string());
+            state.log_debug_message(
+                message.to_string(),
+                source,
+                DebugType::PushGroup,
+                id,
+                DebugSeverity::Notification,
+            );
+        }
+        self.deliver_debug_messages();
+    }
+
+    fn pop_debug_group_khr(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            // The pop is logged from within the group.
+            match state.debug_groups.last().cloned() {
+                Some(message) => {
+                    state.log_debug_message(
+                        message,
+                        DebugSource::Application,
+                        DebugType::PopGroup,
+                        0,
+                        DebugSeverity::Notification,
+                    );
+                    state.debug_groups.pop();
+                }
+                None => state.error(ffi::STACK_UNDERFLOW),
+            }
+        }
+        self.deliver_debug_messages();
+    }
+
+    fn fence_sync(&self, condition: GLenum, flags: GLbitfield) -> GLsync {
+        let mut state = self.state.borrow_mut();
+        if condition != ffi::SYNC_GPU_COMMANDS_COMPLETE {
+            state.error(ffi::INVALID_ENUM);
+            return ptr::null();
+        }
+        if flags != 0 {
+            state.error(ffi::INVALID_VALUE);
+            return ptr::null();
+        }
+        let name = state.gen_names(1)[0] as usize;
+        state.syncs.insert(name);
+        name as GLsync
+    }
+
+    fn client_wait_sync(&self, sync: GLsync, _flags: GLbitfield, _timeout: GLuint64) -> GLenum {
+        let mut state = self.state.borrow_mut();
+        if !state.syncs.contains(&(sync as usize)) {
+            state.error(ffi::INVALID_VALUE);
+            return ffi::WAIT_FAILED;
+        }
+        ffi::ALREADY_SIGNALED
+    }
+
+    fn wait_sync(&self, sync: GLsync, flags: GLbitfield, _timeout: GLuint64) {
+        let mut state = self.state.borrow_mut();
+        if !state.syncs.contains(&(sync as usize)) || flags != 0 {
+            state.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn delete_sync(&self, sync: GLsync) {
+        let mut state = self.state.borrow_mut();
+        if !sync.is_null() && !state.syncs.remove(&(sync as usize)) {
+            state.error(ffi::INVALID_VALUE);
+        }
+    }
+
+    fn texture_range_apple(&self, _target: GLenum, _data: &[u8]) {}
+
+    fn gen_fences_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.gen_names(n);
+        state.fences_apple.extend(names.iter().cloned());
+        names
+    }
+
+    fn delete_fences_apple(&self, fences: &[GLuint]) {
+        let mut state = self.state.borrow_mut();
+        for fence in fences {
+            state.fences_apple.remove(fence);
+        }
+    }
+
+    fn set_fence_apple(&self, fence: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if !state.fences_apple.contains(&fence) {
+            state.error(ffi::INVALID_OPERATION);
+        }
+    }
+
+    fn finish_fence_apple(&self, fence: GLuint) {
+        self.set_fence_apple(fence);
+    }
+
+    fn test_fence_apple(&self, fence: GLuint) {
+        self.set_fence_apple(fence);
+    }
+
+    fn test_object_apple(&self, _object: GLenum, _name: GLuint) -> GLboolean {
+        ffi::TRUE
+    }
+
+    fn finish_object_apple(&self, _object: GLenum, _name: GLuint) {}
+
+    fn blend_barrier_khr(&self) {}
+
+    fn bind_frag_data_location_indexed(
+        &self,
+        program: GLuint,
+        color_number: GLuint,
+        index: GLuint,
+        name: &str,
+    ) {
+        let mut state = self.state.borrow_mut();
+        if index > 1 || color_number >= MOCK_MAX_COLOR_ATTACHMENTS as GLuint {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if let Some(program) = state.program(program) {
+            program
+                .frag_data_bindings
+                .insert(name.to_string(), (color_number, index));
+        }
+    }
+
+    fn get_frag_data_index(&self, program: GLuint, name: &str) -> GLint {
+        let mut state = self.state.borrow_mut();
+        match state.linked_program(program) {
+            Some(program) => program
+                .frag_data_bindings
+                .get(name)
+                .map_or(-1, |b| b.1 as GLint),
+            None => -1,
+        }
+    }
+
+    fn get_debug_messages(&self) -> Vec<DebugMessage> {
+        mem::take(&mut self.state.borrow_mut().debug_messages)
+    }
+
+    fn set_debug_callback(&self, callback: Option<DebugCallback>) {
+        self.state.borrow_mut().debug_callback = callback.map(Rc::new);
+    }
+
+    fn debug_message_control(
+        &self,
+        source: GLenum,
+        ty: GLenum,
+        severity: GLenum,
+        ids: &[GLuint],
+        enabled: bool,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let valid = (source == ffi::DONT_CARE || DebugSource::from_gl(source).is_some())
+            && (ty == ffi::DONT_CARE || DebugType::from_gl(ty).is_some())
+            && (severity == ffi::DONT_CARE || DebugSeverity::from_gl(severity).is_some());
+        if !valid {
+            return state.error(ffi::INVALID_ENUM);
+        }
+        // Ids are only unique within a source and type.
+        if !ids.is_empty()
+            && (source == ffi::DONT_CARE || ty == ffi::DONT_CARE || severity != ffi::DONT_CARE)
+        {
+            return state.error(ffi::INVALID_OPERATION);
+        }
+        state.debug_controls.push(MockDebugControl {
+            source,
+            ty,
+            severity,
+            ids: ids.to_vec(),
+            enabled,
+        });
+    }
+
+    fn object_label(&self, identifier: GLenum, name: GLuint, label: &str) {
+        let mut state = self.state.borrow_mut();
+        if let Err(error) = state.check_object_label(identifier, name) {
+            return state.error(error);
+        }
+        if label.len() >= MOCK_MAX_LABEL_LENGTH {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if label.is_empty() {
+            state.object_labels.remove(&(identifier, name));
+        } else {
+            state
+                .object_labels
+                .insert((identifier, name), label.to_string());
+        }
+    }
+
+    fn get_object_label(&self, identifier: GLenum, name: GLuint) -> String {
+        let mut state = self.state.borrow_mut();
+        if let Err(error) = state.check_object_label(identifier, name) {
+            state.error(error);
+            return String::new();
+        }
+        state
+            .object_labels
+            .get(&(identifier, name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn object_ptr_label(&self, sync: GLsync, label: &str) {
+        let mut state = self.state.borrow_mut();
+        if !state.syncs.contains(&(sync as usize)) || label.len() >= MOCK_MAX_LABEL_LENGTH {
+            return state.error(ffi::INVALID_VALUE);
+        }
+        if label.is_empty() {
+            state.sync_labels.remove(&(sync as usize));
+        } else {
+            state.sync_labels.insert(sync as usize, label.to_string());
+        }
+    }
+
+    fn provoking_vertex_angle(&self, _mode: GLenum) {}
+
+    fn copy_texture_chromium(
+        &self,
+        _source_id: GLuint,
+        _source_level: GLint,
+        _dest_target: GLenum,
+        _dest_id: GLuint,
+        _dest_level: GLint,
+        _internal_format: GLint,
+        _dest_type: GLenum,
+        _unpack_flip_y: GLboolean,
+        _unpack_premultiply_alpha: GLboolean,
+        _unpack_unmultiply_alpha: GLboolean,
+    ) {
+    }
+
+    fn copy_sub_texture_chromium(
+        &self,
+        _source_id: GLuint,
+        _source_level: GLint,
+        _dest_target: GLenum,
+        _dest_id: GLuint,
+        _dest_level: GLint,
+        _x_offset: GLint,
+        _y_offset: GLint,
+        _x: GLint,
+        _y: GLint,
+        _width: GLsizei,
+        _height: GLsizei,
+        _unpack_flip_y: GLboolean,
+        _unpack_premultiply_alpha: GLboolean,
+        _unpack_unmultiply_alpha: GLboolean,
+    ) {
+    }
+
+    fn copy_texture_3d_angle(
+        &self,
+        _source_id: GLuint,
+        _source_level: GLint,
+        _dest_target: GLenum,
+        _dest_id: GLuint,
+        _dest_level: GLint,
+        _internal_format: GLint,
+        _dest_type: GLenum,
+        _unpack_flip_y: GLboolean,
+        _unpack_premultiply_alpha: GLboolean,
+        _unpack_unmultiply_alpha: GLboolean,
+    ) {
+    }
+
+    fn copy_sub_texture_3d_angle(
+        &self,
+        _source_id: GLuint,
+        _source_level: GLint,
+        _dest_target: GLenum,
+        _dest_id: GLuint,
+        _dest_level: GLint,
+        _x_offset: GLint,
+        _y_offset: GLint,
+        _z_offset: GLint,
+        _x: GLint,
+        _y: GLint,
+        _z: GLint,
+        _width: GLsizei,
+        _height: GLsizei,
+        _depth: GLsizei,
+        _unpack_flip_y: GLboolean,
+        _unpack_premultiply_alpha: GLboolean,
+        _unpack_unmultiply_alpha: GLboolean,
+    ) {
+    }
+
+    fn buffer_storage(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        flags: GLbitfield,
+    ) {
+        if size <= 0 {
+            return self.error(ffi::INVALID_VALUE);
+        }
+        self.upload_buffer(target, size, data, true, flags);
+    }
+
+    fn flush_mapped_buffer_range(&self, target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+        let mut state = self.state.borrow_mut();
+        let error = match state.bound_buffer(target) {
+            Some(buffer) => match buffer.mapped {
+                Some((_, len, access)) if access & ffi::MAP_FLUSH_EXPLICIT_BIT != 0 => {
+                    if mock_range(offset, length, len).is_none() {
+                        ffi::INVALID_VALUE
+                    } else {
+                        return;
+                    }
+                }
+                _ => ffi::INVALID_OPERATION,
+            },
+            None => return,
+        };
+        state.error(error);
+    }
+
+    fn start_tiling_qcom(
+        &self,
+        _x: GLuint,
+        _y: GLuint,
+        _width: GLuint,
+        _height: GLuint,
+        _preserve_mask: GLbitfield,
+    ) {
+    }
+
+    fn end_tiling_qcom(&self, _preserve_mask: GLbitfield) {}
+}
@@ -0,0 +1,384 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Descriptions of the client-side pixel layouts used by pixel transfers
+//! such as `tex_image_2d`, `read_pixels` and `get_tex_image_into_buffer`.
+//!
+//! Every `format`/`type` combination accepted by OpenGL 3.3 or OpenGL ES 3.1
+//! is described; anything else is reported as an error rather than guessed.
+
+use ffi;
+use ffi::types::{GLenum, GLint, GLsizei};
+use std::error::Error;
+use std::fmt;
+
+/// What a pixel transfer format holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelKind {
+    /// Normalized or floating-point color.
+    Color,
+    /// Unnormalized integer color, from the `*_INTEGER` formats.
+    Integer,
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+/// The components of a pixel transfer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelComponents {
+    /// One component per element of the given size in bytes.
+    Unpacked(usize),
+    /// All the pixel's components packed into one element of the given size
+    /// in bytes, holding the given number of components.
+    Packed(usize, usize),
+}
+
+/// A description of one `format`/`type` combination of client pixel data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelFormat {
+    pub format: GLenum,
+    pub ty: GLenum,
+    pub kind: PixelKind,
+    /// The number of components of the format.
+    pub components: usize,
+    pub layout: PixelComponents,
+    /// Whether the type holds floating-point values.
+    pub float: bool,
+    /// The size in bytes of one pixel.
+    pub bytes_per_pixel: usize,
+}
+
+impl PixelFormat {
+    /// Describes `format` and `ty`, or fails if the combination is not a
+    /// valid pixel transfer layout.
+    pub fn new(format: GLenum, ty: GLenum) -> Result<PixelFormat, PixelDataError> {
+        let unsupported = PixelDataError::UnsupportedFormat { format, ty };
+        let (kind, components) = format_components(format).ok_or(unsupported)?;
+        let (layout, float) = type_layout(ty).ok_or(unsupported)?;
+        let valid = match (kind, layout) {
+            (PixelKind::DepthStencil, _) => {
+                ty == ffi::UNSIGNED_INT_24_8 || ty == ffi::FLOAT_32_UNSIGNED_INT_24_8_REV
+            }
+            (_, PixelComponents::Unpacked(_)) if kind == PixelKind::Integer => !float,
+            (_, PixelComponents::Unpacked(_)) => true,
+            (PixelKind::Color, PixelComponents::Packed(_, packed))
+            | (PixelKind::Integer, PixelComponents::Packed(_, packed)) => {
+                packed == components
+                    && !(float && kind == PixelKind::Integer)
+                    && packed_type_allows(ty, format)
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(unsupported);
+        }
+        let bytes_per_pixel = match layout {
+            PixelComponents::Unpacked(size) => size * components,
+            PixelComponents::Packed(size, _) => size,
+        };
+        Ok(PixelFormat {
+            format,
+            ty,
+            kind,
+            components,
+            layout,
+            float,
+            bytes_per_pixel,
+        })
+    }
+}
+
+fn format_components(format: GLenum) -> Option<(PixelKind, usize)> {
+    Some(match format {
+        ffi::RED | ffi::GREEN | ffi::BLUE | ffi::ALPHA | ffi::LUMINANCE => (PixelKind::Color, 1),
+        ffi::RG | ffi::LUMINANCE_ALPHA => (PixelKind::Color, 2),
+        ffi::RGB | ffi::BGR => (PixelKind::Color, 3),
+        ffi::RGBA | ffi::BGRA => (PixelKind::Color, 4),
+        ffi::RED_INTEGER | ffi::GREEN_INTEGER | ffi::BLUE_INTEGER => (PixelKind::Integer, 1),
+        ffi::RG_INTEGER => (PixelKind::Integer, 2),
+        ffi::RGB_INTEGER | ffi::BGR_INTEGER => (PixelKind::Integer, 3),
+        ffi::RGBA_INTEGER | ffi::BGRA_INTEGER => (PixelKind::Integer, 4),
+        ffi::DEPTH_COMPONENT => (PixelKind::Depth, 1),
+        ffi::STENCIL_INDEX => (PixelKind::Stencil, 1),
+        ffi::DEPTH_STENCIL => (PixelKind::DepthStencil, 2),
+        _ => return None,
+    })
+}
+
+fn type_layout(ty: GLenum) -> Option<(PixelComponents, bool)> {
+    use self::PixelComponents::*;
+    Some(match ty {
+        ffi::UNSIGNED_BYTE | ffi::BYTE => (Unpacked(1), false),
+        ffi::UNSIGNED_SHORT | ffi::SHORT => (Unpacked(2), false),
+        ffi::UNSIGNED_INT | ffi::INT => (Unpacked(4), false),
+        ffi::HALF_FLOAT | ffi::HALF_FLOAT_OES => (Unpacked(2), true),
+        ffi::FLOAT => (Unpacked(4), true),
+        ffi::UNSIGNED_BYTE_3_3_2 | ffi::UNSIGNED_BYTE_2_3_3_REV => (Packed(1, 3), false),
+        ffi::UNSIGNED_SHORT_5_6_5 | ffi::UNSIGNED_SHORT_5_6_5_REV => (Packed(2, 3), false),
+        ffi::UNSIGNED_SHORT_4_4_4_4
+        | ffi::UNSIGNED_SHORT_4_4_4_4_REV
+        | ffi::UNSIGNED_SHORT_5_5_5_1
+        | ffi::UNSIGNED_SHORT_1_5_5_5_REV => (Packed(2, 4), false),
+        ffi::UNSIGNED_INT_8_8_8_8
+        | ffi::UNSIGNED_INT_8_8_8_8_REV
+        | ffi::UNSIGNED_INT_10_10_10_2
+        | ffi::UNSIGNED_INT_2_10_10_10_REV => (Packed(4, 4), false),
+        ffi::UNSIGNED_INT_10F_11F_11F_REV | ffi::UNSIGNED_INT_5_9_9_9_REV => (Packed(4, 3), true),
+        ffi::UNSIGNED_INT_24_8 => (Packed(4, 2), false),
+        ffi::FLOAT_32_UNSIGNED_INT_24_8_REV => (Packed(8, 2), true),
+        _ => return None,
+    })
+}
+
+/// Packed types with the right number of components are still limited to
+/// the formats whose component order they describe.
+fn packed_type_allows(ty: GLenum, format: GLenum) -> bool {
+    match ty {
+        ffi::UNSIGNED_INT_10F_11F_11F_REV | ffi::UNSIGNED_INT_5_9_9_9_REV => format == ffi::RGB,
+        ffi::UNSIGNED_INT_24_8 | ffi::FLOAT_32_UNSIGNED_INT_24_8_REV => false,
+        _ => format != ffi::LUMINANCE_ALPHA,
+    }
+}
+
+/// An error returned by pixel transfers whose client memory can't be shown
+/// to match the image it describes. Nothing is sent to the driver in that
+/// case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelDataError {
+    /// The format and type are not a valid pixel transfer combination.
+    UnsupportedFormat { format: GLenum, ty: GLenum },
+    /// The slice is shorter than the bytes the driver would access.
+    BufferTooSmall { required: usize, actual: usize },
+    /// The bytes the transfer would access don't fit in `usize`.
+    SizeOverflow,
+}
+
+impl fmt::Display for PixelDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PixelDataError::UnsupportedFormat { format, ty } => {
+                write!(
+                    f,
+                    "unsupported pixel format {:#x} with type {:#x}",
+                    format, ty
+                )
+            }
+            PixelDataError::BufferTooSmall { required, actual } => {
+                write!(
+                    f,
+                    "pixel data is {} bytes but {} are required",
+                    actual, required
+                )
+            }
+            PixelDataError::SizeOverflow => {
+                write!(f, "pixel data size overflows the address space")
+            }
+        }
+    }
+}
+
+impl Error for PixelDataError {}
+
+/// The `UNPACK_*` or `PACK_*` pixel store state that decides which bytes of
+/// client memory a pixel transfer accesses. `image_height` and `skip_images`
+/// only apply to 3D transfers and are left at zero for 2D ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelStore {
+    pub alignment: GLint,
+    pub row_length: GLint,
+    pub image_height: GLint,
+    pub skip_pixels: GLint,
+    pub skip_rows: GLint,
+    pub skip_images: GLint,
+}
+
+impl Default for PixelStore {
+    fn default() -> PixelStore {
+        PixelStore {
+            alignment: 4,
+            row_length: 0,
+            image_height: 0,
+            skip_pixels: 0,
+            skip_rows: 0,
+            skip_images: 0,
+        }
+    }
+}
+
+impl PixelStore {
+    /// Returns the number of bytes of client memory spanned by an image of
+    /// `width` x `height` x `depth` pixels, including the skipped ones.
+    pub fn image_size(
+        &self,
+        (width, height, depth): (GLsizei, GLsizei, GLsizei),
+        format: GLenum,
+        ty: GLenum,
+    ) -> Result<usize, PixelDataError> {
+        let pixel = PixelFormat::new(format, ty)?.bytes_per_pixel;
+        if width <= 0 || height <= 0 || depth <= 0 {
+            return Ok(0);
+        }
+        self.checked_image_size(pixel, (width, height, depth))
+            .ok_or(PixelDataError::SizeOverflow)
+    }
+
+    fn checked_image_size(
+        &self,
+        pixel: usize,
+        (width, height, depth): (GLsizei, GLsizei, GLsizei),
+    ) -> Option<usize> {
+        let dimension = |value: GLint, default: GLsizei| {
+            if value > 0 {
+                value as usize
+            } else {
+                default as usize
+            }
+        };
+        let skip = |value: GLint| value.max(0) as usize;
+        let alignment = self.alignment.max(1) as usize;
+        let row = pixel
+            .checked_mul(dimension(self.row_length, width))?
+            .div_ceil(alignment)
+            .checked_mul(alignment)?;
+        let image = row.checked_mul(dimension(self.image_height, height))?;
+        let images = skip(self.skip_images)
+            .checked_add(depth as usize - 1)?
+            .checked_mul(image)?;
+        skip(self.skip_pixels)
+            .checked_mul(pixel)?
+            .checked_add(skip(self.skip_rows).checked_mul(row)?)?
+            .checked_add(images)?
+            .checked_add((height as usize - 1).checked_mul(row)?)?
+            .checked_add((width as usize).checked_mul(pixel)?)
+    }
+
+    /// Checks that `len` bytes of client memory hold every byte a transfer
+    /// of an image of `size` pixels accesses.
+    pub fn check(
+        &self,
+        size: (GLsizei, GLsizei, GLsizei),
+        format: GLenum,
+        ty: GLenum,
+        len: usize,
+    ) -> Result<(), PixelDataError> {
+        let required = self.image_size(size, format, ty)?;
+        if len < required {
+            return Err(PixelDataError::BufferTooSmall {
+                required,
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(store: PixelStore, dimensions: (GLsizei, GLsizei, GLsizei)) -> usize {
+        store
+            .image_size(dimensions, ffi::RGB, ffi::UNSIGNED_BYTE)
+            .unwrap()
+    }
+
+    #[test]
+    fn tight_rows() {
+        let store = PixelStore {
+            alignment: 1,
+            ..PixelStore::default()
+        };
+        assert_eq!(size(store, (3, 2, 1)), 3 * 3 * 2);
+        assert_eq!(size(store, (3, 2, 4)), 3 * 3 * 2 * 4);
+        assert_eq!(size(store, (0, 2, 1)), 0);
+        assert_eq!(size(store, (3, -1, 1)), 0);
+    }
+
+    #[test]
+    fn alignment_pads_every_row_but_the_last() {
+        // Rows of 9 bytes are padded to 12.
+        assert_eq!(size(PixelStore::default(), (3, 2, 1)), 12 + 9);
+        let store = PixelStore {
+            alignment: 8,
+            ..PixelStore::default()
+        };
+        assert_eq!(size(store, (3, 3, 1)), 16 * 2 + 9);
+    }
+
+    #[test]
+    fn row_length_and_image_height() {
+        let store = PixelStore {
+            alignment: 1,
+            row_length: 5,
+            image_height: 4,
+            ..PixelStore::default()
+        };
+        assert_eq!(size(store, (3, 2, 1)), 15 + 9);
+        assert_eq!(size(store, (3, 2, 2)), 15 * 4 + 15 + 9);
+    }
+
+    #[test]
+    fn skipped_pixels_rows_and_images() {
+        let store = PixelStore {
+            alignment: 1,
+            skip_pixels: 2,
+            skip_rows: 1,
+            skip_images: 1,
+            ..PixelStore::default()
+        };
+        assert_eq!(size(store, (3, 2, 1)), 2 * 3 + 9 + 9 * 2 + 9 + 9);
+    }
+
+    #[test]
+    fn overflow() {
+        let huge = (
+            GLsizei::max_value(),
+            GLsizei::max_value(),
+            GLsizei::max_value(),
+        );
+        assert_eq!(
+            PixelStore::default().image_size(huge, ffi::RGBA, ffi::FLOAT),
+            Err(PixelDataError::SizeOverflow)
+        );
+        let store = PixelStore {
+            skip_images: GLint::max_value(),
+            row_length: GLint::max_value(),
+            image_height: GLint::max_value(),
+            ..PixelStore::default()
+        };
+        assert_eq!(
+            store.image_size((1, 1, 1), ffi::RGBA, ffi::FLOAT),
+            Err(PixelDataError::SizeOverflow)
+        );
+    }
+
+    #[test]
+    fn check_reports_the_required_size() {
+        let store = PixelStore::default();
+        assert_eq!(
+            store.check((3, 2, 1), ffi::RGB, ffi::UNSIGNED_BYTE, 21),
+            Ok(())
+        );
+        assert_eq!(
+            store.check((3, 2, 1), ffi::RGB, ffi::UNSIGNED_BYTE, 20),
+            Err(PixelDataError::BufferTooSmall {
+                required: 21,
+                actual: 20,
+            })
+        );
+        assert_eq!(
+            store.check((3, 2, 1), ffi::RGB, ffi::UNSIGNED_INT_24_8, 100),
+            Err(PixelDataError::UnsupportedFormat {
+                format: ffi::RGB,
+                ty: ffi::UNSIGNED_INT_24_8,
+            })
+        );
+    }
+}
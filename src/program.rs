@@ -0,0 +1,754 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compiling and linking programs, with the info logs of failed shaders
+//! parsed into diagnostics, and listing the resources of linked programs.
+//!
+//! Drivers don't agree on the format of their info logs. The formats of
+//! Mesa, NVIDIA, ANGLE and Adreno are understood, and so are the drivers
+//! that share them, such as Apple's and AMD's, which log like ANGLE.
+
+use ffi;
+use ffi::types::{GLenum, GLint, GLuint};
+use gl::Gl;
+use handles::{Program, Shader};
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+/// How serious a diagnostic is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    /// A note or any other message that doesn't fail the compilation.
+    Info,
+}
+
+/// One diagnostic of an info log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InfoLogEntry {
+    pub severity: Severity,
+    /// The index of the source string the diagnostic is about, which is the
+    /// file of `#line` directives.
+    pub file: Option<u32>,
+    pub line: Option<u32>,
+    /// The column, which only Mesa reports.
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+/// Parses an info log into its diagnostics, in order.
+///
+/// Lines that have neither a severity nor a location, such as the headers
+/// NVIDIA prints before the diagnostics of each stage of a program, are
+/// skipped.
+///
+/// ```
+/// use gleam::program::{parse_info_log, Severity};
+///
+/// // Mesa
+/// let entries = parse_info_log("0:12(5): error: `color' undeclared\n");
+/// assert_eq!(entries[0].severity, Severity::Error);
+/// assert_eq!((entries[0].file, entries[0].line), (Some(0), Some(12)));
+/// assert_eq!(entries[0].column, Some(5));
+/// assert_eq!(entries[0].message, "`color' undeclared");
+///
+/// // NVIDIA
+/// let entries = parse_info_log("0(7) : warning C7050: \"uv\" might be used before being initialized\n");
+/// assert_eq!(entries[0].severity, Severity::Warning);
+/// assert_eq!((entries[0].file, entries[0].line), (Some(0), Some(7)));
+/// assert_eq!(entries[0].message, "C7050: \"uv\" might be used before being initialized");
+///
+/// // ANGLE and Adreno
+/// let entries = parse_info_log(
+///     "ERROR: 0:3: 'vec5' : no matching overloaded function found\n\
+///      ERROR: 1 compilation errors.  No code generated.\n",
+/// );
+/// assert_eq!((entries[0].file, entries[0].line), (Some(0), Some(3)));
+/// assert_eq!(entries[0].message, "'vec5' : no matching overloaded function found");
+/// assert_eq!(entries[1].line, None);
+///
+/// // Link errors usually have no location.
+/// let entries = parse_info_log("error: vertex shader lacks `main'\n");
+/// assert_eq!((entries[0].severity, entries[0].line), (Severity::Error, None));
+/// ```
+pub fn parse_info_log(log: &str) -> Vec<InfoLogEntry> {
+    log.lines().filter_map(parse_info_log_line).collect()
+}
+
+fn parse_info_log_line(line: &str) -> Option<InfoLogEntry> {
+    let line = line.trim();
+    // ANGLE and Adreno start with the severity, the others with the location.
+    let (severity, location, message) = match strip_severity(line) {
+        Some((severity, rest)) => {
+            let (location, message) = strip_location(rest);
+            (Some(severity), location, message)
+        }
+        None => {
+            let (location, rest) = strip_location(line);
+            match strip_severity(rest) {
+                Some((severity, message)) => (Some(severity), location, message),
+                None => (None, location, rest),
+            }
+        }
+    };
+    if severity.is_none() && location.is_none() {
+        return None;
+    }
+    Some(InfoLogEntry {
+        severity: severity.unwrap_or(Severity::Info),
+        file: location.map(|(file, _, _)| file),
+        line: location.map(|(_, line, _)| line),
+        column: location.and_then(|(_, _, column)| column),
+        message: message.trim().to_string(),
+    })
+}
+
+/// Splits a leading `error:`, `ERROR:` or NVIDIA's `error C1008:` off `s`,
+/// keeping the NVIDIA error code in the rest.
+fn strip_severity(s: &str) -> Option<(Severity, &str)> {
+    let s = s.trim_start();
+    let word_len = s
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(s.len());
+    let severity = match &*s[..word_len].to_ascii_lowercase() {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "info" | "note" => Severity::Info,
+        _ => return None,
+    };
+    let rest = &s[word_len..];
+    match rest.strip_prefix(':') {
+        Some(rest) => Some((severity, rest)),
+        None if rest.starts_with(' ') && rest.contains(':') => Some((severity, rest)),
+        None => None,
+    }
+}
+
+/// The file, line and column of a diagnostic.
+type Location = (u32, u32, Option<u32>);
+
+/// Splits a leading `file:line:` (ANGLE and Adreno), `file:line(column):`
+/// (Mesa) or `file(line) :` (NVIDIA) off `s`.
+fn strip_location(s: &str) -> (Option<Location>, &str) {
+    fn number(s: &str) -> Option<(u32, &str)> {
+        let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        s[..len].parse().ok().map(|n| (n, &s[len..]))
+    }
+    let parse = || {
+        let s = s.trim_start();
+        let (file, rest) = number(s)?;
+        let (line, column, rest) = if let Some(rest) = rest.strip_prefix(':') {
+            let (line, rest) = number(rest)?;
+            match rest.strip_prefix('(') {
+                Some(rest) => {
+                    let (column, rest) = number(rest)?;
+                    (line, Some(column), rest.strip_prefix(')')?)
+                }
+                None => (line, None, rest),
+            }
+        } else {
+            let (line, rest) = number(rest.strip_prefix('(')?)?;
+            (line, None, rest.strip_prefix(')')?)
+        };
+        let rest = rest.trim_start().strip_prefix(':')?;
+        Some(((file, line, column), rest))
+    };
+    match parse() {
+        Some((location, rest)) => (Some(location), rest),
+        None => (None, s),
+    }
+}
+
+/// Why `ProgramBuilder::build` failed.
+#[derive(Clone, Debug)]
+pub enum ShaderError {
+    /// A stage failed to compile.
+    Compile {
+        shader_type: GLenum,
+        log: String,
+        entries: Vec<InfoLogEntry>,
+    },
+    /// The compiled stages failed to link.
+    Link {
+        log: String,
+        entries: Vec<InfoLogEntry>,
+    },
+}
+
+impl ShaderError {
+    /// The info log of the shader or program, as returned by the driver.
+    pub fn log(&self) -> &str {
+        match *self {
+            ShaderError::Compile { ref log, .. } | ShaderError::Link { ref log, .. } => log,
+        }
+    }
+
+    /// The diagnostics of the info log.
+    pub fn entries(&self) -> &[InfoLogEntry] {
+        match *self {
+            ShaderError::Compile { ref entries, .. } | ShaderError::Link { ref entries, .. } => {
+                entries
+            }
+        }
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ShaderError::Compile {
+                shader_type,
+                ref log,
+                ..
+            } => {
+                match shader_type_name(shader_type) {
+                    Some(name) => write!(f, "failed to compile {} shader", name)?,
+                    None => write!(f, "failed to compile shader of type {:#x}", shader_type)?,
+                }
+                write!(f, ": {}", log.trim())
+            }
+            ShaderError::Link { ref log, .. } => {
+                write!(f, "failed to link program: {}", log.trim())
+            }
+        }
+    }
+}
+
+impl Error for ShaderError {}
+
+fn shader_type_name(shader_type: GLenum) -> Option<&'static str> {
+    Some(match shader_type {
+        ffi::VERTEX_SHADER => "vertex",
+        ffi::FRAGMENT_SHADER => "fragment",
+        ffi::GEOMETRY_SHADER => "geometry",
+        ffi::COMPUTE_SHADER => "compute",
+        _ => return None,
+    })
+}
+
+/// Compiles the sources of each stage of a program and links them, binding
+/// attribute and fragment output locations before linking.
+///
+/// ```no_run
+/// # use gleam::gl::{self, Gl};
+/// # use gleam::program::ProgramBuilder;
+/// # use std::rc::Rc;
+/// # fn build(gl: &Rc<dyn Gl>, vs: &str, fs: &str) {
+/// let program = ProgramBuilder::new()
+///     .stage(gl::VERTEX_SHADER, vs)
+///     .stage(gl::FRAGMENT_SHADER, fs)
+///     .bind_attrib_location(0, "position")
+///     .build(gl);
+/// match program {
+///     Ok(program) => program.use_program(),
+///     Err(err) => {
+///         for entry in err.entries() {
+///             println!("{:?} {:?}: {}", entry.line, entry.severity, entry.message);
+///         }
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ProgramBuilder<'a> {
+    stages: Vec<(GLenum, &'a str)>,
+    attrib_locations: Vec<(GLuint, &'a str)>,
+    frag_data_locations: Vec<(GLuint, GLuint, &'a str)>,
+}
+
+impl<'a> ProgramBuilder<'a> {
+    pub fn new() -> ProgramBuilder<'a> {
+        ProgramBuilder::default()
+    }
+
+    /// Adds a stage of type `shader_type`, such as `VERTEX_SHADER`.
+    pub fn stage(mut self, shader_type: GLenum, source: &'a str) -> ProgramBuilder<'a> {
+        self.stages.push((shader_type, source));
+        self
+    }
+
+    pub fn bind_attrib_location(mut self, index: GLuint, name: &'a str) -> ProgramBuilder<'a> {
+        self.attrib_locations.push((index, name));
+        self
+    }
+
+    /// Binds the fragment output `name` to `color_number`, as source `index`
+    /// of dual-source blending. This needs `GL_ARB_blend_func_extended` or
+    /// `GL_EXT_blend_func_extended`.
+    pub fn bind_frag_data_location_indexed(
+        mut self,
+        color_number: GLuint,
+        index: GLuint,
+        name: &'a str,
+    ) -> ProgramBuilder<'a> {
+        self.frag_data_locations.push((color_number, index, name));
+        self
+    }
+
+    /// Compiles every stage, stopping at the first that fails, and links
+    /// them. The shaders are deleted either way.
+    pub fn build(&self, gl: &Rc<dyn Gl>) -> Result<Program, ShaderError> {
+        let mut shaders = Vec::with_capacity(self.stages.len());
+        for &(shader_type, source) in &self.stages {
+            let shader = Shader::new(gl, shader_type);
+            shader.source(&[source.as_bytes()]);
+            shader.compile();
+            if !shader.is_compiled() {
+                let log = shader.info_log();
+                return Err(ShaderError::Compile {
+                    shader_type,
+                    entries: parse_info_log(&log),
+                    log,
+                });
+            }
+            shaders.push(shader);
+        }
+
+        let program = Program::new(gl);
+        for shader in &shaders {
+            program.attach(shader);
+        }
+        for &(index, name) in &self.attrib_locations {
+            gl.bind_attrib_location(program.raw(), index, name);
+        }
+        for &(color_number, index, name) in &self.frag_data_locations {
+            gl.bind_frag_data_location_indexed(program.raw(), color_number, index, name);
+        }
+        program.link();
+        // Detached shaders are deleted as soon as they are dropped.
+        for shader in &shaders {
+            gl.detach_shader(program.raw(), shader.raw());
+        }
+        if !program.is_linked() {
+            let log = program.info_log();
+            return Err(ShaderError::Link {
+                entries: parse_info_log(&log),
+                log,
+            });
+        }
+        Ok(program)
+    }
+}
+
+/// An attribute, uniform or buffer variable of a program.
+///
+/// Arrays are named after their first element, such as `lights[0]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramVariable {
+    pub name: String,
+    /// The type, such as `FLOAT_VEC4` or `SAMPLER_2D`.
+    pub ty: GLenum,
+    /// The number of elements of an array, or 1.
+    pub array_size: GLint,
+    /// The location of an attribute, or of a uniform outside blocks.
+    pub location: Option<GLint>,
+    /// The index of the block a uniform or buffer variable is in, in
+    /// `uniform_blocks` or `storage_blocks`.
+    pub block_index: Option<GLuint>,
+    /// The byte offset of a variable in its block.
+    pub offset: Option<GLint>,
+    /// The bytes between the elements of an array in a block.
+    pub array_stride: Option<GLint>,
+    /// The bytes between the columns, or the rows, of a matrix in a block.
+    pub matrix_stride: Option<GLint>,
+}
+
+impl ProgramVariable {
+    fn new(name: String, ty: GLenum, array_size: GLint) -> ProgramVariable {
+        ProgramVariable {
+            name,
+            ty,
+            array_size,
+            location: None,
+            block_index: None,
+            offset: None,
+            array_stride: None,
+            matrix_stride: None,
+        }
+    }
+}
+
+/// A uniform block or shader storage block of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramBlock {
+    pub name: String,
+    /// The binding point the block's buffer is bound to.
+    pub binding: GLuint,
+    /// The smallest size, in bytes, of the range of the buffer bound to the
+    /// block.
+    pub data_size: GLint,
+}
+
+/// The active resources of a linked program, in the order of their index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramReflection {
+    pub attribs: Vec<ProgramVariable>,
+    pub uniforms: Vec<ProgramVariable>,
+    pub uniform_blocks: Vec<ProgramBlock>,
+    /// The shader storage blocks, only listed when the context has
+    /// `GetProgramResource*`.
+    pub storage_blocks: Vec<ProgramBlock>,
+    /// The variables of the shader storage blocks.
+    pub buffer_variables: Vec<ProgramVariable>,
+}
+
+impl ProgramReflection {
+    pub fn attrib(&self, name: &str) -> Option<&ProgramVariable> {
+        self.attribs.iter().find(|attrib| attrib.name == name)
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<&ProgramVariable> {
+        self.uniforms.iter().find(|uniform| uniform.name == name)
+    }
+}
+
+fn get_program_i(gl: &dyn Gl, program: GLuint, pname: GLenum) -> GLint {
+    let mut value = [0];
+    unsafe {
+        gl.get_program_iv(program, pname, &mut value);
+    }
+    value[0]
+}
+
+/// Lists the attributes, uniforms, uniform blocks and shader storage blocks
+/// of `program`, which must be linked, to check the program against what
+/// the caller binds to it once rather than looking up each name when
+/// drawing.
+///
+/// Uniform blocks need GL 3.1, GLES 3.0 or `GL_ARB_uniform_buffer_object`,
+/// and are left out without them, as are the layouts of uniforms. Storage
+/// blocks need `GetProgramResource*`, from GL 4.3, GLES 3.1 or
+/// `GL_ARB_program_interface_query`, and are left out without it.
+pub fn reflect_program(gl: &dyn Gl, program: GLuint) -> ProgramReflection {
+    let attribs = (0..get_program_i(gl, program, ffi::ACTIVE_ATTRIBUTES).max(0) as GLuint)
+        .map(|index| {
+            let (array_size, ty, name) = gl.get_active_attrib(program, index);
+            let location = gl.get_attrib_location(program, &name);
+            ProgramVariable {
+                location: Some(location).filter(|&location| location >= 0),
+                ..ProgramVariable::new(name, ty, array_size)
+            }
+        })
+        .collect();
+
+    let capabilities = gl.capabilities();
+    let has_blocks = [
+        "get_active_uniforms_iv",
+        "get_active_uniform_block_name",
+        "get_active_uniform_block_i",
+    ]
+    .iter()
+    .all(|method| capabilities.is_loaded(method));
+
+    let indices: Vec<GLuint> =
+        (0..get_program_i(gl, program, ffi::ACTIVE_UNIFORMS).max(0) as GLuint).collect();
+    // Without uniform blocks, every uniform has a location and no layout.
+    let uniforms_i = |pname| {
+        if has_blocks {
+            gl.get_active_uniforms_iv(program, indices.clone(), pname)
+        } else {
+            Vec::new()
+        }
+    };
+    let block_indices = uniforms_i(ffi::UNIFORM_BLOCK_INDEX);
+    let offsets = uniforms_i(ffi::UNIFORM_OFFSET);
+    let array_strides = uniforms_i(ffi::UNIFORM_ARRAY_STRIDE);
+    let matrix_strides = uniforms_i(ffi::UNIFORM_MATRIX_STRIDE);
+    let uniforms = indices
+        .iter()
+        .map(|&index| {
+            let (array_size, ty, name) = gl.get_active_uniform(program, index);
+            let index = index as usize;
+            let block_index = block_indices
+                .get(index)
+                .cloned()
+                .filter(|&block| block >= 0);
+            // Uniforms in blocks have a layout instead of a location.
+            let in_block = |values: &[GLint]| block_index.and(values.get(index).cloned());
+            let location = match block_index {
+                Some(_) => None,
+                None => Some(gl.get_uniform_location(program, &name)),
+            };
+            ProgramVariable {
+                location: location.filter(|&location| location >= 0),
+                block_index: block_index.map(|block| block as GLuint),
+                offset: in_block(&offsets),
+                array_stride: in_block(&array_strides),
+                matrix_stride: in_block(&matrix_strides),
+                ..ProgramVariable::new(name, ty, array_size)
+            }
+        })
+        .collect();
+
+    let block_count = if has_blocks {
+        get_program_i(gl, program, ffi::ACTIVE_UNIFORM_BLOCKS).max(0) as GLuint
+    } else {
+        0
+    };
+    let uniform_blocks = (0..block_count)
+        .map(|index| ProgramBlock {
+            name: gl.get_active_uniform_block_name(program, index),
+            binding: gl.get_active_uniform_block_i(program, index, ffi::UNIFORM_BLOCK_BINDING)
+                as GLuint,
+            data_size: gl.get_active_uniform_block_i(program, index, ffi::UNIFORM_BLOCK_DATA_SIZE),
+        })
+        .collect();
+
+    let mut reflection = ProgramReflection {
+        attribs,
+        uniforms,
+        uniform_blocks,
+        ..ProgramReflection::default()
+    };
+    let has_resources = [
+        "get_program_interface_i",
+        "get_program_resource_name",
+        "get_program_resource_iv",
+    ]
+    .iter()
+    .all(|method| capabilities.is_loaded(method));
+    if !has_resources {
+        return reflection;
+    }
+
+    let resources = |interface| {
+        let count = gl.get_program_interface_i(program, interface, ffi::ACTIVE_RESOURCES);
+        (0..count.max(0) as GLuint).map(move |index| {
+            let name = gl.get_program_resource_name(program, interface, index);
+            (index, name)
+        })
+    };
+    reflection.storage_blocks = resources(ffi::SHADER_STORAGE_BLOCK)
+        .map(|(index, name)| {
+            let props = [ffi::BUFFER_BINDING, ffi::BUFFER_DATA_SIZE];
+            let values =
+                gl.get_program_resource_iv(program, ffi::SHADER_STORAGE_BLOCK, index, &props);
+            let value = |prop: usize| values.get(prop).cloned().unwrap_or(0);
+            ProgramBlock {
+                name,
+                binding: value(0) as GLuint,
+                data_size: value(1),
+            }
+        })
+        .collect();
+    reflection.buffer_variables = resources(ffi::BUFFER_VARIABLE)
+        .map(|(index, name)| {
+            let props = [
+                ffi::TYPE,
+                ffi::ARRAY_SIZE,
+                ffi::BLOCK_INDEX,
+                ffi::OFFSET,
+                ffi::ARRAY_STRIDE,
+                ffi::MATRIX_STRIDE,
+            ];
+            let values = gl.get_program_resource_iv(program, ffi::BUFFER_VARIABLE, index, &props);
+            let value = |prop: usize| values.get(prop).cloned();
+            ProgramVariable {
+                block_index: value(2)
+                    .filter(|&block| block >= 0)
+                    .map(|block| block as GLuint),
+                offset: value(3),
+                array_stride: value(4),
+                matrix_stride: value(5),
+                ..ProgramVariable::new(name, value(0).unwrap_or(0) as GLenum, value(1).unwrap_or(1))
+            }
+        })
+        .collect();
+    reflection
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        severity: Severity,
+        location: Option<(u32, u32)>,
+        column: Option<u32>,
+        message: &str,
+    ) -> InfoLogEntry {
+        InfoLogEntry {
+            severity,
+            file: location.map(|(file, _)| file),
+            line: location.map(|(_, line)| line),
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn mesa_compile_log() {
+        let log = "0:5(10): error: `colr' undeclared\n\
+                   0:5(2): error: value of type vec4 cannot be assigned to variable of type error\n\
+                   1:3(1): warning: extension `GL_ARB_shading_language_420pack' unsupported in fragment shader\n";
+        assert_eq!(
+            parse_info_log(log),
+            vec![
+                entry(Severity::Error, Some((0, 5)), Some(10), "`colr' undeclared"),
+                entry(
+                    Severity::Error,
+                    Some((0, 5)),
+                    Some(2),
+                    "value of type vec4 cannot be assigned to variable of type error",
+                ),
+                entry(
+                    Severity::Warning,
+                    Some((1, 3)),
+                    Some(1),
+                    "extension `GL_ARB_shading_language_420pack' unsupported in fragment shader",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn mesa_link_log() {
+        let log = "error: fragment shader varying v_uv not written by vertex shader\n.\n\
+                   error: vertex shader lacks `main'\n";
+        assert_eq!(
+            parse_info_log(log),
+            vec![
+                entry(
+                    Severity::Error,
+                    None,
+                    None,
+                    "fragment shader varying v_uv not written by vertex shader",
+                ),
+                entry(Severity::Error, None, None, "vertex shader lacks `main'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nvidia_compile_log() {
+        let log = "0(5) : error C1008: undefined variable \"colr\"\n\
+                   0(9) : warning C7050: \"uv\" might be used before being initialized\n";
+        assert_eq!(
+            parse_info_log(log),
+            vec![
+                entry(
+                    Severity::Error,
+                    Some((0, 5)),
+                    None,
+                    "C1008: undefined variable \"colr\"",
+                ),
+                entry(
+                    Severity::Warning,
+                    Some((0, 9)),
+                    None,
+                    "C7050: \"uv\" might be used before being initialized",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn nvidia_link_log() {
+        // The diagnostics of each stage come under a header, and the ones of
+        // the link itself have no location.
+        let log = "Vertex info\n\
+                   -----------\n\
+                   0(12) : error C5145: must write to gl_Position\n\
+                   \n\
+                   Fragment info\n\
+                   -------------\n\
+                   0(3) : warning C7533: global variable gl_FragColor is deprecated after version 120\n\
+                   \n\
+                   Link info\n\
+                   ---------\n\
+                   error: \"u_matrix\" type mismatch between shaders\n";
+        assert_eq!(
+            parse_info_log(log),
+            vec![
+                entry(
+                    Severity::Error,
+                    Some((0, 12)),
+                    None,
+                    "C5145: must write to gl_Position",
+                ),
+                entry(
+                    Severity::Warning,
+                    Some((0, 3)),
+                    None,
+                    "C7533: global variable gl_FragColor is deprecated after version 120",
+                ),
+                entry(
+                    Severity::Error,
+                    None,
+                    None,
+                    "\"u_matrix\" type mismatch between shaders",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn angle_compile_log() {
+        // ANGLE on Windows ends its lines with CRLF.
+        let log = "WARNING: 0:2: 'GL_OES_standard_derivatives' : extension is not supported\r\n\
+                   ERROR: 0:4: 'colr' : undeclared identifier\r\n\
+                   ERROR: 0:4: 'assign' : cannot convert from 'const float' to 'out highp 4-component vector of float'\r\n";
+        assert_eq!(
+            parse_info_log(log),
+            vec![
+                entry(
+                    Severity::Warning,
+                    Some((0, 2)),
+                    None,
+                    "'GL_OES_standard_derivatives' : extension is not supported",
+                ),
+                entry(
+                    Severity::Error,
+                    Some((0, 4)),
+                    None,
+                    "'colr' : undeclared identifier",
+                ),
+                entry(
+                    Severity::Error,
+                    Some((0, 4)),
+                    None,
+                    "'assign' : cannot convert from 'const float' to 'out highp 4-component vector of float'",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn adreno_compile_log() {
+        // Adreno pads the messages with spaces and ends with a summary.
+        let log = "ERROR: 0:6: 'vec5' : no matching overloaded function found \n\
+                   ERROR: 0:6: '=' :  cannot convert from 'const mediump float' to 'highp 4-component vector of float'\n\
+                   ERROR: 2 compilation errors.  No code generated.\n\n";
+        assert_eq!(
+            parse_info_log(log),
+            vec![
+                entry(
+                    Severity::Error,
+                    Some((0, 6)),
+                    None,
+                    "'vec5' : no matching overloaded function found",
+                ),
+                entry(
+                    Severity::Error,
+                    Some((0, 6)),
+                    None,
+                    "'=' :  cannot convert from 'const mediump float' to 'highp 4-component vector of float'",
+                ),
+                entry(
+                    Severity::Error,
+                    None,
+                    None,
+                    "2 compilation errors.  No code generated.",
+                ),
+            ]
+        );
+    }
+}
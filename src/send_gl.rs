@@ -0,0 +1,138 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// A platform GL context, as created through EGL, GLX, WGL or CGL, that can
+/// be made current on the calling thread and released from it.
+pub trait GlContext: Send {
+    type Error;
+
+    /// Makes the context current on the calling thread.
+    fn make_current(&self) -> Result<(), Self::Error>;
+
+    /// Releases the context from the calling thread, so that another thread
+    /// can make it current.
+    fn release(&self);
+}
+
+/// A GL context with its loaded functions that can be moved to another
+/// thread. It owns the only handle to the functions, which is only
+/// reachable through the guard returned by `make_current`, so calls are only
+/// made while the context is current on the calling thread.
+///
+/// The functions are loaded as `Box<dyn Gl + Send>`, and `wrap` replaces
+/// them with a wrapper around them, such as a `StatsGl` whose statistics
+/// are then read through the guard.
+pub struct SendGl<C: GlContext, G = Box<dyn Gl + Send>> {
+    context: C,
+    gl: ManuallyDrop<G>,
+}
+
+impl<C: GlContext> SendGl<C> {
+    /// Makes `context` current on this thread to load its functions, as
+    /// `AutoFns::load_with` does, then releases it. The context must not be
+    /// current on any other thread.
+    ///
+    /// # Safety
+    ///
+    /// See `GlFns::load_with_report`.
+    pub unsafe fn load_with<F>(context: C, loadfn: F) -> Result<SendGl<C>, C::Error>
+    where
+        F: FnMut(&str) -> *const c_void,
+    {
+        context.make_current()?;
+        let gl = AutoFns::load_send(loadfn);
+        context.release();
+        Ok(SendGl {
+            context,
+            gl: ManuallyDrop::new(gl),
+        })
+    }
+}
+
+impl<C: GlContext, G: SharedGl> SendGl<C, G> {
+    /// Replaces the functions with a wrapper around them, such as the one
+    /// returned by `StatsGl::wrap_send`. The context is current on this
+    /// thread while `wrap` runs.
+    pub fn wrap<H, W>(self, wrap: W) -> Result<SendGl<C, H>, C::Error>
+    where
+        H: SharedGl,
+        W: FnOnce(G) -> H,
+    {
+        self.context.make_current()?;
+        let (context, gl) = self.into_parts();
+        let gl = wrap(gl);
+        context.release();
+        Ok(SendGl {
+            context,
+            gl: ManuallyDrop::new(gl),
+        })
+    }
+
+    /// Makes the context current on this thread until the returned guard is
+    /// dropped.
+    pub fn make_current<'a>(&'a mut self) -> Result<CurrentGl<'a, C, G>, C::Error> {
+        self.context.make_current()?;
+        Ok(CurrentGl {
+            handle: self,
+            not_send: PhantomData,
+        })
+    }
+
+    /// Drops the functions, with the context current on this thread, and
+    /// returns the context.
+    pub fn into_context(self) -> Result<C, C::Error> {
+        self.context.make_current()?;
+        let (context, gl) = self.into_parts();
+        drop(gl);
+        context.release();
+        Ok(context)
+    }
+
+    /// Takes the context and the functions out without dropping either.
+    fn into_parts(self) -> (C, G) {
+        let mut this = ManuallyDrop::new(self);
+        unsafe { (ptr::read(&this.context), ManuallyDrop::take(&mut this.gl)) }
+    }
+}
+
+impl<C: GlContext, G> Drop for SendGl<C, G> {
+    fn drop(&mut self) {
+        // Dropping the functions may make calls, such as unregistering the
+        // debug callback. They are leaked if the context can't be made
+        // current.
+        if self.context.make_current().is_ok() {
+            unsafe {
+                ManuallyDrop::drop(&mut self.gl);
+            }
+            self.context.release();
+        }
+    }
+}
+
+/// A context made current on this thread by `SendGl::make_current`, which
+/// dereferences to its functions and releases it when dropped. It can't
+/// leave the thread.
+pub struct CurrentGl<'a, C: GlContext + 'a, G: 'a> {
+    handle: &'a mut SendGl<C, G>,
+    not_send: PhantomData<*const ()>,
+}
+
+impl<'a, C: GlContext, G> Deref for CurrentGl<'a, C, G> {
+    type Target = G;
+
+    fn deref(&self) -> &G {
+        &self.handle.gl
+    }
+}
+
+impl<'a, C: GlContext, G> Drop for CurrentGl<'a, C, G> {
+    fn drop(&mut self) {
+        self.handle.context.release();
+    }
+}
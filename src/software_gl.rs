@@ -0,0 +1,3171 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Stands in for a compiled vertex shader. It receives the attribute values
+/// of one vertex, indexed by attribute location, pushes its varyings and
+/// returns the clip-space position.
+pub type SoftwareVertexShader =
+    dyn Fn(&SoftwareShaderContext, &SoftwareVertex, &mut Vec<f32>) -> [f32; 4];
+
+/// Stands in for a compiled fragment shader. Returning `None` discards the
+/// fragment.
+pub type SoftwareFragmentShader =
+    dyn Fn(&SoftwareShaderContext, &SoftwareFragment) -> Option<[f32; 4]>;
+
+pub struct SoftwareVertex<'a> {
+    pub attribs: &'a [[f32; 4]],
+    pub vertex_id: GLint,
+    pub instance_id: GLint,
+}
+
+pub struct SoftwareFragment<'a> {
+    /// Window coordinates, depth and 1/w, like `gl_FragCoord`.
+    pub coord: [f32; 4],
+    pub front_facing: bool,
+    pub varyings: &'a [f32],
+}
+
+/// Uniforms and textures visible to software shaders during a draw.
+pub struct SoftwareShaderContext<'a> {
+    uniforms: HashMap<String, Vec<f32>>,
+    samplers: Vec<Option<SoftwareSampler<'a>>>,
+}
+
+impl<'a> SoftwareShaderContext<'a> {
+    /// The value last set for the uniform `name`. Only uniforms whose
+    /// location was queried with `get_uniform_location` are known.
+    pub fn uniform(&self, name: &str) -> &[f32] {
+        self.uniforms.get(name).map_or(&[], |v| &v[..])
+    }
+
+    /// Samples level 0 of the `TEXTURE_2D` bound to `unit`, honoring the
+    /// texture's magnification filter and wrap modes.
+    pub fn sample(&self, unit: GLuint, s: f32, t: f32) -> [f32; 4] {
+        let sampler = match self.samplers.get(unit as usize) {
+            Some(Some(sampler)) => sampler,
+            _ => return [0.0, 0.0, 0.0, 1.0],
+        };
+        let u = s * sampler.image.width as f32;
+        let v = t * sampler.image.height as f32;
+        if sampler.filter == ffi::NEAREST {
+            return sampler.texel(u.floor() as GLint, v.floor() as GLint);
+        }
+        let (u, v) = (u - 0.5, v - 0.5);
+        let (x, y) = (u.floor(), v.floor());
+        let (fx, fy) = (u - x, v - y);
+        let (x, y) = (x as GLint, y as GLint);
+        let mut result = [0.0; 4];
+        for (dx, dy, weight) in [
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            let texel = sampler.texel(x + dx, y + dy);
+            for (r, c) in result.iter_mut().zip(texel.iter()) {
+                *r += c * weight;
+            }
+        }
+        result
+    }
+}
+
+struct SoftwareSampler<'a> {
+    image: &'a SoftwareImage,
+    filter: GLenum,
+    wrap_s: GLenum,
+    wrap_t: GLenum,
+}
+
+impl<'a> SoftwareSampler<'a> {
+    fn texel(&self, x: GLint, y: GLint) -> [f32; 4] {
+        let x = software_wrap(self.wrap_s, x, self.image.width);
+        let y = software_wrap(self.wrap_t, y, self.image.height);
+        self.image.get(x, y, 0)
+    }
+}
+
+fn software_wrap(mode: GLenum, coord: GLint, size: GLsizei) -> GLint {
+    match mode {
+        ffi::REPEAT => coord.rem_euclid(size),
+        ffi::MIRRORED_REPEAT => {
+            let coord = coord.rem_euclid(2 * size);
+            if coord >= size {
+                2 * size - 1 - coord
+            } else {
+                coord
+            }
+        }
+        _ => coord.max(0).min(size - 1),
+    }
+}
+
+#[derive(Clone)]
+enum SoftwareShader {
+    Vertex(Rc<SoftwareVertexShader>),
+    Fragment(Rc<SoftwareFragmentShader>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum SoftwareSurface {
+    Texture(GLuint, GLenum, GLint),
+    Renderbuffer(GLuint),
+    DefaultColor,
+    DefaultDepth,
+}
+
+/// One layer of a surface together with the storage it was defined with.
+#[derive(Clone, Copy)]
+struct SoftwareView {
+    surface: SoftwareSurface,
+    format: GLenum,
+    width: GLsizei,
+    height: GLsizei,
+    depth: GLsizei,
+    layer: GLint,
+}
+
+fn software_texture_view(
+    state: &MockState,
+    name: GLuint,
+    target: GLenum,
+    level: GLint,
+    layer: GLint,
+) -> Option<SoftwareView> {
+    let texture = if name == 0 {
+        state.default_textures.get(&mock_binding_target(target))
+    } else {
+        state.textures.get(&name)
+    };
+    let storage = texture?.level(target, level)?;
+    Some(SoftwareView {
+        surface: SoftwareSurface::Texture(name, target, level),
+        format: storage.internal_format,
+        width: storage.width,
+        height: storage.height,
+        depth: storage.depth,
+        layer,
+    })
+}
+
+fn software_attachment_view(
+    state: &MockState,
+    attachment: &MockAttachment,
+) -> Option<SoftwareView> {
+    if attachment.object_type != ffi::RENDERBUFFER {
+        return software_texture_view(
+            state,
+            attachment.name,
+            attachment.target,
+            attachment.level,
+            attachment.layer,
+        );
+    }
+    let renderbuffer = state.renderbuffers.get(&attachment.name)?;
+    Some(SoftwareView {
+        surface: SoftwareSurface::Renderbuffer(attachment.name),
+        format: renderbuffer.internal_format,
+        width: renderbuffer.width,
+        height: renderbuffer.height,
+        depth: 1,
+        layer: 0,
+    })
+}
+
+fn software_is_depth(format: GLenum) -> bool {
+    matches!(
+        format,
+        ffi::DEPTH_COMPONENT
+            | ffi::DEPTH_COMPONENT16
+            | ffi::DEPTH_COMPONENT24
+            | ffi::DEPTH_COMPONENT32
+            | ffi::DEPTH_COMPONENT32F
+            | ffi::DEPTH_STENCIL
+            | ffi::DEPTH24_STENCIL8
+            | ffi::DEPTH32F_STENCIL8
+    )
+}
+
+/// Whether values stored in `format` are clamped and quantized to 8 bits.
+fn software_is_unorm8(format: GLenum) -> bool {
+    matches!(
+        format,
+        ffi::RED
+            | ffi::R8
+            | ffi::RG
+            | ffi::RG8
+            | ffi::RGB
+            | ffi::RGB8
+            | ffi::SRGB8
+            | ffi::RGB565
+            | ffi::RGBA
+            | ffi::RGBA8
+            | ffi::SRGB8_ALPHA8
+            | ffi::RGBA4
+            | ffi::RGB5_A1
+            | ffi::BGRA
+            | ffi::BGRA8_EXT
+            | ffi::ALPHA
+            | ffi::ALPHA8_EXT
+            | ffi::LUMINANCE
+            | ffi::LUMINANCE_ALPHA
+    )
+}
+
+/// Converts a color to what an image of `format` would hold.
+fn software_store(format: GLenum, color: [f32; 4]) -> [f32; 4] {
+    let mut color = match format {
+        ffi::RED | ffi::R8 | ffi::R16 | ffi::R16F | ffi::R32F => [color[0], 0.0, 0.0, 1.0],
+        ffi::RG | ffi::RG8 | ffi::RG16 | ffi::RG16F | ffi::RG32F => [color[0], color[1], 0.0, 1.0],
+        ffi::RGB | ffi::RGB8 | ffi::SRGB8 | ffi::RGB565 | ffi::RGB16F | ffi::RGB32F => {
+            [color[0], color[1], color[2], 1.0]
+        }
+        ffi::ALPHA | ffi::ALPHA8_EXT => [0.0, 0.0, 0.0, color[3]],
+        format if software_is_depth(format) => [color[0].clamp(0.0, 1.0), 0.0, 0.0, 1.0],
+        _ => color,
+    };
+    if software_is_unorm8(format) {
+        for c in color.iter_mut() {
+            *c = (c.clamp(0.0, 1.0) * 255.0).round() / 255.0;
+        }
+    }
+    color
+}
+
+struct SoftwareImage {
+    format: GLenum,
+    width: GLsizei,
+    height: GLsizei,
+    depth: GLsizei,
+    texels: Vec<[f32; 4]>,
+}
+
+impl SoftwareImage {
+    fn new(view: &SoftwareView) -> SoftwareImage {
+        let len = view.width as usize * view.height as usize * view.depth as usize;
+        SoftwareImage {
+            format: view.format,
+            width: view.width,
+            height: view.height,
+            depth: view.depth,
+            texels: vec![[0.0; 4]; len],
+        }
+    }
+
+    fn matches(&self, view: &SoftwareView) -> bool {
+        self.format == view.format
+            && self.width == view.width
+            && self.height == view.height
+            && self.depth == view.depth
+    }
+
+    fn index(&self, x: GLint, y: GLint, z: GLint) -> Option<usize> {
+        if x < 0 || y < 0 || z < 0 || x >= self.width || y >= self.height || z >= self.depth {
+            return None;
+        }
+        Some(((z * self.height + y) * self.width + x) as usize)
+    }
+
+    fn get(&self, x: GLint, y: GLint, z: GLint) -> [f32; 4] {
+        self.index(x, y, z).map_or([0.0; 4], |i| self.texels[i])
+    }
+
+    fn set(&mut self, x: GLint, y: GLint, z: GLint, color: [f32; 4]) {
+        if let Some(i) = self.index(x, y, z) {
+            self.texels[i] = software_store(self.format, color);
+        }
+    }
+}
+
+/// Maps a client pixel format and type to the image components it carries,
+/// in memory order, and the size in bytes of each component.
+fn software_pixel_layout(format: GLenum, ty: GLenum) -> Option<(&'static [usize], usize)> {
+    let channels: &'static [usize] = match format {
+        ffi::RED | ffi::LUMINANCE | ffi::DEPTH_COMPONENT => &[0],
+        ffi::RG => &[0, 1],
+        ffi::RGB => &[0, 1, 2],
+        ffi::BGR => &[2, 1, 0],
+        ffi::RGBA => &[0, 1, 2, 3],
+        ffi::BGRA => &[2, 1, 0, 3],
+        ffi::ALPHA => &[3],
+        _ => return None,
+    };
+    let size = match ty {
+        ffi::UNSIGNED_BYTE => 1,
+        ffi::UNSIGNED_INT_8_8_8_8_REV if channels.len() == 4 => 1,
+        ffi::UNSIGNED_SHORT => 2,
+        ffi::FLOAT => 4,
+        _ => return None,
+    };
+    Some((channels, size))
+}
+
+fn software_unpack(format: GLenum, (channels, size): (&[usize], usize), bytes: &[u8]) -> [f32; 4] {
+    let mut color = [0.0, 0.0, 0.0, 1.0];
+    for (&channel, bytes) in channels.iter().zip(bytes.chunks(size)) {
+        color[channel] = match size {
+            1 => bytes[0] as f32 / 255.0,
+            2 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+            _ => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        };
+    }
+    if format == ffi::LUMINANCE {
+        color[1] = color[0];
+        color[2] = color[0];
+    }
+    color
+}
+
+fn software_pack((channels, size): (&[usize], usize), color: [f32; 4], bytes: &mut [u8]) {
+    for (&channel, bytes) in channels.iter().zip(bytes.chunks_mut(size)) {
+        let c = color[channel];
+        match size {
+            1 => bytes[0] = (c.clamp(0.0, 1.0) * 255.0).round() as u8,
+            2 => {
+                let c = (c.clamp(0.0, 1.0) * 65535.0).round() as u16;
+                bytes.copy_from_slice(&c.to_ne_bytes());
+            }
+            _ => bytes.copy_from_slice(&c.to_ne_bytes()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SoftwareState {
+    shaders: Vec<(String, SoftwareShader)>,
+    compiled: HashMap<GLuint, SoftwareShader>,
+    programs: HashMap<GLuint, (Rc<SoftwareVertexShader>, Rc<SoftwareFragmentShader>)>,
+    images: HashMap<SoftwareSurface, SoftwareImage>,
+}
+
+impl SoftwareState {
+    /// Removes the image backing `view` so it can be written while other
+    /// images are borrowed, allocating it if the storage was (re)defined.
+    fn take_image(&mut self, view: &SoftwareView) -> SoftwareImage {
+        match self.images.remove(&view.surface) {
+            Some(image) if image.matches(view) => image,
+            _ => SoftwareImage::new(view),
+        }
+    }
+
+    fn image_mut(&mut self, view: &SoftwareView) -> &mut SoftwareImage {
+        let image = self.take_image(view);
+        self.images.entry(view.surface).or_insert(image)
+    }
+}
+
+struct SoftwareBlend {
+    src_rgb: GLenum,
+    dst_rgb: GLenum,
+    src_alpha: GLenum,
+    dst_alpha: GLenum,
+    equation_rgb: GLenum,
+    equation_alpha: GLenum,
+    color: [f32; 4],
+}
+
+fn software_blend_factor(
+    factor: GLenum,
+    channel: usize,
+    src: &[f32; 4],
+    dst: &[f32; 4],
+    constant: &[f32; 4],
+) -> f32 {
+    match factor {
+        ffi::ZERO => 0.0,
+        ffi::SRC_COLOR => src[channel],
+        ffi::ONE_MINUS_SRC_COLOR => 1.0 - src[channel],
+        ffi::DST_COLOR => dst[channel],
+        ffi::ONE_MINUS_DST_COLOR => 1.0 - dst[channel],
+        ffi::SRC_ALPHA => src[3],
+        ffi::ONE_MINUS_SRC_ALPHA => 1.0 - src[3],
+        ffi::DST_ALPHA => dst[3],
+        ffi::ONE_MINUS_DST_ALPHA => 1.0 - dst[3],
+        ffi::CONSTANT_COLOR => constant[channel],
+        ffi::ONE_MINUS_CONSTANT_COLOR => 1.0 - constant[channel],
+        ffi::CONSTANT_ALPHA => constant[3],
+        ffi::ONE_MINUS_CONSTANT_ALPHA => 1.0 - constant[3],
+        ffi::SRC_ALPHA_SATURATE if channel < 3 => src[3].min(1.0 - dst[3]),
+        _ => 1.0,
+    }
+}
+
+impl SoftwareBlend {
+    fn apply(&self, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+        let mut result = [0.0; 4];
+        for (channel, r) in result.iter_mut().enumerate() {
+            let (src_factor, dst_factor, equation) = if channel < 3 {
+                (self.src_rgb, self.dst_rgb, self.equation_rgb)
+            } else {
+                (self.src_alpha, self.dst_alpha, self.equation_alpha)
+            };
+            let s =
+                src[channel] * software_blend_factor(src_factor, channel, &src, &dst, &self.color);
+            let d =
+                dst[channel] * software_blend_factor(dst_factor, channel, &src, &dst, &self.color);
+            *r = match equation {
+                ffi::FUNC_SUBTRACT => s - d,
+                ffi::FUNC_REVERSE_SUBTRACT => d - s,
+                ffi::MIN => src[channel].min(dst[channel]),
+                ffi::MAX => src[channel].max(dst[channel]),
+                _ => s + d,
+            };
+        }
+        result
+    }
+}
+
+fn software_depth_test(func: GLenum, z: f32, stored: f32) -> bool {
+    match func {
+        ffi::NEVER => false,
+        ffi::LESS => z < stored,
+        ffi::EQUAL => z == stored,
+        ffi::LEQUAL => z <= stored,
+        ffi::GREATER => z > stored,
+        ffi::NOTEQUAL => z != stored,
+        ffi::GEQUAL => z >= stored,
+        _ => true,
+    }
+}
+
+/// The fixed-function state a draw or clear depends on.
+struct SoftwarePipeline {
+    viewport: [f32; 4],
+    depth_range: (f32, f32),
+    depth_clamp: bool,
+    /// Scissor box intersected with the render target, as x0, y0, x1, y1.
+    clip: [GLint; 4],
+    cull: Option<GLenum>,
+    front_face: GLenum,
+    depth_func: Option<GLenum>,
+    depth_mask: bool,
+    blend: Option<SoftwareBlend>,
+    color_mask: [bool; 4],
+}
+
+impl SoftwarePipeline {
+    fn new(state: &MockState, width: GLsizei, height: GLsizei) -> SoftwarePipeline {
+        let int = |name: GLenum| state.integers[&name][0];
+        let enabled = |cap: GLenum| state.enabled.contains(&cap);
+        let viewport = &state.integers[&ffi::VIEWPORT];
+        let depth_range = &state.floats[&ffi::DEPTH_RANGE];
+        let mut clip = [0, 0, width, height];
+        if enabled(ffi::SCISSOR_TEST) {
+            let scissor = &state.integers[&ffi::SCISSOR_BOX];
+            clip = [
+                clip[0].max(scissor[0]),
+                clip[1].max(scissor[1]),
+                clip[2].min(scissor[0] + scissor[2]),
+                clip[3].min(scissor[1] + scissor[3]),
+            ];
+        }
+        let color_mask = &state.integers[&ffi::COLOR_WRITEMASK];
+        let blend_color = &state.floats[&ffi::BLEND_COLOR];
+        SoftwarePipeline {
+            viewport: [
+                viewport[0] as f32,
+                viewport[1] as f32,
+                viewport[2] as f32,
+                viewport[3] as f32,
+            ],
+            depth_range: (depth_range[0], depth_range[1]),
+            depth_clamp: enabled(ffi::DEPTH_CLAMP),
+            clip,
+            cull: if enabled(ffi::CULL_FACE) {
+                Some(int(ffi::CULL_FACE_MODE) as GLenum)
+            } else {
+                None
+            },
+            front_face: int(ffi::FRONT_FACE) as GLenum,
+            depth_func: if enabled(ffi::DEPTH_TEST) {
+                Some(int(ffi::DEPTH_FUNC) as GLenum)
+            } else {
+                None
+            },
+            depth_mask: int(ffi::DEPTH_WRITEMASK) != 0,
+            blend: if enabled(ffi::BLEND) {
+                Some(SoftwareBlend {
+                    src_rgb: int(ffi::BLEND_SRC_RGB) as GLenum,
+                    dst_rgb: int(ffi::BLEND_DST_RGB) as GLenum,
+                    src_alpha: int(ffi::BLEND_SRC_ALPHA) as GLenum,
+                    dst_alpha: int(ffi::BLEND_DST_ALPHA) as GLenum,
+                    equation_rgb: int(ffi::BLEND_EQUATION_RGB) as GLenum,
+                    equation_alpha: int(ffi::BLEND_EQUATION_ALPHA) as GLenum,
+                    color: [
+                        blend_color[0],
+                        blend_color[1],
+                        blend_color[2],
+                        blend_color[3],
+                    ],
+                })
+            } else {
+                None
+            },
+            color_mask: [
+                color_mask[0] != 0,
+                color_mask[1] != 0,
+                color_mask[2] != 0,
+                color_mask[3] != 0,
+            ],
+        }
+    }
+
+    /// Maps a clip-space position to window x, y, depth and 1/w. Vertices
+    /// behind the eye are not clipped, so primitives touching them are
+    /// dropped.
+    fn window(&self, position: &[f32; 4]) -> Option<[f32; 4]> {
+        let w = position[3];
+        if w <= 0.0 {
+            return None;
+        }
+        let [vx, vy, vw, vh] = self.viewport;
+        let (near, far) = self.depth_range;
+        Some([
+            vx + (position[0] / w + 1.0) * vw / 2.0,
+            vy + (position[1] / w + 1.0) * vh / 2.0,
+            near + (position[2] / w + 1.0) * (far - near) / 2.0,
+            1.0 / w,
+        ])
+    }
+}
+
+struct SoftwareShadedVertex {
+    window: Option<[f32; 4]>,
+    varyings: Vec<f32>,
+}
+
+struct SoftwareTarget {
+    view: SoftwareView,
+    image: SoftwareImage,
+}
+
+/// Rasterizes primitives for one draw call into its render targets.
+struct SoftwareRasterizer<'a> {
+    pipeline: &'a SoftwarePipeline,
+    context: &'a SoftwareShaderContext<'a>,
+    fragment_shader: &'a SoftwareFragmentShader,
+    color: Option<&'a mut SoftwareTarget>,
+    depth: Option<&'a mut SoftwareTarget>,
+}
+
+impl<'a> SoftwareRasterizer<'a> {
+    fn fragment(
+        &mut self,
+        x: GLint,
+        y: GLint,
+        coord: [f32; 4],
+        front_facing: bool,
+        varyings: &[f32],
+    ) {
+        let pipeline = self.pipeline;
+        if x < pipeline.clip[0]
+            || y < pipeline.clip[1]
+            || x >= pipeline.clip[2]
+            || y >= pipeline.clip[3]
+        {
+            return;
+        }
+        let (near, far) = pipeline.depth_range;
+        let (near, far) = (near.min(far), near.max(far));
+        let z = if pipeline.depth_clamp {
+            coord[2].clamp(near, far)
+        } else if coord[2] < near || coord[2] > far {
+            return;
+        } else {
+            coord[2]
+        };
+        if let (Some(func), Some(depth)) = (pipeline.depth_func, self.depth.as_ref()) {
+            if !software_depth_test(func, z, depth.image.get(x, y, depth.view.layer)[0]) {
+                return;
+            }
+        }
+        let fragment = SoftwareFragment {
+            coord: [coord[0], coord[1], z, coord[3]],
+            front_facing,
+            varyings,
+        };
+        let mut color = match (self.fragment_shader)(self.context, &fragment) {
+            Some(color) => color,
+            None => return,
+        };
+        if let (Some(_), true, Some(depth)) = (
+            pipeline.depth_func,
+            pipeline.depth_mask,
+            self.depth.as_mut(),
+        ) {
+            depth.image.set(x, y, depth.view.layer, [z, 0.0, 0.0, 1.0]);
+        }
+        if let Some(target) = self.color.as_mut() {
+            let dst = target.image.get(x, y, target.view.layer);
+            if let Some(ref blend) = pipeline.blend {
+                if software_is_unorm8(target.image.format) {
+                    for c in color.iter_mut() {
+                        *c = c.clamp(0.0, 1.0);
+                    }
+                }
+                color = blend.apply(color, dst);
+            }
+            for (channel, c) in color.iter_mut().enumerate() {
+                if !pipeline.color_mask[channel] {
+                    *c = dst[channel];
+                }
+            }
+            target.image.set(x, y, target.view.layer, color);
+        }
+    }
+
+    fn point(&mut self, v: &SoftwareShadedVertex) {
+        if let Some(w) = v.window {
+            let coord = [w[0].floor() + 0.5, w[1].floor() + 0.5, w[2], w[3]];
+            self.fragment(
+                coord[0] as GLint,
+                coord[1] as GLint,
+                coord,
+                true,
+                &v.varyings,
+            );
+        }
+    }
+
+    fn line(&mut self, a: &SoftwareShadedVertex, b: &SoftwareShadedVertex) {
+        let (wa, wb) = match (a.window, b.window) {
+            (Some(wa), Some(wb)) => (wa, wb),
+            _ => return,
+        };
+        let steps = (wb[0] - wa[0])
+            .abs()
+            .max((wb[1] - wa[1]).abs())
+            .ceil()
+            .max(1.0) as usize;
+        let count = a.varyings.len().min(b.varyings.len());
+        let mut varyings = vec![0.0; count];
+        for step in 0..steps {
+            let t = (step as f32 + 0.5) / steps as f32;
+            let lerp = |i: usize| wa[i] + (wb[i] - wa[i]) * t;
+            let (x, y) = (lerp(0).floor(), lerp(1).floor());
+            let inv_w = lerp(3);
+            let (ka, kb) = ((1.0 - t) * wa[3] / inv_w, t * wb[3] / inv_w);
+            for (i, v) in varyings.iter_mut().enumerate() {
+                *v = a.varyings[i] * ka + b.varyings[i] * kb;
+            }
+            self.fragment(
+                x as GLint,
+                y as GLint,
+                [x + 0.5, y + 0.5, lerp(2), inv_w],
+                true,
+                &varyings,
+            );
+        }
+    }
+
+    fn triangle(
+        &mut self,
+        a: &SoftwareShadedVertex,
+        b: &SoftwareShadedVertex,
+        c: &SoftwareShadedVertex,
+    ) {
+        let (mut wa, mut wb, wc) = match (a.window, b.window, c.window) {
+            (Some(wa), Some(wb), Some(wc)) => (wa, wb, wc),
+            _ => return,
+        };
+        let edge = |p: &[f32; 4], q: &[f32; 4], x: f32, y: f32| {
+            (q[0] - p[0]) * (y - p[1]) - (q[1] - p[1]) * (x - p[0])
+        };
+        let area = edge(&wa, &wb, wc[0], wc[1]);
+        if area == 0.0 {
+            return;
+        }
+        let counter_clockwise = area > 0.0;
+        let front_facing = counter_clockwise == (self.pipeline.front_face == ffi::CCW);
+        match self.pipeline.cull {
+            Some(ffi::FRONT_AND_BACK) => return,
+            Some(ffi::FRONT) if front_facing => return,
+            Some(ffi::BACK) if !front_facing => return,
+            _ => {}
+        }
+        // Rasterize with counter-clockwise winding so the fill rule below
+        // holds, swapping the first two vertices back when interpolating.
+        let (mut va, mut vb) = (a, b);
+        if !counter_clockwise {
+            mem::swap(&mut wa, &mut wb);
+            mem::swap(&mut va, &mut vb);
+        }
+        let area = area.abs();
+        // Top-left fill rule: pixels exactly on an edge belong to the
+        // triangle only for left edges and top edges.
+        let top_left = |p: &[f32; 4], q: &[f32; 4]| {
+            let (dx, dy) = (q[0] - p[0], q[1] - p[1]);
+            dy < 0.0 || (dy == 0.0 && dx < 0.0)
+        };
+        let edges = [
+            (wb, wc, top_left(&wb, &wc)),
+            (wc, wa, top_left(&wc, &wa)),
+            (wa, wb, top_left(&wa, &wb)),
+        ];
+        let clip = self.pipeline.clip;
+        let x0 = (wa[0].min(wb[0]).min(wc[0]).floor() as GLint).max(clip[0]);
+        let y0 = (wa[1].min(wb[1]).min(wc[1]).floor() as GLint).max(clip[1]);
+        let x1 = (wa[0].max(wb[0]).max(wc[0]).ceil() as GLint).min(clip[2]);
+        let y1 = (wa[1].max(wb[1]).max(wc[1]).ceil() as GLint).min(clip[3]);
+        let count = va
+            .varyings
+            .len()
+            .min(vb.varyings.len())
+            .min(c.varyings.len());
+        let mut varyings = vec![0.0; count];
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let mut weights = [0.0; 3];
+                let mut inside = true;
+                for (weight, &(p, q, top_left)) in weights.iter_mut().zip(edges.iter()) {
+                    let e = edge(&p, &q, px, py);
+                    inside &= e > 0.0 || (e == 0.0 && top_left);
+                    *weight = e / area;
+                }
+                if !inside {
+                    continue;
+                }
+                let z = weights[0] * wa[2] + weights[1] * wb[2] + weights[2] * wc[2];
+                let inv_w = weights[0] * wa[3] + weights[1] * wb[3] + weights[2] * wc[3];
+                let k = [
+                    weights[0] * wa[3] / inv_w,
+                    weights[1] * wb[3] / inv_w,
+                    weights[2] * wc[3] / inv_w,
+                ];
+                for (i, v) in varyings.iter_mut().enumerate() {
+                    *v = va.varyings[i] * k[0] + vb.varyings[i] * k[1] + c.varyings[i] * k[2];
+                }
+                self.fragment(x, y, [px, py, z, inv_w], front_facing, &varyings);
+            }
+        }
+    }
+}
+
+/// How a draw call sources its vertex indices.
+enum SoftwareIndices {
+    Arrays(GLint),
+    Elements(GLenum, GLuint),
+}
+
+/// Reads the components of vertex attribute `attrib` for `element`, or
+/// `None` if the read falls outside the buffer.
+fn software_fetch(attrib: &MockVertexAttrib, data: &[u8], element: usize) -> Option<[f32; 4]> {
+    let size = match attrib.type_ {
+        ffi::BYTE | ffi::UNSIGNED_BYTE => 1,
+        ffi::SHORT | ffi::UNSIGNED_SHORT => 2,
+        ffi::INT | ffi::UNSIGNED_INT | ffi::FLOAT => 4,
+        _ => return None,
+    };
+    let stride = if attrib.stride == 0 {
+        attrib.size as usize * size
+    } else {
+        attrib.stride as usize
+    };
+    let start = attrib.offset as usize + element * stride;
+    let bytes = data.get(start..start + attrib.size as usize * size)?;
+    let normalized = attrib.normalized && !attrib.integer;
+    let mut value = [0.0, 0.0, 0.0, 1.0];
+    for (v, b) in value.iter_mut().zip(bytes.chunks(size)) {
+        *v = match attrib.type_ {
+            ffi::BYTE if normalized => (b[0] as i8 as f32 / 127.0).max(-1.0),
+            ffi::BYTE => b[0] as i8 as f32,
+            ffi::UNSIGNED_BYTE if normalized => b[0] as f32 / 255.0,
+            ffi::UNSIGNED_BYTE => b[0] as f32,
+            ffi::SHORT if normalized => {
+                (i16::from_ne_bytes([b[0], b[1]]) as f32 / 32767.0).max(-1.0)
+            }
+            ffi::SHORT => i16::from_ne_bytes([b[0], b[1]]) as f32,
+            ffi::UNSIGNED_SHORT if normalized => u16::from_ne_bytes([b[0], b[1]]) as f32 / 65535.0,
+            ffi::UNSIGNED_SHORT => u16::from_ne_bytes([b[0], b[1]]) as f32,
+            ffi::INT => i32::from_ne_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            ffi::UNSIGNED_INT => u32::from_ne_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            _ => f32::from_ne_bytes([b[0], b[1], b[2], b[3]]),
+        };
+    }
+    Some(value)
+}
+
+/// A CPU rasterizer behind the `Gl` trait, for pixel tests on machines
+/// without a GPU. State tracking and error checking are those of `MockGl`;
+/// on top of that, texture and renderbuffer contents are stored, and
+/// clears, draws, blits and pixel transfers actually run.
+///
+/// GLSL is not compiled. Instead, Rust closures are registered against a
+/// key, and a shader whose source contains the key runs that closure. A
+/// program linked from shaders with no registered closure draws nothing and
+/// logs a debug message.
+///
+/// Not supported: stencil operations, multisampling, mipmapped sampling,
+/// client-side vertex arrays, primitive restart, multiple render targets
+/// and clipping of primitives that cross the w = 0 plane.
+pub struct SoftwareGl {
+    mock: MockGl,
+    width: GLsizei,
+    height: GLsizei,
+    raster: RefCell<SoftwareState>,
+}
+
+impl SoftwareGl {
+    /// Creates a context whose default framebuffer is `width` by `height`
+    /// RGBA8 pixels with a depth buffer.
+    pub fn new(gl_type: GlType, width: GLsizei, height: GLsizei) -> Rc<SoftwareGl> {
+        let gl = SoftwareGl {
+            mock: MockGl::with_type(gl_type),
+            width,
+            height,
+            raster: RefCell::new(SoftwareState::default()),
+        };
+        gl.mock.viewport(0, 0, width, height);
+        gl.mock.scissor(0, 0, width, height);
+        Rc::new(gl)
+    }
+
+    /// Runs `shader` for vertex shaders compiled from a source containing
+    /// `key`. Later registrations take precedence.
+    pub fn register_vertex_shader<F>(&self, key: &str, shader: F)
+    where
+        F: Fn(&SoftwareShaderContext, &SoftwareVertex, &mut Vec<f32>) -> [f32; 4] + 'static,
+    {
+        let shader = SoftwareShader::Vertex(Rc::new(shader));
+        self.raster
+            .borrow_mut()
+            .shaders
+            .push((key.to_string(), shader));
+    }
+
+    /// Runs `shader` for fragment shaders compiled from a source containing
+    /// `key`. Later registrations take precedence.
+    pub fn register_fragment_shader<F>(&self, key: &str, shader: F)
+    where
+        F: Fn(&SoftwareShaderContext, &SoftwareFragment) -> Option<[f32; 4]> + 'static,
+    {
+        let shader = SoftwareShader::Fragment(Rc::new(shader));
+        self.raster
+            .borrow_mut()
+            .shaders
+            .push((key.to_string(), shader));
+    }
+
+    /// Runs `f` against the state tracker and reports whether it raised an
+    /// error. An error that was already pending stays the one `get_error`
+    /// returns.
+    fn validate<R, F: FnOnce(&MockGl) -> R>(&self, f: F) -> (R, bool) {
+        let pending = mem::replace(&mut self.mock.state.borrow_mut().error, ffi::NO_ERROR);
+        let rv = f(&self.mock);
+        let mut state = self.mock.state.borrow_mut();
+        let ok = state.error == ffi::NO_ERROR;
+        if pending != ffi::NO_ERROR {
+            state.error = pending;
+        }
+        (rv, ok)
+    }
+
+    fn default_view(&self, surface: SoftwareSurface) -> SoftwareView {
+        SoftwareView {
+            surface,
+            format: if surface == SoftwareSurface::DefaultDepth {
+                ffi::DEPTH_COMPONENT24
+            } else {
+                ffi::RGBA8
+            },
+            width: self.width,
+            height: self.height,
+            depth: 1,
+            layer: 0,
+        }
+    }
+
+    /// The color attachment selected by `buffer`, a draw or read buffer.
+    fn color_view(
+        &self,
+        state: &MockState,
+        framebuffer: GLuint,
+        buffer: GLenum,
+    ) -> Option<SoftwareView> {
+        if buffer == ffi::NONE {
+            return None;
+        }
+        if framebuffer == 0 {
+            return Some(self.default_view(SoftwareSurface::DefaultColor));
+        }
+        let attachment = if buffer >= ffi::COLOR_ATTACHMENT0
+            && buffer < ffi::COLOR_ATTACHMENT0 + MOCK_MAX_COLOR_ATTACHMENTS as GLenum
+        {
+            buffer
+        } else {
+            ffi::COLOR_ATTACHMENT0
+        };
+        let attachment = state
+            .framebuffers
+            .get(&framebuffer)?
+            .attachments
+            .get(&attachment)?;
+        software_attachment_view(state, attachment)
+    }
+
+    fn depth_view(&self, state: &MockState, framebuffer: GLuint) -> Option<SoftwareView> {
+        if framebuffer == 0 {
+            return Some(self.default_view(SoftwareSurface::DefaultDepth));
+        }
+        let attachments = &state.framebuffers.get(&framebuffer)?.attachments;
+        let attachment = attachments
+            .get(&ffi::DEPTH_ATTACHMENT)
+            .or_else(|| attachments.get(&ffi::DEPTH_STENCIL_ATTACHMENT))?;
+        software_attachment_view(state, attachment)
+    }
+
+    fn draw_views(&self, state: &MockState) -> (Option<SoftwareView>, Option<SoftwareView>) {
+        let draw_buffer = state.integers[&ffi::DRAW_BUFFER0][0] as GLenum;
+        (
+            self.color_view(state, state.draw_framebuffer, draw_buffer),
+            self.depth_view(state, state.draw_framebuffer),
+        )
+    }
+
+    fn read_view(&self, state: &MockState) -> Option<SoftwareView> {
+        let read_buffer = state.integers[&ffi::READ_BUFFER][0] as GLenum;
+        self.color_view(state, state.read_framebuffer, read_buffer)
+    }
+
+    fn bound_texture_view(&self, target: GLenum, level: GLint) -> Option<SoftwareView> {
+        let state = self.mock.state.borrow();
+        let name = state.texture_binding(mock_binding_target(target));
+        software_texture_view(&state, name, target, level, 0)
+    }
+
+    /// The contents of the bound pixel unpack buffer from `offset` on.
+    fn unpack_buffer_data(&self, offset: usize) -> Vec<u8> {
+        let state = self.mock.state.borrow();
+        let buffer = state.buffer_bindings[&ffi::PIXEL_UNPACK_BUFFER];
+        state.buffers[&buffer].data[offset..].to_vec()
+    }
+
+    /// Writes tightly packed client pixels into a box of a texture level.
+    fn upload(
+        &self,
+        target: GLenum,
+        level: GLint,
+        offset: (GLint, GLint, GLint),
+        size: (GLsizei, GLsizei, GLsizei),
+        (format, ty): (GLenum, GLenum),
+        data: Option<&[u8]>,
+    ) {
+        let view = match self.bound_texture_view(target, level) {
+            Some(view) => view,
+            None => return,
+        };
+        let layout = match software_pixel_layout(format, ty) {
+            Some(layout) => layout,
+            None => return self.mock.error(ffi::INVALID_ENUM),
+        };
+        let pixel_size = layout.0.len() * layout.1;
+        let mut raster = self.raster.borrow_mut();
+        let image = raster.image_mut(&view);
+        let mut i = 0;
+        for z in 0..size.2 {
+            for y in 0..size.1 {
+                for x in 0..size.0 {
+                    let color = match data {
+                        Some(data) => match data.get(i..i + pixel_size) {
+                            Some(bytes) => software_unpack(format, layout, bytes),
+                            None => return,
+                        },
+                        None => [0.0; 4],
+                    };
+                    image.set(offset.0 + x, offset.1 + y, offset.2 + z, color);
+                    i += pixel_size;
+                }
+            }
+        }
+    }
+
+    /// Packs a box of `image` into `output`, skipping pixels outside it.
+    fn download(
+        &self,
+        image: &SoftwareImage,
+        offset: (GLint, GLint, GLint),
+        size: (GLsizei, GLsizei, GLsizei),
+        (format, ty): (GLenum, GLenum),
+        output: &mut [u8],
+    ) {
+        let layout = match software_pixel_layout(format, ty) {
+            Some(layout) => layout,
+            None => return self.mock.error(ffi::INVALID_ENUM),
+        };
+        let pixel_size = layout.0.len() * layout.1;
+        let mut pixels = output.chunks_mut(pixel_size);
+        for z in 0..size.2 {
+            for y in 0..size.1 {
+                for x in 0..size.0 {
+                    let pixel = match pixels.next() {
+                        Some(pixel) => pixel,
+                        None => return,
+                    };
+                    let (x, y, z) = (offset.0 + x, offset.1 + y, offset.2 + z);
+                    if image.index(x, y, z).is_some() {
+                        software_pack(layout, image.get(x, y, z), pixel);
+                    }
+                }
+            }
+        }
+    }
+
+    fn read_into(
+        &self,
+        x: GLint,
+        y: GLint,
+        size: (GLsizei, GLsizei),
+        pixels: (GLenum, GLenum),
+        output: &mut [u8],
+    ) {
+        let view = match self.read_view(&self.mock.state.borrow()) {
+            Some(view) => view,
+            None => return,
+        };
+        let mut raster = self.raster.borrow_mut();
+        let image = raster.image_mut(&view);
+        self.download(
+            image,
+            (x, y, view.layer),
+            (size.0, size.1, 1),
+            pixels,
+            output,
+        );
+    }
+
+    /// Copies a rectangle of the read buffer into a texture level.
+    fn copy_to_texture(
+        &self,
+        target: GLenum,
+        level: GLint,
+        offset: (GLint, GLint, GLint),
+        x: GLint,
+        y: GLint,
+        (width, height): (GLsizei, GLsizei),
+    ) {
+        let src = match self.read_view(&self.mock.state.borrow()) {
+            Some(view) => view,
+            None => return,
+        };
+        let dst = match self.bound_texture_view(target, level) {
+            Some(view) => view,
+            None => return,
+        };
+        let mut raster = self.raster.borrow_mut();
+        let src_image = raster.take_image(&src);
+        {
+            let dst_image = raster.image_mut(&dst);
+            for j in 0..height {
+                for i in 0..width {
+                    let color = src_image.get(x + i, y + j, src.layer);
+                    dst_image.set(offset.0 + i, offset.1 + j, offset.2, color);
+                }
+            }
+        }
+        raster.images.insert(src.surface, src_image);
+    }
+
+    fn clear_buffers(&self, mask: GLbitfield) {
+        let state = self.mock.state.borrow();
+        let (color, depth) = self.draw_views(&state);
+        let mut raster = self.raster.borrow_mut();
+        let targets = [
+            (
+                color.filter(|_| mask & ffi::COLOR_BUFFER_BIT != 0),
+                ffi::COLOR_CLEAR_VALUE,
+            ),
+            (
+                depth.filter(|_| mask & ffi::DEPTH_BUFFER_BIT != 0),
+                ffi::DEPTH_CLEAR_VALUE,
+            ),
+        ];
+        for &(view, value) in &targets {
+            let view = match view {
+                Some(view) => view,
+                None => continue,
+            };
+            let pipeline = SoftwarePipeline::new(&state, view.width, view.height);
+            let value = &state.floats[&value];
+            let image = raster.image_mut(&view);
+            for y in pipeline.clip[1]..pipeline.clip[3] {
+                for x in pipeline.clip[0]..pipeline.clip[2] {
+                    if software_is_depth(view.format) {
+                        if pipeline.depth_mask {
+                            image.set(x, y, view.layer, [value[0], 0.0, 0.0, 1.0]);
+                        }
+                        continue;
+                    }
+                    let mut color = image.get(x, y, view.layer);
+                    for (channel, c) in color.iter_mut().enumerate() {
+                        if pipeline.color_mask[channel] {
+                            *c = value[channel];
+                        }
+                    }
+                    image.set(x, y, view.layer, color);
+                }
+            }
+        }
+    }
+
+    fn blit(&self, src: [GLint; 4], dst: [GLint; 4], mask: GLbitfield) {
+        let state = self.mock.state.borrow();
+        let mut pairs = Vec::new();
+        if mask & ffi::COLOR_BUFFER_BIT != 0 {
+            pairs.push((self.read_view(&state), self.draw_views(&state).0));
+        }
+        if mask & ffi::DEPTH_BUFFER_BIT != 0 {
+            pairs.push((
+                self.depth_view(&state, state.read_framebuffer),
+                self.depth_view(&state, state.draw_framebuffer),
+            ));
+        }
+        let mut raster = self.raster.borrow_mut();
+        for (src_view, dst_view) in pairs {
+            let (src_view, dst_view) = match (src_view, dst_view) {
+                (Some(src_view), Some(dst_view)) => (src_view, dst_view),
+                _ => continue,
+            };
+            let pipeline = SoftwarePipeline::new(&state, dst_view.width, dst_view.height);
+            let src_image = raster.take_image(&src_view);
+            let source = if src_view.surface == dst_view.surface {
+                Some(src_image.texels.clone())
+            } else {
+                None
+            };
+            raster.images.insert(src_view.surface, src_image);
+            let (x0, x1) = (dst[0].min(dst[2]), dst[0].max(dst[2]));
+            let (y0, y1) = (dst[1].min(dst[3]), dst[1].max(dst[3]));
+            let mut writes = Vec::new();
+            {
+                let src_image = &raster.images[&src_view.surface];
+                let texel = |x: GLint, y: GLint| match source {
+                    Some(ref texels) => src_image.index(x, y, src_view.layer).map(|i| texels[i]),
+                    None => src_image
+                        .index(x, y, src_view.layer)
+                        .map(|i| src_image.texels[i]),
+                };
+                for y in y0.max(pipeline.clip[1])..y1.min(pipeline.clip[3]) {
+                    for x in x0.max(pipeline.clip[0])..x1.min(pipeline.clip[2]) {
+                        // Sample the source at the pixel center, nearest
+                        // neighbor even for linear filtering.
+                        let tx = (x as f32 + 0.5 - dst[0] as f32) / (dst[2] - dst[0]) as f32;
+                        let ty = (y as f32 + 0.5 - dst[1] as f32) / (dst[3] - dst[1]) as f32;
+                        let sx = (src[0] as f32 + tx * (src[2] - src[0]) as f32).floor() as GLint;
+                        let sy = (src[1] as f32 + ty * (src[3] - src[1]) as f32).floor() as GLint;
+                        if let Some(color) = texel(sx, sy) {
+                            writes.push((x, y, color));
+                        }
+                    }
+                }
+            }
+            let dst_image = raster.image_mut(&dst_view);
+            for (x, y, color) in writes {
+                dst_image.set(x, y, dst_view.layer, color);
+            }
+        }
+    }
+
+    /// Compiles a shader by looking up a registered closure for its source.
+    fn compile(&self, shader: GLuint) {
+        let state = self.mock.state.borrow();
+        let shader_state = match state.shaders.get(&shader) {
+            Some(shader_state) if shader_state.compiled => shader_state,
+            _ => return,
+        };
+        let mut raster = self.raster.borrow_mut();
+        let found = raster
+            .shaders
+            .iter()
+            .rev()
+            .find(|(key, software)| {
+                let stage = match software {
+                    SoftwareShader::Vertex(_) => ffi::VERTEX_SHADER,
+                    SoftwareShader::Fragment(_) => ffi::FRAGMENT_SHADER,
+                };
+                stage == shader_state.shader_type && shader_state.source.contains(key.as_str())
+            })
+            .map(|(_, software)| software.clone());
+        match found {
+            Some(software) => raster.compiled.insert(shader, software),
+            None => raster.compiled.remove(&shader),
+        };
+    }
+
+    fn link(&self, program: GLuint) {
+        let state = self.mock.state.borrow();
+        let mut raster = self.raster.borrow_mut();
+        let (mut vertex, mut fragment) = (None, None);
+        if let Some(p) = state.programs.get(&program).filter(|p| p.linked) {
+            for shader in &p.shaders {
+                match raster.compiled.get(shader) {
+                    Some(SoftwareShader::Vertex(shader)) => vertex = Some(shader.clone()),
+                    Some(SoftwareShader::Fragment(shader)) => fragment = Some(shader.clone()),
+                    None => {}
+                }
+            }
+        }
+        match (vertex, fragment) {
+            (Some(vertex), Some(fragment)) => raster.programs.insert(program, (vertex, fragment)),
+            _ => raster.programs.remove(&program),
+        };
+    }
+
+    fn draw(&self, mode: GLenum, indices: SoftwareIndices, count: GLsizei, instances: GLsizei) {
+        if let Err(message) = self.rasterize(mode, indices, count, instances) {
+            self.mock.state.borrow_mut().log_debug_message(
+                format!("SoftwareGl: {}", message),
+                DebugSource::ThirdParty,
+                DebugType::Other,
+                0,
+                DebugSeverity::Medium,
+            );
+            self.mock.deliver_debug_messages();
+        }
+    }
+
+    fn rasterize(
+        &self,
+        mode: GLenum,
+        indices: SoftwareIndices,
+        count: GLsizei,
+        instances: GLsizei,
+    ) -> Result<(), String> {
+        let state = self.mock.state.borrow();
+        let mut raster = self.raster.borrow_mut();
+        let (vertex_shader, fragment_shader) = match raster.programs.get(&state.program) {
+            Some(shaders) => shaders.clone(),
+            None => {
+                return Err(format!(
+                    "program {} has no registered shaders",
+                    state.program
+                ));
+            }
+        };
+
+        let vao = &state.vertex_arrays[&state.vertex_array];
+        let ids: Vec<GLint> = match indices {
+            SoftwareIndices::Arrays(first) => (first..first + count).collect(),
+            SoftwareIndices::Elements(ty, offset) => {
+                let size = match ty {
+                    ffi::UNSIGNED_BYTE => 1,
+                    ffi::UNSIGNED_SHORT => 2,
+                    ffi::UNSIGNED_INT => 4,
+                    _ => return Err(format!("unsupported index type {:#x}", ty)),
+                };
+                let data = &state.buffers[&vao.element_buffer].data;
+                let start = offset as usize;
+                let bytes = data
+                    .get(start..start + count as usize * size)
+                    .ok_or("index buffer overrun")?;
+                bytes
+                    .chunks(size)
+                    .map(|b| match size {
+                        1 => b[0] as GLint,
+                        2 => u16::from_ne_bytes([b[0], b[1]]) as GLint,
+                        _ => u32::from_ne_bytes([b[0], b[1], b[2], b[3]]) as GLint,
+                    })
+                    .collect()
+            }
+        };
+
+        let program = &state.programs[&state.program];
+        let mut uniforms = HashMap::new();
+        for (location, name) in program.uniforms.iter().enumerate() {
+            if let Some(values) = program.uniform_values.get(&(location as GLint)) {
+                uniforms.insert(name.clone(), values.iter().map(|v| *v as f32).collect());
+            }
+        }
+
+        let mut sampled = Vec::new();
+        for unit in 0..MOCK_MAX_TEXTURE_UNITS {
+            let name = state
+                .texture_bindings
+                .get(&(unit, ffi::TEXTURE_2D))
+                .cloned()
+                .unwrap_or(0);
+            let texture = if name == 0 {
+                state.default_textures.get(&ffi::TEXTURE_2D)
+            } else {
+                state.textures.get(&name)
+            };
+            let view = software_texture_view(&state, name, ffi::TEXTURE_2D, 0, 0);
+            sampled.push(match (texture, view) {
+                (Some(texture), Some(view)) => {
+                    raster.image_mut(&view);
+                    let param = |name| texture.param(name).unwrap_or(0.0) as GLenum;
+                    Some((
+                        view,
+                        param(ffi::TEXTURE_MAG_FILTER),
+                        param(ffi::TEXTURE_WRAP_S),
+                        param(ffi::TEXTURE_WRAP_T),
+                    ))
+                }
+                _ => None,
+            });
+        }
+
+        let (color_view, depth_view) = self.draw_views(&state);
+        let mut color = color_view.map(|view| SoftwareTarget {
+            view,
+            image: raster.take_image(&view),
+        });
+        let mut depth = depth_view.map(|view| SoftwareTarget {
+            view,
+            image: raster.take_image(&view),
+        });
+        let (width, height) = [color_view, depth_view]
+            .iter()
+            .filter_map(|view| view.as_ref())
+            .fold((GLsizei::MAX, GLsizei::MAX), |size, view| {
+                (size.0.min(view.width), size.1.min(view.height))
+            });
+        let pipeline = SoftwarePipeline::new(&state, width, height);
+
+        let result = {
+            let context = SoftwareShaderContext {
+                uniforms,
+                samplers: sampled
+                    .iter()
+                    .map(|&sampler| {
+                        sampler.map(|(view, filter, wrap_s, wrap_t)| SoftwareSampler {
+                            image: &raster.images[&view.surface],
+                            filter,
+                            wrap_s,
+                            wrap_t,
+                        })
+                    })
+                    .collect(),
+            };
+            let mut rasterizer = SoftwareRasterizer {
+                pipeline: &pipeline,
+                context: &context,
+                fragment_shader: &*fragment_shader,
+                color: color.as_mut(),
+                depth: depth.as_mut(),
+            };
+            let mut attribs = [[0.0, 0.0, 0.0, 1.0]; MOCK_MAX_VERTEX_ATTRIBS as usize];
+            let mut result = Ok(());
+            for instance in 0..instances {
+                let mut vertices = Vec::with_capacity(ids.len());
+                for &id in &ids {
+                    for (location, value) in attribs.iter_mut().enumerate() {
+                        let location = location as GLuint;
+                        let current = state
+                            .current_attribs
+                            .get(&location)
+                            .cloned()
+                            .unwrap_or([0.0, 0.0, 0.0, 1.0]);
+                        *value = match vao.attribs.get(&location) {
+                            Some(attrib) if attrib.enabled => {
+                                let element = (instance as GLuint)
+                                    .checked_div(attrib.divisor)
+                                    .unwrap_or(id as GLuint)
+                                    as usize;
+                                state
+                                    .buffers
+                                    .get(&attrib.buffer)
+                                    .and_then(|buffer| {
+                                        software_fetch(attrib, &buffer.data, element)
+                                    })
+                                    .unwrap_or(current)
+                            }
+                            _ => current,
+                        };
+                    }
+                    let vertex = SoftwareVertex {
+                        attribs: &attribs,
+                        vertex_id: id,
+                        instance_id: instance,
+                    };
+                    let mut varyings = Vec::new();
+                    let position = vertex_shader(&context, &vertex, &mut varyings);
+                    vertices.push(SoftwareShadedVertex {
+                        window: pipeline.window(&position),
+                        varyings,
+                    });
+                }
+                let v = &vertices;
+                match mode {
+                    ffi::POINTS => v.iter().for_each(|a| rasterizer.point(a)),
+                    ffi::LINES => v
+                        .chunks_exact(2)
+                        .for_each(|l| rasterizer.line(&l[0], &l[1])),
+                    ffi::LINE_STRIP => v.windows(2).for_each(|l| rasterizer.line(&l[0], &l[1])),
+                    ffi::LINE_LOOP => {
+                        v.windows(2).for_each(|l| rasterizer.line(&l[0], &l[1]));
+                        if v.len() > 2 {
+                            rasterizer.line(&v[v.len() - 1], &v[0]);
+                        }
+                    }
+                    ffi::TRIANGLES => v
+                        .chunks_exact(3)
+                        .for_each(|t| rasterizer.triangle(&t[0], &t[1], &t[2])),
+                    ffi::TRIANGLE_STRIP => {
+                        for i in 2..v.len() {
+                            if i % 2 == 0 {
+                                rasterizer.triangle(&v[i - 2], &v[i - 1], &v[i]);
+                            } else {
+                                rasterizer.triangle(&v[i - 1], &v[i - 2], &v[i]);
+                            }
+                        }
+                    }
+                    ffi::TRIANGLE_FAN => {
+                        for i in 2..v.len() {
+                            rasterizer.triangle(&v[0], &v[i - 1], &v[i]);
+                        }
+                    }
+                    _ => {
+                        result = Err(format!("unsupported primitive mode {:#x}", mode));
+                        break;
+                    }
+                }
+            }
+            result
+        };
+
+        for target in color.into_iter().chain(depth) {
+            raster.images.insert(target.view.surface, target.image);
+        }
+        result
+    }
+}
+
+impl Gl for SoftwareGl {
+    fn capabilities(&self) -> &GlCapabilities {
+        self.mock.capabilities()
+    }
+
+    fn get_type(&self) -> GlType {
+        self.mock.get_type()
+    }
+
+    fn buffer_data_untyped(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        usage: GLenum,
+    ) {
+        self.mock.buffer_data_untyped(target, size, data, usage)
+    }
+
+    fn buffer_sub_data_untyped(
+        &self,
+        target: GLenum,
+        offset: isize,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+    ) {
+        self.mock
+            .buffer_sub_data_untyped(target, offset, size, data)
+    }
+
+    fn map_buffer(&self, target: GLenum, access: GLbitfield) -> *mut c_void {
+        self.mock.map_buffer(target, access)
+    }
+
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: GLintptr,
+        length: GLsizeiptr,
+        access: GLbitfield,
+    ) -> *mut c_void {
+        self.mock.map_buffer_range(target, offset, length, access)
+    }
+
+    fn unmap_buffer(&self, target: GLenum) -> GLboolean {
+        self.mock.unmap_buffer(target)
+    }
+
+    fn tex_buffer(&self, target: GLenum, internal_format: GLenum, buffer: GLuint) {
+        self.mock.tex_buffer(target, internal_format, buffer)
+    }
+
+    fn shader_source(&self, shader: GLuint, strings: &[&[u8]]) {
+        self.mock.shader_source(shader, strings)
+    }
+
+    fn read_buffer(&self, mode: GLenum) {
+        self.mock.read_buffer(mode)
+    }
+
+    fn read_pixels_into_buffer(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+        dst_buffer: &mut [u8],
+    ) -> Result<(), PixelDataError> {
+        let (rv, ok) = self.validate(|gl| {
+            gl.read_pixels_into_buffer(x, y, width, height, format, pixel_type, dst_buffer)
+        });
+        if ok && rv.is_ok() {
+            self.read_into(x, y, (width, height), (format, pixel_type), dst_buffer);
+        }
+        rv
+    }
+
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+    ) -> Result<Vec<u8>, PixelDataError> {
+        let mut pixels = vec![0; calculate_length(width, height, format, pixel_type)?];
+        self.read_pixels_into_buffer(x, y, width, height, format, pixel_type, &mut pixels)?;
+        Ok(pixels)
+    }
+
+    unsafe fn read_pixels_into_pbo(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+    ) {
+        let (_, ok) =
+            self.validate(|gl| gl.read_pixels_into_pbo(x, y, width, height, format, pixel_type));
+        if !ok {
+            return;
+        }
+        let buffer = self.mock.state.borrow().buffer_bindings[&ffi::PIXEL_PACK_BUFFER];
+        let mut pixels = mem::take(
+            &mut self
+                .mock
+                .state
+                .borrow_mut()
+                .buffers
+                .get_mut(&buffer)
+                .unwrap()
+                .data,
+        );
+        self.read_into(x, y, (width, height), (format, pixel_type), &mut pixels);
+        self.mock
+            .state
+            .borrow_mut()
+            .buffers
+            .get_mut(&buffer)
+            .unwrap()
+            .data = pixels;
+    }
+
+    fn sample_coverage(&self, value: GLclampf, invert: bool) {
+        self.mock.sample_coverage(value, invert)
+    }
+
+    fn polygon_offset(&self, factor: GLfloat, units: GLfloat) {
+        self.mock.polygon_offset(factor, units)
+    }
+
+    fn pixel_store_i(&self, name: GLenum, param: GLint) {
+        self.mock.pixel_store_i(name, param)
+    }
+
+    fn gen_buffers(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_buffers(n)
+    }
+
+    fn gen_renderbuffers(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_renderbuffers(n)
+    }
+
+    fn gen_framebuffers(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_framebuffers(n)
+    }
+
+    fn gen_textures(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_textures(n)
+    }
+
+    fn gen_vertex_arrays(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_vertex_arrays(n)
+    }
+
+    fn gen_vertex_arrays_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_vertex_arrays_apple(n)
+    }
+
+    fn gen_queries(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_queries(n)
+    }
+
+    fn begin_query(&self, target: GLenum, id: GLuint) {
+        self.mock.begin_query(target, id)
+    }
+
+    fn end_query(&self, target: GLenum) {
+        self.mock.end_query(target)
+    }
+
+    fn query_counter(&self, id: GLuint, target: GLenum) {
+        self.mock.query_counter(id, target)
+    }
+
+    fn get_query_iv(&self, target: GLenum, pname: GLenum) -> i32 {
+        self.mock.get_query_iv(target, pname)
+    }
+
+    fn get_query_object_iv(&self, id: GLuint, pname: GLenum) -> i32 {
+        self.mock.get_query_object_iv(id, pname)
+    }
+
+    fn get_query_object_uiv(&self, id: GLuint, pname: GLenum) -> u32 {
+        self.mock.get_query_object_uiv(id, pname)
+    }
+
+    fn get_query_object_i64v(&self, id: GLuint, pname: GLenum) -> i64 {
+        self.mock.get_query_object_i64v(id, pname)
+    }
+
+    fn get_query_object_ui64v(&self, id: GLuint, pname: GLenum) -> u64 {
+        self.mock.get_query_object_ui64v(id, pname)
+    }
+
+    fn delete_queries(&self, queries: &[GLuint]) {
+        self.mock.delete_queries(queries)
+    }
+
+    fn delete_vertex_arrays(&self, vertex_arrays: &[GLuint]) {
+        self.mock.delete_vertex_arrays(vertex_arrays)
+    }
+
+    fn delete_vertex_arrays_apple(&self, vertex_arrays: &[GLuint]) {
+        self.mock.delete_vertex_arrays_apple(vertex_arrays)
+    }
+
+    fn delete_buffers(&self, buffers: &[GLuint]) {
+        self.mock.delete_buffers(buffers)
+    }
+
+    fn delete_renderbuffers(&self, renderbuffers: &[GLuint]) {
+        self.mock.delete_renderbuffers(renderbuffers);
+        self.raster
+            .borrow_mut()
+            .images
+            .retain(|surface, _| match *surface {
+                SoftwareSurface::Renderbuffer(name) => !renderbuffers.contains(&name),
+                _ => true,
+            });
+    }
+
+    fn delete_framebuffers(&self, framebuffers: &[GLuint]) {
+        self.mock.delete_framebuffers(framebuffers)
+    }
+
+    fn delete_textures(&self, textures: &[GLuint]) {
+        self.mock.delete_textures(textures);
+        self.raster
+            .borrow_mut()
+            .images
+            .retain(|surface, _| match *surface {
+                SoftwareSurface::Texture(name, _, _) => name == 0 || !textures.contains(&name),
+                _ => true,
+            });
+    }
+
+    fn framebuffer_renderbuffer(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    ) {
+        self.mock
+            .framebuffer_renderbuffer(target, attachment, renderbuffertarget, renderbuffer)
+    }
+
+    fn renderbuffer_storage(
+        &self,
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.mock
+            .renderbuffer_storage(target, internalformat, width, height)
+    }
+
+    fn depth_func(&self, func: GLenum) {
+        self.mock.depth_func(func)
+    }
+
+    fn active_texture(&self, texture: GLenum) {
+        self.mock.active_texture(texture)
+    }
+
+    fn attach_shader(&self, program: GLuint, shader: GLuint) {
+        self.mock.attach_shader(program, shader)
+    }
+
+    fn bind_attrib_location(&self, program: GLuint, index: GLuint, name: &str) {
+        self.mock.bind_attrib_location(program, index, name)
+    }
+
+    unsafe fn get_uniform_iv(&self, program: GLuint, location: GLint, result: &mut [GLint]) {
+        self.mock.get_uniform_iv(program, location, result)
+    }
+
+    unsafe fn get_uniform_fv(&self, program: GLuint, location: GLint, result: &mut [GLfloat]) {
+        self.mock.get_uniform_fv(program, location, result)
+    }
+
+    fn get_uniform_block_index(&self, program: GLuint, name: &str) -> GLuint {
+        self.mock.get_uniform_block_index(program, name)
+    }
+
+    fn get_uniform_indices(&self, program: GLuint, names: &[&str]) -> Vec<GLuint> {
+        self.mock.get_uniform_indices(program, names)
+    }
+
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, buffer: GLuint) {
+        self.mock.bind_buffer_base(target, index, buffer)
+    }
+
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        size: GLsizeiptr,
+    ) {
+        self.mock
+            .bind_buffer_range(target, index, buffer, offset, size)
+    }
+
+    fn uniform_block_binding(
+        &self,
+        program: GLuint,
+        uniform_block_index: GLuint,
+        uniform_block_binding: GLuint,
+    ) {
+        self.mock
+            .uniform_block_binding(program, uniform_block_index, uniform_block_binding)
+    }
+
+    fn bind_buffer(&self, target: GLenum, buffer: GLuint) {
+        self.mock.bind_buffer(target, buffer)
+    }
+
+    fn bind_vertex_array(&self, vao: GLuint) {
+        self.mock.bind_vertex_array(vao)
+    }
+
+    fn bind_vertex_array_apple(&self, vao: GLuint) {
+        self.mock.bind_vertex_array_apple(vao)
+    }
+
+    fn bind_renderbuffer(&self, target: GLenum, renderbuffer: GLuint) {
+        self.mock.bind_renderbuffer(target, renderbuffer)
+    }
+
+    fn bind_framebuffer(&self, target: GLenum, framebuffer: GLuint) {
+        self.mock.bind_framebuffer(target, framebuffer)
+    }
+
+    fn bind_texture(&self, target: GLenum, texture: GLuint) {
+        self.mock.bind_texture(target, texture)
+    }
+
+    fn bind_vertex_buffer(
+        &self,
+        binding_index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        stride: GLint,
+    ) {
+        self.mock
+            .bind_vertex_buffer(binding_index, buffer, offset, stride)
+    }
+
+    fn draw_buffers(&self, bufs: &[GLenum]) {
+        self.mock.draw_buffers(bufs)
+    }
+
+    fn tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ty: GLenum,
+        opt_data: Option<&[u8]>,
+    ) -> Result<(), PixelDataError> {
+        let (rv, ok) = self.validate(|gl| {
+            gl.tex_image_2d(
+                target,
+                level,
+                internal_format,
+                width,
+                height,
+                border,
+                format,
+                ty,
+                opt_data,
+            )
+        });
+        if ok && rv.is_ok() {
+            self.upload(
+                target,
+                level,
+                (0, 0, 0),
+                (width, height, 1),
+                (format, ty),
+                opt_data,
+            );
+        }
+        rv
+    }
+
+    fn compressed_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        data: &[u8],
+    ) {
+        self.mock.compressed_tex_image_2d(
+            target,
+            level,
+            internal_format,
+            width,
+            height,
+            border,
+            data,
+        )
+    }
+
+    fn compressed_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        data: &[u8],
+    ) {
+        self.mock.compressed_tex_sub_image_2d(
+            target, level, xoffset, yoffset, width, height, format, data,
+        )
+    }
+
+    fn tex_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ty: GLenum,
+        opt_data: Option<&[u8]>,
+    ) -> Result<(), PixelDataError> {
+        let (rv, ok) = self.validate(|gl| {
+            gl.tex_image_3d(
+                target,
+                level,
+                internal_format,
+                width,
+                height,
+                depth,
+                border,
+                format,
+                ty,
+                opt_data,
+            )
+        });
+        if ok && rv.is_ok() {
+            self.upload(
+                target,
+                level,
+                (0, 0, 0),
+                (width, height, depth),
+                (format, ty),
+                opt_data,
+            );
+        }
+        rv
+    }
+
+    fn copy_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.copy_tex_image_2d(target, level, internal_format, x, y, width, height, border)
+        });
+        if ok {
+            self.copy_to_texture(target, level, (0, 0, 0), x, y, (width, height));
+        }
+    }
+
+    fn copy_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.copy_tex_sub_image_2d(target, level, xoffset, yoffset, x, y, width, height)
+        });
+        if ok {
+            self.copy_to_texture(target, level, (xoffset, yoffset, 0), x, y, (width, height));
+        }
+    }
+
+    fn copy_tex_sub_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.copy_tex_sub_image_3d(
+                target, level, xoffset, yoffset, zoffset, x, y, width, height,
+            )
+        });
+        if ok {
+            self.copy_to_texture(
+                target,
+                level,
+                (xoffset, yoffset, zoffset),
+                x,
+                y,
+                (width, height),
+            );
+        }
+    }
+
+    fn tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        data: &[u8],
+    ) -> Result<(), PixelDataError> {
+        let (rv, ok) = self.validate(|gl| {
+            gl.tex_sub_image_2d(
+                target, level, xoffset, yoffset, width, height, format, ty, data,
+            )
+        });
+        if ok && rv.is_ok() {
+            self.upload(
+                target,
+                level,
+                (xoffset, yoffset, 0),
+                (width, height, 1),
+                (format, ty),
+                Some(data),
+            );
+        }
+        rv
+    }
+
+    fn tex_sub_image_2d_pbo(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        offset: usize,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.tex_sub_image_2d_pbo(
+                target, level, xoffset, yoffset, width, height, format, ty, offset,
+            )
+        });
+        if ok {
+            let data = self.unpack_buffer_data(offset);
+            self.upload(
+                target,
+                level,
+                (xoffset, yoffset, 0),
+                (width, height, 1),
+                (format, ty),
+                Some(&data),
+            );
+        }
+    }
+
+    fn tex_sub_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        data: &[u8],
+    ) -> Result<(), PixelDataError> {
+        let (rv, ok) = self.validate(|gl| {
+            gl.tex_sub_image_3d(
+                target, level, xoffset, yoffset, zoffset, width, height, depth, format, ty, data,
+            )
+        });
+        if ok && rv.is_ok() {
+            let offset = (xoffset, yoffset, zoffset);
+            self.upload(
+                target,
+                level,
+                offset,
+                (width, height, depth),
+                (format, ty),
+                Some(data),
+            );
+        }
+        rv
+    }
+
+    fn tex_sub_image_3d_pbo(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        offset: usize,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.tex_sub_image_3d_pbo(
+                target, level, xoffset, yoffset, zoffset, width, height, depth, format, ty, offset,
+            )
+        });
+        if ok {
+            let data = self.unpack_buffer_data(offset);
+            let offset = (xoffset, yoffset, zoffset);
+            self.upload(
+                target,
+                level,
+                offset,
+                (width, height, depth),
+                (format, ty),
+                Some(&data),
+            );
+        }
+    }
+
+    fn tex_storage_2d(
+        &self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.mock
+            .tex_storage_2d(target, levels, internal_format, width, height)
+    }
+
+    fn tex_storage_3d(
+        &self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+    ) {
+        self.mock
+            .tex_storage_3d(target, levels, internal_format, width, height, depth)
+    }
+
+    fn get_tex_image_into_buffer(
+        &self,
+        target: GLenum,
+        level: GLint,
+        format: GLenum,
+        ty: GLenum,
+        output: &mut [u8],
+    ) -> Result<(), PixelDataError> {
+        let (rv, ok) =
+            self.validate(|gl| gl.get_tex_image_into_buffer(target, level, format, ty, output));
+        let view = match self.bound_texture_view(target, level) {
+            Some(view) if ok && rv.is_ok() => view,
+            _ => return rv,
+        };
+        let mut raster = self.raster.borrow_mut();
+        let image = raster.image_mut(&view);
+        self.download(
+            image,
+            (0, 0, 0),
+            (view.width, view.height, view.depth),
+            (format, ty),
+            output,
+        );
+        rv
+    }
+
+    unsafe fn copy_image_sub_data(
+        &self,
+        src_name: GLuint,
+        src_target: GLenum,
+        src_level: GLint,
+        src_x: GLint,
+        src_y: GLint,
+        src_z: GLint,
+        dst_name: GLuint,
+        dst_target: GLenum,
+        dst_level: GLint,
+        dst_x: GLint,
+        dst_y: GLint,
+        dst_z: GLint,
+        src_width: GLsizei,
+        src_height: GLsizei,
+        src_depth: GLsizei,
+    ) {
+        self.mock.copy_image_sub_data(
+            src_name, src_target, src_level, src_x, src_y, src_z, dst_name, dst_target, dst_level,
+            dst_x, dst_y, dst_z, src_width, src_height, src_depth,
+        )
+    }
+
+    fn invalidate_framebuffer(&self, target: GLenum, attachments: &[GLenum]) {
+        self.mock.invalidate_framebuffer(target, attachments)
+    }
+
+    fn invalidate_sub_framebuffer(
+        &self,
+        target: GLenum,
+        attachments: &[GLenum],
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.mock
+            .invalidate_sub_framebuffer(target, attachments, xoffset, yoffset, width, height)
+    }
+
+    unsafe fn get_integer_v(&self, name: GLenum, result: &mut [GLint]) {
+        self.mock.get_integer_v(name, result)
+    }
+
+    unsafe fn get_integer_64v(&self, name: GLenum, result: &mut [GLint64]) {
+        self.mock.get_integer_64v(name, result)
+    }
+
+    unsafe fn get_integer_iv(&self, name: GLenum, index: GLuint, result: &mut [GLint]) {
+        self.mock.get_integer_iv(name, index, result)
+    }
+
+    unsafe fn get_integer_64iv(&self, name: GLenum, index: GLuint, result: &mut [GLint64]) {
+        self.mock.get_integer_64iv(name, index, result)
+    }
+
+    unsafe fn get_boolean_v(&self, name: GLenum, result: &mut [GLboolean]) {
+        self.mock.get_boolean_v(name, result)
+    }
+
+    unsafe fn get_float_v(&self, name: GLenum, result: &mut [GLfloat]) {
+        self.mock.get_float_v(name, result)
+    }
+
+    fn get_framebuffer_attachment_parameter_iv(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        self.mock
+            .get_framebuffer_attachment_parameter_iv(target, attachment, pname)
+    }
+
+    fn get_renderbuffer_parameter_iv(&self, target: GLenum, pname: GLenum) -> GLint {
+        self.mock.get_renderbuffer_parameter_iv(target, pname)
+    }
+
+    fn get_tex_parameter_iv(&self, target: GLenum, name: GLenum) -> GLint {
+        self.mock.get_tex_parameter_iv(target, name)
+    }
+
+    fn get_tex_parameter_fv(&self, target: GLenum, name: GLenum) -> GLfloat {
+        self.mock.get_tex_parameter_fv(target, name)
+    }
+
+    fn tex_parameter_i(&self, target: GLenum, pname: GLenum, param: GLint) {
+        self.mock.tex_parameter_i(target, pname, param)
+    }
+
+    fn tex_parameter_f(&self, target: GLenum, pname: GLenum, param: GLfloat) {
+        self.mock.tex_parameter_f(target, pname, param)
+    }
+
+    fn framebuffer_texture_2d(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    ) {
+        self.mock
+            .framebuffer_texture_2d(target, attachment, textarget, texture, level)
+    }
+
+    fn framebuffer_texture_layer(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        texture: GLuint,
+        level: GLint,
+        layer: GLint,
+    ) {
+        self.mock
+            .framebuffer_texture_layer(target, attachment, texture, level, layer)
+    }
+
+    fn blit_framebuffer(
+        &self,
+        src_x0: GLint,
+        src_y0: GLint,
+        src_x1: GLint,
+        src_y1: GLint,
+        dst_x0: GLint,
+        dst_y0: GLint,
+        dst_x1: GLint,
+        dst_y1: GLint,
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.blit_framebuffer(
+                src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter,
+            )
+        });
+        if ok {
+            self.blit(
+                [src_x0, src_y0, src_x1, src_y1],
+                [dst_x0, dst_y0, dst_x1, dst_y1],
+                mask,
+            );
+        }
+    }
+
+    fn vertex_attrib_4f(&self, index: GLuint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+        self.mock.vertex_attrib_4f(index, x, y, z, w)
+    }
+
+    fn vertex_attrib_binding(&self, attrib_index: GLuint, binding_index: GLuint) {
+        self.mock.vertex_attrib_binding(attrib_index, binding_index)
+    }
+
+    fn vertex_attrib_pointer_f32(
+        &self,
+        index: GLuint,
+        size: GLint,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        self.mock
+            .vertex_attrib_pointer_f32(index, size, normalized, stride, offset)
+    }
+
+    fn vertex_attrib_pointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        self.mock
+            .vertex_attrib_pointer(index, size, type_, normalized, stride, offset)
+    }
+
+    fn vertex_attrib_i_pointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        self.mock
+            .vertex_attrib_i_pointer(index, size, type_, stride, offset)
+    }
+
+    fn vertex_attrib_divisor(&self, index: GLuint, divisor: GLuint) {
+        self.mock.vertex_attrib_divisor(index, divisor)
+    }
+
+    fn vertex_attrib_format(
+        &self,
+        attrib_index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: bool,
+        relative_offset: GLuint,
+    ) {
+        self.mock
+            .vertex_attrib_format(attrib_index, size, type_, normalized, relative_offset)
+    }
+
+    fn vertex_attrib_i_format(
+        &self,
+        attrib_index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        relative_offset: GLuint,
+    ) {
+        self.mock
+            .vertex_attrib_i_format(attrib_index, size, type_, relative_offset)
+    }
+
+    fn vertex_binding_divisor(&self, binding_index: GLuint, divisor: GLuint) {
+        self.mock.vertex_binding_divisor(binding_index, divisor)
+    }
+
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.mock.viewport(x, y, width, height)
+    }
+
+    fn scissor(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.mock.scissor(x, y, width, height)
+    }
+
+    fn line_width(&self, width: GLfloat) {
+        self.mock.line_width(width)
+    }
+
+    fn use_program(&self, program: GLuint) {
+        self.mock.use_program(program)
+    }
+
+    fn validate_program(&self, program: GLuint) {
+        self.mock.validate_program(program)
+    }
+
+    fn draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        let (_, ok) = self.validate(|gl| gl.draw_arrays(mode, first, count));
+        if ok {
+            self.draw(mode, SoftwareIndices::Arrays(first), count, 1);
+        }
+    }
+
+    fn draw_arrays_instanced(
+        &self,
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        primcount: GLsizei,
+    ) {
+        let (_, ok) = self.validate(|gl| gl.draw_arrays_instanced(mode, first, count, primcount));
+        if ok {
+            self.draw(mode, SoftwareIndices::Arrays(first), count, primcount);
+        }
+    }
+
+    fn draw_elements(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        element_type: GLenum,
+        indices_offset: GLuint,
+    ) {
+        let (_, ok) =
+            self.validate(|gl| gl.draw_elements(mode, count, element_type, indices_offset));
+        if ok {
+            self.draw(
+                mode,
+                SoftwareIndices::Elements(element_type, indices_offset),
+                count,
+                1,
+            );
+        }
+    }
+
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        element_type: GLenum,
+        indices_offset: GLuint,
+        primcount: GLsizei,
+    ) {
+        let (_, ok) = self.validate(|gl| {
+            gl.draw_elements_instanced(mode, count, element_type, indices_offset, primcount)
+        });
+        if ok {
+            let indices = SoftwareIndices::Elements(element_type, indices_offset);
+            self.draw(mode, indices, count, primcount);
+        }
+    }
+
+    fn blend_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.mock.blend_color(r, g, b, a)
+    }
+
+    fn blend_func(&self, sfactor: GLenum, dfactor: GLenum) {
+        self.mock.blend_func(sfactor, dfactor)
+    }
+
+    fn blend_func_separate(
+        &self,
+        src_rgb: GLenum,
+        dest_rgb: GLenum,
+        src_alpha: GLenum,
+        dest_alpha: GLenum,
+    ) {
+        self.mock
+            .blend_func_separate(src_rgb, dest_rgb, src_alpha, dest_alpha)
+    }
+
+    fn blend_equation(&self, mode: GLenum) {
+        self.mock.blend_equation(mode)
+    }
+
+    fn blend_equation_separate(&self, mode_rgb: GLenum, mode_alpha: GLenum) {
+        self.mock.blend_equation_separate(mode_rgb, mode_alpha)
+    }
+
+    fn color_mask(&self, r: bool, g: bool, b: bool, a: bool) {
+        self.mock.color_mask(r, g, b, a)
+    }
+
+    fn cull_face(&self, mode: GLenum) {
+        self.mock.cull_face(mode)
+    }
+
+    fn front_face(&self, mode: GLenum) {
+        self.mock.front_face(mode)
+    }
+
+    fn enable(&self, cap: GLenum) {
+        self.mock.enable(cap)
+    }
+
+    fn disable(&self, cap: GLenum) {
+        self.mock.disable(cap)
+    }
+
+    fn hint(&self, param_name: GLenum, param_val: GLenum) {
+        self.mock.hint(param_name, param_val)
+    }
+
+    fn is_enabled(&self, cap: GLenum) -> GLboolean {
+        self.mock.is_enabled(cap)
+    }
+
+    fn is_shader(&self, shader: GLuint) -> GLboolean {
+        self.mock.is_shader(shader)
+    }
+
+    fn is_texture(&self, texture: GLenum) -> GLboolean {
+        self.mock.is_texture(texture)
+    }
+
+    fn is_framebuffer(&self, framebuffer: GLenum) -> GLboolean {
+        self.mock.is_framebuffer(framebuffer)
+    }
+
+    fn is_renderbuffer(&self, renderbuffer: GLenum) -> GLboolean {
+        self.mock.is_renderbuffer(renderbuffer)
+    }
+
+    fn check_frame_buffer_status(&self, target: GLenum) -> GLenum {
+        self.mock.check_frame_buffer_status(target)
+    }
+
+    fn enable_vertex_attrib_array(&self, index: GLuint) {
+        self.mock.enable_vertex_attrib_array(index)
+    }
+
+    fn disable_vertex_attrib_array(&self, index: GLuint) {
+        self.mock.disable_vertex_attrib_array(index)
+    }
+
+    fn uniform_1f(&self, location: GLint, v0: GLfloat) {
+        self.mock.uniform_1f(location, v0)
+    }
+
+    fn uniform_1fv(&self, location: GLint, values: &[f32]) {
+        self.mock.uniform_1fv(location, values)
+    }
+
+    fn uniform_1i(&self, location: GLint, v0: GLint) {
+        self.mock.uniform_1i(location, v0)
+    }
+
+    fn uniform_1iv(&self, location: GLint, values: &[i32]) {
+        self.mock.uniform_1iv(location, values)
+    }
+
+    fn uniform_1ui(&self, location: GLint, v0: GLuint) {
+        self.mock.uniform_1ui(location, v0)
+    }
+
+    fn uniform_2f(&self, location: GLint, v0: GLfloat, v1: GLfloat) {
+        self.mock.uniform_2f(location, v0, v1)
+    }
+
+    fn uniform_2fv(&self, location: GLint, values: &[f32]) {
+        self.mock.uniform_2fv(location, values)
+    }
+
+    fn uniform_2i(&self, location: GLint, v0: GLint, v1: GLint) {
+        self.mock.uniform_2i(location, v0, v1)
+    }
+
+    fn uniform_2iv(&self, location: GLint, values: &[i32]) {
+        self.mock.uniform_2iv(location, values)
+    }
+
+    fn uniform_2ui(&self, location: GLint, v0: GLuint, v1: GLuint) {
+        self.mock.uniform_2ui(location, v0, v1)
+    }
+
+    fn uniform_3f(&self, location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+        self.mock.uniform_3f(location, v0, v1, v2)
+    }
+
+    fn uniform_3fv(&self, location: GLint, values: &[f32]) {
+        self.mock.uniform_3fv(location, values)
+    }
+
+    fn uniform_3i(&self, location: GLint, v0: GLint, v1: GLint, v2: GLint) {
+        self.mock.uniform_3i(location, v0, v1, v2)
+    }
+
+    fn uniform_3iv(&self, location: GLint, values: &[i32]) {
+        self.mock.uniform_3iv(location, values)
+    }
+
+    fn uniform_3ui(&self, location: GLint, v0: GLuint, v1: GLuint, v2: GLuint) {
+        self.mock.uniform_3ui(location, v0, v1, v2)
+    }
+
+    fn uniform_4f(&self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+        self.mock.uniform_4f(location, x, y, z, w)
+    }
+
+    fn uniform_4i(&self, location: GLint, x: GLint, y: GLint, z: GLint, w: GLint) {
+        self.mock.uniform_4i(location, x, y, z, w)
+    }
+
+    fn uniform_4iv(&self, location: GLint, values: &[i32]) {
+        self.mock.uniform_4iv(location, values)
+    }
+
+    fn uniform_4ui(&self, location: GLint, x: GLuint, y: GLuint, z: GLuint, w: GLuint) {
+        self.mock.uniform_4ui(location, x, y, z, w)
+    }
+
+    fn uniform_4fv(&self, location: GLint, values: &[f32]) {
+        self.mock.uniform_4fv(location, values)
+    }
+
+    fn uniform_matrix_2fv(&self, location: GLint, transpose: bool, value: &[f32]) {
+        self.mock.uniform_matrix_2fv(location, transpose, value)
+    }
+
+    fn uniform_matrix_3fv(&self, location: GLint, transpose: bool, value: &[f32]) {
+        self.mock.uniform_matrix_3fv(location, transpose, value)
+    }
+
+    fn uniform_matrix_4fv(&self, location: GLint, transpose: bool, value: &[f32]) {
+        self.mock.uniform_matrix_4fv(location, transpose, value)
+    }
+
+    fn depth_mask(&self, flag: bool) {
+        self.mock.depth_mask(flag)
+    }
+
+    fn depth_range(&self, near: f64, far: f64) {
+        self.mock.depth_range(near, far)
+    }
+
+    fn get_active_attrib(&self, program: GLuint, index: GLuint) -> (i32, u32, String) {
+        self.mock.get_active_attrib(program, index)
+    }
+
+    fn get_active_uniform(&self, program: GLuint, index: GLuint) -> (i32, u32, String) {
+        self.mock.get_active_uniform(program, index)
+    }
+
+    fn get_active_uniforms_iv(
+        &self,
+        program: GLuint,
+        indices: Vec<GLuint>,
+        pname: GLenum,
+    ) -> Vec<GLint> {
+        self.mock.get_active_uniforms_iv(program, indices, pname)
+    }
+
+    fn get_active_uniform_block_i(&self, program: GLuint, index: GLuint, pname: GLenum) -> GLint {
+        self.mock.get_active_uniform_block_i(program, index, pname)
+    }
+
+    fn get_active_uniform_block_iv(
+        &self,
+        program: GLuint,
+        index: GLuint,
+        pname: GLenum,
+    ) -> Vec<GLint> {
+        self.mock.get_active_uniform_block_iv(program, index, pname)
+    }
+
+    fn get_active_uniform_block_name(&self, program: GLuint, index: GLuint) -> String {
+        self.mock.get_active_uniform_block_name(program, index)
+    }
+
+    fn get_program_interface_i(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        self.mock
+            .get_program_interface_i(program, program_interface, pname)
+    }
+
+    fn get_program_resource_name(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+    ) -> String {
+        self.mock
+            .get_program_resource_name(program, program_interface, index)
+    }
+
+    fn get_program_resource_iv(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+        props: &[GLenum],
+    ) -> Vec<GLint> {
+        self.mock
+            .get_program_resource_iv(program, program_interface, index, props)
+    }
+
+    fn get_attrib_location(&self, program: GLuint, name: &str) -> c_int {
+        self.mock.get_attrib_location(program, name)
+    }
+
+    fn get_frag_data_location(&self, program: GLuint, name: &str) -> c_int {
+        self.mock.get_frag_data_location(program, name)
+    }
+
+    fn get_uniform_location(&self, program: GLuint, name: &str) -> c_int {
+        self.mock.get_uniform_location(program, name)
+    }
+
+    fn get_program_info_log(&self, program: GLuint) -> String {
+        self.mock.get_program_info_log(program)
+    }
+
+    unsafe fn get_program_iv(&self, program: GLuint, pname: GLenum, result: &mut [GLint]) {
+        self.mock.get_program_iv(program, pname, result)
+    }
+
+    fn get_program_binary(&self, program: GLuint) -> (Vec<u8>, GLenum) {
+        self.mock.get_program_binary(program)
+    }
+
+    fn program_binary(&self, program: GLuint, format: GLenum, binary: &[u8]) {
+        self.mock.program_binary(program, format, binary)
+    }
+
+    fn program_parameter_i(&self, program: GLuint, pname: GLenum, value: GLint) {
+        self.mock.program_parameter_i(program, pname, value)
+    }
+
+    unsafe fn get_vertex_attrib_iv(&self, index: GLuint, pname: GLenum, result: &mut [GLint]) {
+        self.mock.get_vertex_attrib_iv(index, pname, result)
+    }
+
+    unsafe fn get_vertex_attrib_fv(&self, index: GLuint, pname: GLenum, result: &mut [GLfloat]) {
+        self.mock.get_vertex_attrib_fv(index, pname, result)
+    }
+
+    fn get_vertex_attrib_pointer_v(&self, index: GLuint, pname: GLenum) -> GLsizeiptr {
+        self.mock.get_vertex_attrib_pointer_v(index, pname)
+    }
+
+    fn get_buffer_parameter_iv(&self, target: GLuint, pname: GLenum) -> GLint {
+        self.mock.get_buffer_parameter_iv(target, pname)
+    }
+
+    fn get_shader_info_log(&self, shader: GLuint) -> String {
+        self.mock.get_shader_info_log(shader)
+    }
+
+    fn get_string(&self, which: GLenum) -> String {
+        self.mock.get_string(which)
+    }
+
+    fn get_string_i(&self, which: GLenum, index: GLuint) -> String {
+        self.mock.get_string_i(which, index)
+    }
+
+    unsafe fn get_shader_iv(&self, shader: GLuint, pname: GLenum, result: &mut [GLint]) {
+        self.mock.get_shader_iv(shader, pname, result)
+    }
+
+    fn get_shader_precision_format(
+        &self,
+        shader_type: GLuint,
+        precision_type: GLuint,
+    ) -> (GLint, GLint, GLint) {
+        self.mock
+            .get_shader_precision_format(shader_type, precision_type)
+    }
+
+    fn compile_shader(&self, shader: GLuint) {
+        self.mock.compile_shader(shader);
+        self.compile(shader);
+    }
+
+    fn create_program(&self) -> GLuint {
+        self.mock.create_program()
+    }
+
+    fn delete_program(&self, program: GLuint) {
+        self.mock.delete_program(program)
+    }
+
+    fn create_shader(&self, shader_type: GLenum) -> GLuint {
+        self.mock.create_shader(shader_type)
+    }
+
+    fn delete_shader(&self, shader: GLuint) {
+        self.mock.delete_shader(shader)
+    }
+
+    fn detach_shader(&self, program: GLuint, shader: GLuint) {
+        self.mock.detach_shader(program, shader)
+    }
+
+    fn link_program(&self, program: GLuint) {
+        self.mock.link_program(program);
+        self.link(program);
+    }
+
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.mock.clear_color(r, g, b, a)
+    }
+
+    fn clear(&self, buffer_mask: GLbitfield) {
+        let (_, ok) = self.validate(|gl| gl.clear(buffer_mask));
+        if ok {
+            self.clear_buffers(buffer_mask);
+        }
+    }
+
+    fn clear_depth(&self, depth: f64) {
+        self.mock.clear_depth(depth)
+    }
+
+    fn clear_stencil(&self, s: GLint) {
+        self.mock.clear_stencil(s)
+    }
+
+    fn flush(&self) {
+        self.mock.flush()
+    }
+
+    fn finish(&self) {
+        self.mock.finish()
+    }
+
+    fn get_error(&self) -> GLenum {
+        self.mock.get_error()
+    }
+
+    fn stencil_mask(&self, mask: GLuint) {
+        self.mock.stencil_mask(mask)
+    }
+
+    fn stencil_mask_separate(&self, face: GLenum, mask: GLuint) {
+        self.mock.stencil_mask_separate(face, mask)
+    }
+
+    fn stencil_func(&self, func: GLenum, ref_: GLint, mask: GLuint) {
+        self.mock.stencil_func(func, ref_, mask)
+    }
+
+    fn stencil_func_separate(&self, face: GLenum, func: GLenum, ref_: GLint, mask: GLuint) {
+        self.mock.stencil_func_separate(face, func, ref_, mask)
+    }
+
+    fn stencil_op(&self, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        self.mock.stencil_op(sfail, dpfail, dppass)
+    }
+
+    fn stencil_op_separate(&self, face: GLenum, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        self.mock.stencil_op_separate(face, sfail, dpfail, dppass)
+    }
+
+    fn egl_image_target_texture2d_oes(&self, target: GLenum, image: GLeglImageOES) {
+        self.mock.egl_image_target_texture2d_oes(target, image)
+    }
+
+    fn egl_image_target_renderbuffer_storage_oes(&self, target: GLenum, image: GLeglImageOES) {
+        self.mock
+            .egl_image_target_renderbuffer_storage_oes(target, image)
+    }
+
+    fn generate_mipmap(&self, target: GLenum) {
+        self.mock.generate_mipmap(target)
+    }
+
+    fn insert_event_marker_ext(&self, message: &str) {
+        self.mock.insert_event_marker_ext(message)
+    }
+
+    fn push_group_marker_ext(&self, message: &str) {
+        self.mock.push_group_marker_ext(message)
+    }
+
+    fn pop_group_marker_ext(&self) {
+        self.mock.pop_group_marker_ext()
+    }
+
+    fn debug_message_insert_khr(
+        &self,
+        source: GLenum,
+        type_: GLenum,
+        id: GLuint,
+        severity: GLenum,
+        message: &str,
+    ) {
+        self.mock
+            .debug_message_insert_khr(source, type_, id, severity, message)
+    }
+
+    fn push_debug_group_khr(&self, source: GLenum, id: GLuint, message: &str) {
+        self.mock.push_debug_group_khr(source, id, message)
+    }
+
+    fn pop_debug_group_khr(&self) {
+        self.mock.pop_debug_group_khr()
+    }
+
+    fn fence_sync(&self, condition: GLenum, flags: GLbitfield) -> GLsync {
+        self.mock.fence_sync(condition, flags)
+    }
+
+    fn client_wait_sync(&self, sync: GLsync, flags: GLbitfield, timeout: GLuint64) -> GLenum {
+        self.mock.client_wait_sync(sync, flags, timeout)
+    }
+
+    fn wait_sync(&self, sync: GLsync, flags: GLbitfield, timeout: GLuint64) {
+        self.mock.wait_sync(sync, flags, timeout)
+    }
+
+    fn delete_sync(&self, sync: GLsync) {
+        self.mock.delete_sync(sync)
+    }
+
+    fn texture_range_apple(&self, target: GLenum, data: &[u8]) {
+        self.mock.texture_range_apple(target, data)
+    }
+
+    fn gen_fences_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        self.mock.gen_fences_apple(n)
+    }
+
+    fn delete_fences_apple(&self, fences: &[GLuint]) {
+        self.mock.delete_fences_apple(fences)
+    }
+
+    fn set_fence_apple(&self, fence: GLuint) {
+        self.mock.set_fence_apple(fence)
+    }
+
+    fn finish_fence_apple(&self, fence: GLuint) {
+        self.mock.finish_fence_apple(fence)
+    }
+
+    fn test_fence_apple(&self, fence: GLuint) {
+        self.mock.test_fence_apple(fence)
+    }
+
+    fn test_object_apple(&self, object: GLenum, name: GLuint) -> GLboolean {
+        self.mock.test_object_apple(object, name)
+    }
+
+    fn finish_object_apple(&self, object: GLenum, name: GLuint) {
+        self.mock.finish_object_apple(object, name)
+    }
+
+    fn blend_barrier_khr(&self) {
+        self.mock.blend_barrier_khr()
+    }
+
+    fn bind_frag_data_location_indexed(
+        &self,
+        program: GLuint,
+        color_number: GLuint,
+        index: GLuint,
+        name: &str,
+    ) {
+        self.mock
+            .bind_frag_data_location_indexed(program, color_number, index, name)
+    }
+
+    fn get_frag_data_index(&self, program: GLuint, name: &str) -> GLint {
+        self.mock.get_frag_data_index(program, name)
+    }
+
+    fn get_debug_messages(&self) -> Vec<DebugMessage> {
+        self.mock.get_debug_messages()
+    }
+
+    fn set_debug_callback(&self, callback: Option<DebugCallback>) {
+        self.mock.set_debug_callback(callback)
+    }
+
+    fn debug_message_control(
+        &self,
+        source: GLenum,
+        ty: GLenum,
+        severity: GLenum,
+        ids: &[GLuint],
+        enabled: bool,
+    ) {
+        self.mock
+            .debug_message_control(source, ty, severity, ids, enabled)
+    }
+
+    fn object_label(&self, identifier: GLenum, name: GLuint, label: &str) {
+        self.mock.object_label(identifier, name, label)
+    }
+
+    fn get_object_label(&self, identifier: GLenum, name: GLuint) -> String {
+        self.mock.get_object_label(identifier, name)
+    }
+
+    fn object_ptr_label(&self, sync: GLsync, label: &str) {
+        self.mock.object_ptr_label(sync, label)
+    }
+
+    fn provoking_vertex_angle(&self, mode: GLenum) {
+        self.mock.provoking_vertex_angle(mode)
+    }
+
+    fn copy_texture_chromium(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        internal_format: GLint,
+        dest_type: GLenum,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.mock.copy_texture_chromium(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            internal_format,
+            dest_type,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn copy_sub_texture_chromium(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        x_offset: GLint,
+        y_offset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.mock.copy_sub_texture_chromium(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            x_offset,
+            y_offset,
+            x,
+            y,
+            width,
+            height,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn copy_texture_3d_angle(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        internal_format: GLint,
+        dest_type: GLenum,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.mock.copy_texture_3d_angle(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            internal_format,
+            dest_type,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn copy_sub_texture_3d_angle(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        x_offset: GLint,
+        y_offset: GLint,
+        z_offset: GLint,
+        x: GLint,
+        y: GLint,
+        z: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.mock.copy_sub_texture_3d_angle(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            x_offset,
+            y_offset,
+            z_offset,
+            x,
+            y,
+            z,
+            width,
+            height,
+            depth,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn buffer_storage(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        flags: GLbitfield,
+    ) {
+        self.mock.buffer_storage(target, size, data, flags)
+    }
+
+    fn flush_mapped_buffer_range(&self, target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+        self.mock.flush_mapped_buffer_range(target, offset, length)
+    }
+
+    fn start_tiling_qcom(
+        &self,
+        x: GLuint,
+        y: GLuint,
+        width: GLuint,
+        height: GLuint,
+        preserve_mask: GLbitfield,
+    ) {
+        self.mock
+            .start_tiling_qcom(x, y, width, height, preserve_mask)
+    }
+
+    fn end_tiling_qcom(&self, preserve_mask: GLbitfield) {
+        self.mock.end_tiling_qcom(preserve_mask)
+    }
+}
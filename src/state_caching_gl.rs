@@ -0,0 +1,205 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The state `StateCachingGl` shadows, where a missing value is not known and
+/// the next call setting it is always forwarded.
+#[derive(Default)]
+struct StateCache {
+    active_texture: Option<GLenum>,
+    /// The texture bound to each target of each unit.
+    texture_bindings: HashMap<(GLenum, GLenum), GLuint>,
+    program: Option<GLuint>,
+    enabled: HashMap<GLenum, bool>,
+    blend_func: Option<(GLenum, GLenum, GLenum, GLenum)>,
+    blend_equation: Option<(GLenum, GLenum)>,
+    /// The buffer bound to each target, including the element array buffer
+    /// of the bound vertex array.
+    buffer_bindings: HashMap<GLenum, GLuint>,
+    vertex_array: Option<GLuint>,
+    /// The draw and read framebuffers.
+    framebuffers: HashMap<GLenum, GLuint>,
+    renderbuffer: Option<GLuint>,
+}
+
+impl StateCache {
+    /// Records the state a call sets, returning whether it needs to be
+    /// forwarded because it changes something or isn't cached.
+    fn update(&mut self, call: &GlCall) -> bool {
+        let arg = |name: &str| call.enum_arg(name);
+        let name = |name: &str| call.uint_arg(name);
+        match call.method {
+            "active_texture" => cache(&mut self.active_texture, arg("texture")),
+            "bind_texture" => match self.active_texture {
+                Some(unit) => cache_key(
+                    &mut self.texture_bindings,
+                    (unit, arg("target")),
+                    name("texture"),
+                ),
+                None => true,
+            },
+            "use_program" => cache(&mut self.program, name("program")),
+            "enable" => cache_key(&mut self.enabled, arg("cap"), true),
+            "disable" => cache_key(&mut self.enabled, arg("cap"), false),
+            "blend_func" => {
+                let (src, dest) = (arg("sfactor"), arg("dfactor"));
+                cache(&mut self.blend_func, (src, dest, src, dest))
+            }
+            "blend_func_separate" => cache(
+                &mut self.blend_func,
+                (
+                    arg("src_rgb"),
+                    arg("dest_rgb"),
+                    arg("src_alpha"),
+                    arg("dest_alpha"),
+                ),
+            ),
+            "blend_equation" => cache(&mut self.blend_equation, (arg("mode"), arg("mode"))),
+            "blend_equation_separate" => cache(
+                &mut self.blend_equation,
+                (arg("mode_rgb"), arg("mode_alpha")),
+            ),
+            "bind_buffer" => cache_key(&mut self.buffer_bindings, arg("target"), name("buffer")),
+            // Binding an indexed target binds its generic target too.
+            "bind_buffer_base" | "bind_buffer_range" => {
+                self.buffer_bindings.insert(arg("target"), name("buffer"));
+                true
+            }
+            "bind_vertex_array" | "bind_vertex_array_apple" => {
+                let changed = cache(&mut self.vertex_array, name("vao"));
+                if changed {
+                    self.buffer_bindings.remove(&ffi::ELEMENT_ARRAY_BUFFER);
+                }
+                changed
+            }
+            "bind_framebuffer" => {
+                let targets = if arg("target") == ffi::FRAMEBUFFER {
+                    vec![ffi::DRAW_FRAMEBUFFER, ffi::READ_FRAMEBUFFER]
+                } else {
+                    vec![arg("target")]
+                };
+                let mut changed = false;
+                for target in targets {
+                    changed |= cache_key(&mut self.framebuffers, target, name("framebuffer"));
+                }
+                changed
+            }
+            "bind_renderbuffer" => cache(&mut self.renderbuffer, name("renderbuffer")),
+
+            // Deleting a bound object unbinds it, and its name may be reused.
+            "delete_textures" => {
+                self.texture_bindings.clear();
+                true
+            }
+            "delete_program" => {
+                self.program = None;
+                true
+            }
+            "delete_buffers" => {
+                self.buffer_bindings.clear();
+                true
+            }
+            "delete_vertex_arrays" | "delete_vertex_arrays_apple" => {
+                self.vertex_array = None;
+                self.buffer_bindings.remove(&ffi::ELEMENT_ARRAY_BUFFER);
+                true
+            }
+            "delete_framebuffers" => {
+                self.framebuffers.clear();
+                true
+            }
+            "delete_renderbuffers" => {
+                self.renderbuffer = None;
+                true
+            }
+            // Native backends enable synchronous output with the callback.
+            "set_debug_callback" => {
+                self.enabled.remove(&ffi::DEBUG_OUTPUT_SYNCHRONOUS);
+                true
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Sets `slot` to `value`, returning whether it was different or unknown.
+fn cache<T: Copy + PartialEq>(slot: &mut Option<T>, value: T) -> bool {
+    slot.replace(value) != Some(value)
+}
+
+/// Sets the value of `key` to `value`, returning whether it was different or
+/// unknown.
+fn cache_key<K: Eq + Hash, T: Copy + PartialEq>(map: &mut HashMap<K, T>, key: K, value: T) -> bool {
+    map.insert(key, value) != Some(value)
+}
+
+/// A wrapper around GL context that shadows the bindings, enabled
+/// capabilities and blend state set through it, and drops the calls that
+/// would set them to the value they already have.
+///
+/// The cache starts out empty, and is cleared by `invalidate_cache`, which
+/// must be called whenever code that doesn't go through the wrapper touches
+/// the context. It is also cleared when `get_error` reports that the context
+/// was lost. Deleting objects forgets the bindings of their kind.
+///
+/// Keep the returned wrapper to call `invalidate_cache` and
+/// `elided_calls`, and pass it on as an `Rc<dyn Gl>`.
+pub struct StateCachingGl<G = Rc<dyn Gl>> {
+    gl: G,
+    cache: Mutex<StateCache>,
+    elided: AtomicUsize,
+}
+
+impl StateCachingGl {
+    pub fn wrap(fns: Rc<dyn Gl>) -> Rc<StateCachingGl> {
+        Rc::new(StateCachingGl {
+            gl: fns,
+            cache: Mutex::default(),
+            elided: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl<G: SharedGl> StateCachingGl<G> {
+    /// Wraps the functions of a `SendGl`, with `SendGl::wrap`.
+    pub fn wrap_send(fns: G) -> StateCachingGl<G> {
+        StateCachingGl {
+            gl: fns,
+            cache: Mutex::default(),
+            elided: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<G> StateCachingGl<G> {
+    /// Forgets the cached state, so that the next call setting any of it is
+    /// forwarded.
+    pub fn invalidate_cache(&self) {
+        *lock(&self.cache) = StateCache::default();
+    }
+
+    /// How many calls were dropped since the wrapper was created.
+    pub fn elided_calls(&self) -> usize {
+        self.elided.load(Ordering::Relaxed)
+    }
+
+    /// Whether the call needs to be forwarded, counting it as elided if not.
+    fn forward(&self, call: &GlCall) -> bool {
+        let forward = lock(&self.cache).update(call);
+        if !forward {
+            self.elided.fetch_add(1, Ordering::Relaxed);
+        }
+        forward
+    }
+
+    fn returned(&self, name: &str, result: &dyn Any) {
+        if name == "get_error" && result.downcast_ref() == Some(&ffi::CONTEXT_LOST) {
+            self.invalidate_cache();
+        }
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The methods `StatsGl` counts as state changes.
+const STATS_STATE_CHANGES: &[&str] = &[
+    "active_texture",
+    "bind_buffer",
+    "bind_buffer_base",
+    "bind_buffer_range",
+    "bind_framebuffer",
+    "bind_renderbuffer",
+    "bind_texture",
+    "bind_vertex_array",
+    "bind_vertex_array_apple",
+    "blend_color",
+    "blend_equation",
+    "blend_equation_separate",
+    "blend_func",
+    "blend_func_separate",
+    "color_mask",
+    "cull_face",
+    "depth_func",
+    "depth_mask",
+    "disable",
+    "enable",
+    "front_face",
+    "line_width",
+    "pixel_store_i",
+    "polygon_offset",
+    "sample_coverage",
+    "scissor",
+    "stencil_func",
+    "stencil_func_separate",
+    "stencil_mask",
+    "stencil_mask_separate",
+    "stencil_op",
+    "stencil_op_separate",
+    "use_program",
+    "viewport",
+];
+
+/// The draw calls made with one primitive mode.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DrawStats {
+    pub calls: u64,
+    /// The points, lines or triangles drawn, counting every instance.
+    pub primitives: u64,
+}
+
+/// The calls made through a `StatsGl` since it was last reset.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GlStats {
+    /// How many times each method was called.
+    pub calls: BTreeMap<&'static str, u64>,
+    /// The bytes uploaded with `buffer_data_untyped` and
+    /// `buffer_sub_data_untyped`, not counting buffers allocated without
+    /// data.
+    pub buffer_bytes: u64,
+    /// The bytes uploaded from client memory with `tex_sub_image_2d` and
+    /// `tex_sub_image_3d`.
+    pub texture_bytes: u64,
+    /// The draw calls made with each primitive mode, such as `TRIANGLES`.
+    pub draws: BTreeMap<GLenum, DrawStats>,
+    /// How many calls bound an object or changed fixed-function state, such
+    /// as `bind_texture`, `use_program` or `enable`.
+    pub state_changes: u64,
+}
+
+impl GlStats {
+    /// The draw calls made with any primitive mode.
+    pub fn draw_calls(&self) -> u64 {
+        self.draws.values().map(|draws| draws.calls).sum()
+    }
+
+    fn count(&mut self, call: &GlCall) {
+        *self.calls.entry(call.method).or_insert(0) += 1;
+        if STATS_STATE_CHANGES.contains(&call.method) {
+            self.state_changes += 1;
+        }
+        match call.method {
+            // Buffers allocated without data upload nothing.
+            "buffer_data_untyped" | "buffer_sub_data_untyped"
+                if !call.ptr_arg("data").is_null() =>
+            {
+                self.buffer_bytes += call.int_arg("size").max(0) as u64;
+            }
+            "tex_sub_image_2d" | "tex_sub_image_3d" => {
+                self.texture_bytes += call.byte_len_arg("data") as u64;
+            }
+            "draw_arrays" | "draw_elements" => self.draw(call, 1),
+            "draw_arrays_instanced" | "draw_elements_instanced" => {
+                self.draw(call, call.int_arg("primcount"))
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, call: &GlCall, instances: i64) {
+        let mode = call.enum_arg("mode");
+        let count = call.int_arg("count").max(0) as u64;
+        let primitives = match mode {
+            ffi::POINTS => count,
+            ffi::LINES => count / 2,
+            ffi::LINE_LOOP if count >= 2 => count,
+            ffi::LINE_STRIP => count.saturating_sub(1),
+            ffi::TRIANGLES => count / 3,
+            ffi::TRIANGLE_STRIP | ffi::TRIANGLE_FAN => count.saturating_sub(2),
+            _ => 0,
+        };
+        let draws = self.draws.entry(mode).or_default();
+        draws.calls += 1;
+        draws.primitives += primitives * instances.max(0) as u64;
+    }
+}
+
+/// A wrapper around GL context that counts the calls made through it, the
+/// bytes they upload and the primitives they draw, for a performance
+/// overlay.
+///
+/// Keep the returned wrapper to read the statistics, usually with `reset`
+/// once per frame, and pass it on as an `Rc<dyn Gl>`.
+pub struct StatsGl<G = Rc<dyn Gl>> {
+    gl: G,
+    stats: Mutex<GlStats>,
+}
+
+impl StatsGl {
+    pub fn wrap(fns: Rc<dyn Gl>) -> Rc<StatsGl> {
+        Rc::new(StatsGl {
+            gl: fns,
+            stats: Mutex::default(),
+        })
+    }
+}
+
+impl<G: SharedGl> StatsGl<G> {
+    /// Wraps the functions of a `SendGl`, with `SendGl::wrap`. The
+    /// statistics are read through the guard of `SendGl::make_current`.
+    pub fn wrap_send(fns: G) -> StatsGl<G> {
+        StatsGl {
+            gl: fns,
+            stats: Mutex::default(),
+        }
+    }
+}
+
+impl<G> StatsGl<G> {
+    /// The statistics of the calls made since the last reset.
+    pub fn snapshot(&self) -> GlStats {
+        lock(&self.stats).clone()
+    }
+
+    /// Returns the statistics of the calls made since the last reset, and
+    /// starts counting from zero again.
+    pub fn reset(&self) -> GlStats {
+        mem::take(&mut *lock(&self.stats))
+    }
+
+    fn count(&self, call: &GlCall) {
+        lock(&self.stats).count(call);
+    }
+}
@@ -0,0 +1,1005 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// An argument or return value captured by `RecordingGl`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceValue {
+    Unit,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    /// A pointer that is only meaningful in the recording process, such as a
+    /// mapped buffer or a `GLsync`.
+    Pointer(usize),
+    /// A caller-provided output buffer, recorded by its length.
+    Output(usize),
+    List(Vec<TraceValue>),
+    Option(Option<Box<TraceValue>>),
+}
+
+/// A single recorded call through the `Gl` trait.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceCall {
+    pub name: &'static str,
+    pub args: Vec<TraceValue>,
+    pub ret: TraceValue,
+}
+
+/// A sequence of recorded calls, in the order they were issued.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    pub calls: Vec<TraceCall>,
+}
+
+pub trait ToTraceValue {
+    fn to_trace_value(&self) -> TraceValue;
+}
+
+macro_rules! impl_trace_value {
+    ($variant:ident($repr:ty): $($t:ty),+) => {
+        $(impl ToTraceValue for $t {
+            fn to_trace_value(&self) -> TraceValue {
+                TraceValue::$variant(*self as $repr)
+            }
+        })+
+    };
+}
+
+impl_trace_value!(Int(i64): i8, i16, i32, i64, isize);
+impl_trace_value!(UInt(u64): u8, u16, u32, u64, usize);
+impl_trace_value!(Float(f64): f32, f64);
+
+macro_rules! impl_trace_value_list {
+    ($($t:ty),+) => {
+        $(impl ToTraceValue for &[$t] {
+            fn to_trace_value(&self) -> TraceValue {
+                TraceValue::List(self.iter().map(ToTraceValue::to_trace_value).collect())
+            }
+        }
+
+        impl ToTraceValue for Vec<$t> {
+            fn to_trace_value(&self) -> TraceValue {
+                (&self[..]).to_trace_value()
+            }
+        })+
+    };
+}
+
+impl_trace_value_list!(i32, u32, f32, DebugMessage);
+
+impl ToTraceValue for () {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Unit
+    }
+}
+
+impl ToTraceValue for bool {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Bool(*self)
+    }
+}
+
+impl ToTraceValue for &str {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Str(self.to_string())
+    }
+}
+
+impl ToTraceValue for String {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Str(self.clone())
+    }
+}
+
+impl ToTraceValue for &[u8] {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Bytes(self.to_vec())
+    }
+}
+
+impl ToTraceValue for Vec<u8> {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Bytes(self.clone())
+    }
+}
+
+impl ToTraceValue for &[&[u8]] {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::List(self.iter().map(ToTraceValue::to_trace_value).collect())
+    }
+}
+
+impl ToTraceValue for &[&str] {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::List(self.iter().map(ToTraceValue::to_trace_value).collect())
+    }
+}
+
+impl<T> ToTraceValue for &mut [T] {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Output(self.len())
+    }
+}
+
+impl<T> ToTraceValue for *const T {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Pointer(*self as usize)
+    }
+}
+
+impl<T> ToTraceValue for *mut T {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Pointer(*self as usize)
+    }
+}
+
+impl<T: ToTraceValue> ToTraceValue for Option<T> {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Option(self.as_ref().map(|v| Box::new(v.to_trace_value())))
+    }
+}
+
+/// Uploads rejected before reaching the driver are recorded by their error
+/// message.
+impl<T: ToTraceValue> ToTraceValue for Result<T, PixelDataError> {
+    fn to_trace_value(&self) -> TraceValue {
+        match *self {
+            Ok(ref value) => value.to_trace_value(),
+            Err(ref error) => TraceValue::Str(error.to_string()),
+        }
+    }
+}
+
+impl<A: ToTraceValue, B: ToTraceValue> ToTraceValue for (A, B) {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::List(vec![self.0.to_trace_value(), self.1.to_trace_value()])
+    }
+}
+
+impl<A: ToTraceValue, B: ToTraceValue, C: ToTraceValue> ToTraceValue for (A, B, C) {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::List(vec![
+            self.0.to_trace_value(),
+            self.1.to_trace_value(),
+            self.2.to_trace_value(),
+        ])
+    }
+}
+
+impl ToTraceValue for GlType {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::UInt(match *self {
+            GlType::Gl => 0,
+            GlType::Gles => 1,
+        })
+    }
+}
+
+impl ToTraceValue for DebugMessage {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::List(vec![
+            self.message.to_trace_value(),
+            self.source.to_gl().to_trace_value(),
+            self.ty.to_gl().to_trace_value(),
+            self.id.to_trace_value(),
+            self.severity.to_gl().to_trace_value(),
+        ])
+    }
+}
+
+/// Callbacks are recorded without their code, which can't be replayed.
+impl ToTraceValue for DebugCallback {
+    fn to_trace_value(&self) -> TraceValue {
+        TraceValue::Unit
+    }
+}
+
+/// A wrapper around GL context that records every call, its arguments and
+/// its return value into an in-memory `Trace`.
+///
+/// What is written through a buffer mapped for writing is captured when the
+/// buffer is unmapped, and stored as the return value of the call that
+/// mapped it, so that replaying that call writes it again. Writes to a
+/// mapping that is never unmapped through the wrapper, or that is still
+/// mapped when the trace is taken, are not captured.
+pub struct RecordingGl {
+    gl: Rc<dyn Gl>,
+    trace: RefCell<Trace>,
+    /// The buffers mapped for writing, by target.
+    mappings: RefCell<HashMap<GLenum, RecordedMapping>>,
+}
+
+/// A buffer mapping whose contents are captured at `unmap_buffer`.
+struct RecordedMapping {
+    /// The index of the `map_buffer` or `map_buffer_range` call in the trace.
+    call: usize,
+    data: *const u8,
+    len: usize,
+}
+
+impl RecordingGl {
+    /// The returned handle coerces to `Rc<dyn Gl>`; keep a clone around to
+    /// retrieve the trace.
+    pub fn wrap(fns: Rc<dyn Gl>) -> Rc<RecordingGl> {
+        Rc::new(RecordingGl {
+            gl: fns,
+            trace: RefCell::new(Trace::default()),
+            mappings: RefCell::default(),
+        })
+    }
+
+    /// Returns the calls recorded so far and starts a new trace.
+    pub fn take_trace(&self) -> Trace {
+        self.mappings.borrow_mut().clear();
+        mem::take(&mut *self.trace.borrow_mut())
+    }
+
+    pub fn clear(&self) {
+        self.mappings.borrow_mut().clear();
+        self.trace.borrow_mut().calls.clear();
+    }
+
+    /// Captures the contents of a mapping before `unmap_buffer` releases it.
+    fn before_call(&self, name: &str, args: &[TraceValue]) {
+        let target = match (name, args) {
+            ("unmap_buffer", &[TraceValue::UInt(target)]) => target as GLenum,
+            _ => return,
+        };
+        if let Some(mapping) = self.mappings.borrow_mut().remove(&target) {
+            let data = unsafe { slice::from_raw_parts(mapping.data, mapping.len) };
+            if let Some(call) = self.trace.borrow_mut().calls.get_mut(mapping.call) {
+                call.ret = TraceValue::Bytes(data.to_vec());
+            }
+        }
+    }
+
+    fn record(&self, name: &'static str, mut args: Vec<TraceValue>, ret: TraceValue) {
+        capture_pointer_payload(name, &mut args);
+        let mut trace = self.trace.borrow_mut();
+        self.track_mapping(name, &args, &ret, trace.calls.len());
+        trace.calls.push(TraceCall { name, args, ret });
+    }
+
+    fn track_mapping(&self, name: &str, args: &[TraceValue], ret: &TraceValue, call: usize) {
+        let data = match *ret {
+            TraceValue::Pointer(data) if data != 0 => data as *const u8,
+            _ => return,
+        };
+        let (target, len) = match (name, args) {
+            ("map_buffer", &[TraceValue::UInt(target), TraceValue::UInt(access)])
+                if access != ffi::READ_ONLY as u64 =>
+            {
+                let target = target as GLenum;
+                let len = self.gl.get_buffer_parameter_iv(target, ffi::BUFFER_SIZE);
+                (target, len)
+            }
+            (
+                "map_buffer_range",
+                &[TraceValue::UInt(target), _, TraceValue::Int(len), TraceValue::UInt(access)],
+            ) if access & ffi::MAP_WRITE_BIT as u64 != 0 => (target as GLenum, len as GLint),
+            _ => return,
+        };
+        let mapping = RecordedMapping {
+            call,
+            data,
+            len: len.max(0) as usize,
+        };
+        self.mappings.borrow_mut().insert(target, mapping);
+    }
+}
+
+/// Replaces the raw `data` pointer of the buffer upload calls with a copy of
+/// the bytes it points at, so the trace does not refer to client memory.
+fn capture_pointer_payload(name: &str, args: &mut [TraceValue]) {
+    let (size_index, data_index) = match name {
+        "buffer_data_untyped" | "buffer_storage" => (1, 2),
+        "buffer_sub_data_untyped" => (2, 3),
+        _ => return,
+    };
+    let size = match args[size_index] {
+        TraceValue::Int(size) if size >= 0 => size as usize,
+        _ => return,
+    };
+    if let TraceValue::Pointer(data) = args[data_index] {
+        if data != 0 {
+            let bytes = unsafe { slice::from_raw_parts(data as *const u8, size) };
+            args[data_index] = TraceValue::Bytes(bytes.to_vec());
+        }
+    }
+}
+
+const TRACE_MAGIC: &[u8; 8] = b"GLTRACE\0";
+const TRACE_VERSION: u32 = 1;
+/// How deeply lists and options may nest in a trace file. Recorded values
+/// never nest more than a few levels, so deeper ones are rejected rather than
+/// recursed into.
+const TRACE_MAX_DEPTH: usize = 32;
+
+const TAG_UNIT: u8 = 0;
+const TAG_BOOL: u8 = 1;
+const TAG_INT: u8 = 2;
+const TAG_UINT: u8 = 3;
+const TAG_FLOAT: u8 = 4;
+const TAG_STR: u8 = 5;
+const TAG_BYTES: u8 = 6;
+const TAG_POINTER: u8 = 7;
+const TAG_OUTPUT: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_OPTION: u8 = 10;
+
+fn invalid_data<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut buf = [0; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_bytes<R: Read>(r: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_u32(r)? as u64;
+    let mut bytes = Vec::new();
+    r.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+fn write_bytes<W: Write>(w: &mut W, bytes: &[u8]) -> io::Result<()> {
+    w.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    w.write_all(bytes)
+}
+
+impl TraceValue {
+    fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        match *self {
+            TraceValue::Unit => w.write_all(&[TAG_UNIT]),
+            TraceValue::Bool(v) => w.write_all(&[TAG_BOOL, v as u8]),
+            TraceValue::Int(v) => {
+                w.write_all(&[TAG_INT])?;
+                w.write_all(&v.to_le_bytes())
+            }
+            TraceValue::UInt(v) => {
+                w.write_all(&[TAG_UINT])?;
+                w.write_all(&v.to_le_bytes())
+            }
+            TraceValue::Float(v) => {
+                w.write_all(&[TAG_FLOAT])?;
+                w.write_all(&v.to_bits().to_le_bytes())
+            }
+            TraceValue::Str(ref v) => {
+                w.write_all(&[TAG_STR])?;
+                write_bytes(w, v.as_bytes())
+            }
+            TraceValue::Bytes(ref v) => {
+                w.write_all(&[TAG_BYTES])?;
+                write_bytes(w, v)
+            }
+            TraceValue::Pointer(v) => {
+                w.write_all(&[TAG_POINTER])?;
+                w.write_all(&(v as u64).to_le_bytes())
+            }
+            TraceValue::Output(v) => {
+                w.write_all(&[TAG_OUTPUT])?;
+                w.write_all(&(v as u64).to_le_bytes())
+            }
+            TraceValue::List(ref values) => {
+                w.write_all(&[TAG_LIST])?;
+                w.write_all(&(values.len() as u32).to_le_bytes())?;
+                for value in values {
+                    value.write_to(w)?;
+                }
+                Ok(())
+            }
+            TraceValue::Option(None) => w.write_all(&[TAG_OPTION, 0]),
+            TraceValue::Option(Some(ref value)) => {
+                w.write_all(&[TAG_OPTION, 1])?;
+                value.write_to(w)
+            }
+        }
+    }
+
+    /// Reads a value nested in `depth` lists or options.
+    fn read_from<R: Read>(r: &mut R, depth: usize) -> io::Result<TraceValue> {
+        if depth > TRACE_MAX_DEPTH {
+            return Err(invalid_data("trace values nested too deeply"));
+        }
+        Ok(match read_u8(r)? {
+            TAG_UNIT => TraceValue::Unit,
+            TAG_BOOL => TraceValue::Bool(read_u8(r)? != 0),
+            TAG_INT => TraceValue::Int(read_u64(r)? as i64),
+            TAG_UINT => TraceValue::UInt(read_u64(r)?),
+            TAG_FLOAT => TraceValue::Float(f64::from_bits(read_u64(r)?)),
+            TAG_STR => TraceValue::Str(String::from_utf8(read_bytes(r)?).map_err(invalid_data)?),
+            TAG_BYTES => TraceValue::Bytes(read_bytes(r)?),
+            TAG_POINTER => TraceValue::Pointer(read_u64(r)? as usize),
+            TAG_OUTPUT => TraceValue::Output(read_u64(r)? as usize),
+            TAG_LIST => {
+                let len = read_u32(r)?;
+                let mut values = Vec::new();
+                for _ in 0..len {
+                    values.push(TraceValue::read_from(r, depth + 1)?);
+                }
+                TraceValue::List(values)
+            }
+            TAG_OPTION => match read_u8(r)? {
+                0 => TraceValue::Option(None),
+                _ => TraceValue::Option(Some(Box::new(TraceValue::read_from(r, depth + 1)?))),
+            },
+            tag => return Err(invalid_data(format!("unknown trace value tag {}", tag))),
+        })
+    }
+
+    fn write_json(&self, out: &mut String) {
+        match *self {
+            TraceValue::Unit | TraceValue::Option(None) => out.push_str("null"),
+            TraceValue::Bool(v) => out.push_str(if v { "true" } else { "false" }),
+            TraceValue::Int(v) => out.push_str(&v.to_string()),
+            TraceValue::UInt(v) => out.push_str(&v.to_string()),
+            TraceValue::Float(v) if v.is_finite() => out.push_str(&format!("{:?}", v)),
+            TraceValue::Float(_) => out.push_str("null"),
+            TraceValue::Str(ref v) => write_json_string(out, v),
+            TraceValue::Bytes(ref v) => {
+                out.push_str("{\"bytes\":\"");
+                for byte in v {
+                    out.push_str(&format!("{:02x}", byte));
+                }
+                out.push_str("\"}");
+            }
+            TraceValue::Pointer(v) => out.push_str(&format!("{{\"pointer\":{}}}", v)),
+            TraceValue::Output(v) => out.push_str(&format!("{{\"output\":{}}}", v)),
+            TraceValue::List(ref values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 {
+                        out.push(',');
+                    }
+                    value.write_json(out);
+                }
+                out.push(']');
+            }
+            TraceValue::Option(Some(ref value)) => value.write_json(out),
+        }
+    }
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl Trace {
+    /// Writes the trace in the versioned binary format read by `read_from`.
+    /// All integers are little-endian.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(TRACE_MAGIC)?;
+        w.write_all(&TRACE_VERSION.to_le_bytes())?;
+        w.write_all(&(self.calls.len() as u32).to_le_bytes())?;
+        for call in &self.calls {
+            write_bytes(w, call.name.as_bytes())?;
+            w.write_all(&(call.args.len() as u32).to_le_bytes())?;
+            for arg in &call.args {
+                arg.write_to(w)?;
+            }
+            call.ret.write_to(w)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Trace> {
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != TRACE_MAGIC {
+            return Err(invalid_data("not a GL trace"));
+        }
+        let version = read_u32(r)?;
+        if version != TRACE_VERSION {
+            return Err(invalid_data(format!("unsupported GL trace version {}", version)));
+        }
+        let len = read_u32(r)?;
+        let mut calls = Vec::new();
+        for _ in 0..len {
+            let name = read_bytes(r)?;
+            let name = *GL_METHOD_NAMES
+                .iter()
+                .find(|known| known.as_bytes() == &name[..])
+                .ok_or_else(|| {
+                    invalid_data(format!("unknown GL method {}", String::from_utf8_lossy(&name)))
+                })?;
+            let arg_count = read_u32(r)?;
+            let mut args = Vec::new();
+            for _ in 0..arg_count {
+                args.push(TraceValue::read_from(r, 0)?);
+            }
+            let ret = TraceValue::read_from(r, 0)?;
+            calls.push(TraceCall { name, args, ret });
+        }
+        Ok(Trace { calls })
+    }
+
+    /// Returns a JSON rendering of the trace, meant for inspection and
+    /// diffing rather than for replay.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (i, call) in self.calls.iter().enumerate() {
+            if i != 0 {
+                out.push(',');
+            }
+            out.push_str("\n{\"name\":");
+            write_json_string(&mut out, call.name);
+            out.push_str(",\"args\":");
+            TraceValue::List(call.args.clone()).write_json(&mut out);
+            out.push_str(",\"ret\":");
+            call.ret.write_json(&mut out);
+            out.push('}');
+        }
+        out.push_str("\n]\n");
+        out
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The trace names a method this version of the `Gl` trait doesn't have.
+    UnknownMethod(String),
+    /// The recorded arguments don't match the method's signature.
+    BadArguments(&'static str),
+    /// The call refers to client memory or an object that can't be
+    /// recreated in the replaying process.
+    Unreplayable(&'static str),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReplayError::UnknownMethod(ref name) => write!(f, "unknown GL method {}", name),
+            ReplayError::BadArguments(name) => write!(f, "bad recorded arguments for {}", name),
+            ReplayError::Unreplayable(name) => write!(f, "{} can't be replayed", name),
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+/// Re-issues every call of `trace` against `gl`, substituting the object
+/// names the replaying context hands out for the ones in the recording.
+pub fn replay(trace: &Trace, gl: &dyn Gl) -> Result<(), ReplayError> {
+    let mut names = ReplayNames::default();
+    for call in &trace.calls {
+        replay_call(gl, call, &mut names)?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum NameKind {
+    Buffer,
+    Texture,
+    Framebuffer,
+    Renderbuffer,
+    VertexArray,
+    Query,
+    Program,
+    Shader,
+    Sync,
+    Fence,
+    UniformLocation,
+    UniformBlock,
+}
+
+impl NameKind {
+    fn returned_by(method: &str) -> Option<NameKind> {
+        Some(match method {
+            "gen_buffers" => NameKind::Buffer,
+            "gen_textures" => NameKind::Texture,
+            "gen_framebuffers" => NameKind::Framebuffer,
+            "gen_renderbuffers" => NameKind::Renderbuffer,
+            "gen_vertex_arrays" | "gen_vertex_arrays_apple" => NameKind::VertexArray,
+            "gen_queries" => NameKind::Query,
+            "create_program" => NameKind::Program,
+            "create_shader" => NameKind::Shader,
+            "fence_sync" => NameKind::Sync,
+            "gen_fences_apple" => NameKind::Fence,
+            "get_uniform_location" => NameKind::UniformLocation,
+            "get_uniform_block_index" => NameKind::UniformBlock,
+            _ => return None,
+        })
+    }
+
+    fn passed_as(method: &str, arg: &str) -> Option<NameKind> {
+        Some(match (method, arg) {
+            // A texture unit, such as TEXTURE0, rather than a texture.
+            ("active_texture", "texture") => return None,
+            (_, "buffer") | (_, "buffers") => NameKind::Buffer,
+            (_, "texture") | (_, "textures") | (_, "source_id") | (_, "dest_id")
+            | (_, "src_name") | (_, "dst_name") => NameKind::Texture,
+            (_, "framebuffer") | (_, "framebuffers") => NameKind::Framebuffer,
+            (_, "renderbuffer") | (_, "renderbuffers") => NameKind::Renderbuffer,
+            (_, "vao") | (_, "vertex_arrays") => NameKind::VertexArray,
+            (_, "queries") => NameKind::Query,
+            (_, "id") if method.contains("query") => NameKind::Query,
+            (_, "program") => NameKind::Program,
+            (_, "shader") => NameKind::Shader,
+            (_, "sync") => NameKind::Sync,
+            (_, "fence") | (_, "fences") => NameKind::Fence,
+            (_, "location") => NameKind::UniformLocation,
+            (_, "uniform_block_index") => NameKind::UniformBlock,
+            _ => return None,
+        })
+    }
+
+    /// Uniform locations and block indices are only unique per program.
+    fn is_program_scoped(self) -> bool {
+        self == NameKind::UniformLocation || self == NameKind::UniformBlock
+    }
+}
+
+fn raw_name(value: &TraceValue) -> Option<u64> {
+    match *value {
+        TraceValue::Int(v) => Some(v as u64),
+        TraceValue::UInt(v) => Some(v),
+        TraceValue::Pointer(v) => Some(v as u64),
+        _ => None,
+    }
+}
+
+fn with_raw_name(value: &TraceValue, raw: u64) -> TraceValue {
+    match *value {
+        TraceValue::Int(_) => TraceValue::Int(raw as i64),
+        TraceValue::Pointer(_) => TraceValue::Pointer(raw as usize),
+        _ => TraceValue::UInt(raw),
+    }
+}
+
+/// Maps object names seen in a recording to the ones created during replay.
+#[derive(Default)]
+struct ReplayNames {
+    names: HashMap<(NameKind, u64, u64), u64>,
+    current_program: u64,
+}
+
+impl ReplayNames {
+    fn map_name(
+        &self,
+        call: &TraceCall,
+        kind: NameKind,
+        scope: u64,
+        value: &TraceValue,
+    ) -> Result<TraceValue, ReplayError> {
+        if let TraceValue::List(ref values) = *value {
+            let values = values
+                .iter()
+                .map(|v| self.map_name(call, kind, scope, v))
+                .collect::<Result<_, _>>()?;
+            return Ok(TraceValue::List(values));
+        }
+        let raw = match raw_name(value) {
+            Some(raw) => raw,
+            None => return Ok(value.clone()),
+        };
+        match self.names.get(&(kind, scope, raw)) {
+            Some(&mapped) => Ok(with_raw_name(value, mapped)),
+            // Syncs are pointers, so one we never saw created can't be passed on.
+            None if kind == NameKind::Sync && raw != 0 => Err(ReplayError::Unreplayable(call.name)),
+            None => Ok(value.clone()),
+        }
+    }
+
+    fn scope(&self, call: &TraceCall, arg_names: &[&str]) -> u64 {
+        arg_names
+            .iter()
+            .position(|arg| *arg == "program")
+            .and_then(|i| raw_name(&call.args[i]))
+            .unwrap_or(self.current_program)
+    }
+
+    fn map_args(
+        &mut self,
+        call: &TraceCall,
+        arg_names: &[&str],
+    ) -> Result<Vec<TraceValue>, ReplayError> {
+        if call.args.len() != arg_names.len() {
+            return Err(ReplayError::BadArguments(call.name));
+        }
+        if call.name == "use_program" {
+            self.current_program = raw_name(&call.args[0]).unwrap_or(0);
+        }
+        let scope = self.scope(call, arg_names);
+        call.args
+            .iter()
+            .zip(arg_names)
+            .map(|(value, arg)| match NameKind::passed_as(call.name, arg) {
+                Some(kind) => {
+                    let scope = if kind.is_program_scoped() { scope } else { 0 };
+                    self.map_name(call, kind, scope, value)
+                }
+                None => match *value {
+                    TraceValue::Pointer(p) if p != 0 => Err(ReplayError::Unreplayable(call.name)),
+                    _ => Ok(value.clone()),
+                },
+            })
+            .collect()
+    }
+
+    fn map_result(
+        &mut self,
+        gl: &dyn Gl,
+        call: &TraceCall,
+        replayed: &TraceValue,
+    ) -> Result<(), ReplayError> {
+        if let (TraceValue::Bytes(data), &TraceValue::Pointer(mapped)) = (&call.ret, replayed) {
+            return write_mapping(gl, call, data, mapped);
+        }
+        let kind = match NameKind::returned_by(call.name) {
+            Some(kind) => kind,
+            None => return Ok(()),
+        };
+        let scope = if kind.is_program_scoped() {
+            call.args.first().and_then(raw_name).unwrap_or(0)
+        } else {
+            0
+        };
+        match (&call.ret, replayed) {
+            (TraceValue::List(recorded), TraceValue::List(replayed)) => {
+                for (recorded, replayed) in recorded.iter().zip(replayed) {
+                    self.insert(kind, scope, recorded, replayed);
+                }
+            }
+            (recorded, replayed) => self.insert(kind, scope, recorded, replayed),
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, kind: NameKind, scope: u64, recorded: &TraceValue, replayed: &TraceValue) {
+        if let (Some(recorded), Some(replayed)) = (raw_name(recorded), raw_name(replayed)) {
+            self.names.insert((kind, scope, recorded), replayed);
+        }
+    }
+}
+
+/// Writes what was written through a recorded buffer mapping through the
+/// replayed one.
+fn write_mapping(
+    gl: &dyn Gl,
+    call: &TraceCall,
+    data: &[u8],
+    mapped: usize,
+) -> Result<(), ReplayError> {
+    let len = match (call.name, &call.args[..]) {
+        ("map_buffer", &[TraceValue::UInt(target), _]) => {
+            gl.get_buffer_parameter_iv(target as GLenum, ffi::BUFFER_SIZE) as i64
+        }
+        ("map_buffer_range", &[_, _, TraceValue::Int(len), _]) => len,
+        _ => return Err(ReplayError::BadArguments(call.name)),
+    };
+    if mapped == 0 {
+        return Err(ReplayError::Unreplayable(call.name));
+    }
+    if data.len() as i64 > len {
+        return Err(ReplayError::BadArguments(call.name));
+    }
+    unsafe { ptr::copy_nonoverlapping(data.as_ptr(), mapped as *mut u8, data.len()) };
+    Ok(())
+}
+
+/// Converts recorded values back into `Gl` method arguments. `prepare`
+/// builds whatever owned storage the argument needs and `get` borrows the
+/// argument from it.
+pub trait ReplayArg<'t, 's>: Sized {
+    type Storage;
+    fn prepare(value: &'t TraceValue) -> Option<Self::Storage>;
+    fn get(storage: &'s mut Self::Storage) -> Self;
+}
+
+macro_rules! impl_replay_arg {
+    ($($t:ty),+) => {
+        $(impl<'t, 's> ReplayArg<'t, 's> for $t {
+            type Storage = $t;
+            fn prepare(value: &'t TraceValue) -> Option<$t> {
+                match *value {
+                    TraceValue::Int(v) => Some(v as $t),
+                    TraceValue::UInt(v) => Some(v as $t),
+                    TraceValue::Float(v) => Some(v as $t),
+                    _ => None,
+                }
+            }
+            fn get(storage: &'s mut $t) -> $t {
+                *storage
+            }
+        }
+
+        impl<'t: 's, 's> ReplayArg<'t, 's> for &'s [$t] {
+            type Storage = Vec<$t>;
+            fn prepare(value: &'t TraceValue) -> Option<Vec<$t>> {
+                match *value {
+                    TraceValue::List(ref values) => values.iter().map(<$t>::prepare).collect(),
+                    _ => None,
+                }
+            }
+            fn get(storage: &'s mut Vec<$t>) -> &'s [$t] {
+                storage
+            }
+        }
+
+        impl<'t, 's> ReplayArg<'t, 's> for Vec<$t> {
+            type Storage = Vec<$t>;
+            fn prepare(value: &'t TraceValue) -> Option<Vec<$t>> {
+                <&[$t]>::prepare(value)
+            }
+            fn get(storage: &'s mut Vec<$t>) -> Vec<$t> {
+                storage.clone()
+            }
+        })+
+    };
+}
+
+impl_replay_arg!(i32, u32, i64, u64, isize, usize, f32, f64);
+
+impl<'t, 's> ReplayArg<'t, 's> for u8 {
+    type Storage = u8;
+    fn prepare(value: &'t TraceValue) -> Option<u8> {
+        match *value {
+            TraceValue::UInt(v) => Some(v as u8),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut u8) -> u8 {
+        *storage
+    }
+}
+
+impl<'t, 's> ReplayArg<'t, 's> for bool {
+    type Storage = bool;
+    fn prepare(value: &'t TraceValue) -> Option<bool> {
+        match *value {
+            TraceValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut bool) -> bool {
+        *storage
+    }
+}
+
+impl<'t: 's, 's> ReplayArg<'t, 's> for &'s str {
+    type Storage = &'t str;
+    fn prepare(value: &'t TraceValue) -> Option<&'t str> {
+        match *value {
+            TraceValue::Str(ref v) => Some(v),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut &'t str) -> &'s str {
+        storage
+    }
+}
+
+impl<'t: 's, 's> ReplayArg<'t, 's> for &'s [u8] {
+    type Storage = &'t [u8];
+    fn prepare(value: &'t TraceValue) -> Option<&'t [u8]> {
+        match *value {
+            TraceValue::Bytes(ref v) => Some(v),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut &'t [u8]) -> &'s [u8] {
+        storage
+    }
+}
+
+impl<'t: 's, 's> ReplayArg<'t, 's> for &'s [&'t [u8]] {
+    type Storage = Vec<&'t [u8]>;
+    fn prepare(value: &'t TraceValue) -> Option<Vec<&'t [u8]>> {
+        match *value {
+            TraceValue::List(ref values) => values.iter().map(<&[u8]>::prepare).collect(),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut Vec<&'t [u8]>) -> &'s [&'t [u8]] {
+        storage
+    }
+}
+
+impl<'t: 's, 's> ReplayArg<'t, 's> for &'s [&'t str] {
+    type Storage = Vec<&'t str>;
+    fn prepare(value: &'t TraceValue) -> Option<Vec<&'t str>> {
+        match *value {
+            TraceValue::List(ref values) => values.iter().map(<&str>::prepare).collect(),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut Vec<&'t str>) -> &'s [&'t str] {
+        storage
+    }
+}
+
+impl<'t, 's, T: Clone + Default> ReplayArg<'t, 's> for &'s mut [T] {
+    type Storage = Vec<T>;
+    fn prepare(value: &'t TraceValue) -> Option<Vec<T>> {
+        match *value {
+            TraceValue::Output(len) => Some(vec![T::default(); len]),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut Vec<T>) -> &'s mut [T] {
+        storage
+    }
+}
+
+impl<'t, 's, T: ReplayArg<'t, 's>> ReplayArg<'t, 's> for Option<T> {
+    type Storage = Option<T::Storage>;
+    fn prepare(value: &'t TraceValue) -> Option<Option<T::Storage>> {
+        match *value {
+            TraceValue::Option(None) => Some(None),
+            TraceValue::Option(Some(ref value)) => T::prepare(value).map(Some),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut Option<T::Storage>) -> Option<T> {
+        storage.as_mut().map(T::get)
+    }
+}
+
+/// A recorded debug callback is replayed as one that ignores every message.
+impl<'t, 's> ReplayArg<'t, 's> for DebugCallback {
+    type Storage = ();
+    fn prepare(value: &'t TraceValue) -> Option<()> {
+        match *value {
+            TraceValue::Unit => Some(()),
+            _ => None,
+        }
+    }
+    fn get(_: &'s mut ()) -> DebugCallback {
+        Box::new(|_| {})
+    }
+}
+
+/// Pointers are either recorded payloads, null, or names remapped by
+/// `ReplayNames` such as `GLsync`.
+impl<'t, 's, T> ReplayArg<'t, 's> for *const T {
+    type Storage = *const T;
+    fn prepare(value: &'t TraceValue) -> Option<*const T> {
+        match *value {
+            TraceValue::Bytes(ref v) => Some(v.as_ptr() as *const T),
+            TraceValue::Pointer(p) => Some(p as *const T),
+            _ => None,
+        }
+    }
+    fn get(storage: &'s mut *const T) -> *const T {
+        *storage
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The reason a call made through `TryGl` failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GlError {
+    /// `get_error` reported this error right after the call.
+    Driver { method: &'static str, error: GLenum },
+    /// The backend has no implementation of the method, or an entry point it
+    /// calls wasn't loaded, so it wasn't called.
+    Unsupported { method: &'static str },
+    /// An argument was rejected before the call was made.
+    BadArgument {
+        method: &'static str,
+        reason: &'static str,
+    },
+    /// The client memory of a pixel transfer doesn't match the image it
+    /// describes.
+    SizeMismatch {
+        method: &'static str,
+        error: PixelDataError,
+    },
+}
+
+impl fmt::Display for GlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GlError::Driver { method, error } => {
+                write!(f, "{} raised GL error {:#x}", method, error)
+            }
+            GlError::Unsupported { method } => write!(f, "{} is not supported", method),
+            GlError::BadArgument { method, reason } => {
+                write!(f, "bad argument to {}: {}", method, reason)
+            }
+            GlError::SizeMismatch { method, ref error } => write!(f, "{}: {}", method, error),
+        }
+    }
+}
+
+impl Error for GlError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            GlError::SizeMismatch { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The return value of a `Gl` method, turned into the value of the matching
+/// `TryGl` method.
+pub trait TryGlResult {
+    type Output;
+
+    fn into_try_result(self, method: &'static str) -> Result<Self::Output, GlError>;
+}
+
+macro_rules! impl_try_gl_result {
+    ($($t:ty),+) => {
+        $(impl TryGlResult for $t {
+            type Output = $t;
+
+            fn into_try_result(self, _: &'static str) -> Result<$t, GlError> {
+                Ok(self)
+            }
+        })+
+    }
+}
+
+impl_try_gl_result!(
+    (),
+    GlType,
+    u8,
+    i32,
+    u32,
+    i64,
+    u64,
+    isize,
+    f32,
+    String,
+    Vec<u8>,
+    Vec<GLint>,
+    Vec<GLuint>,
+    Vec<DebugMessage>,
+    (GLint, GLint, GLint),
+    (i32, u32, String)
+);
+
+impl<T> TryGlResult for *const T {
+    type Output = *const T;
+
+    fn into_try_result(self, _: &'static str) -> Result<*const T, GlError> {
+        Ok(self)
+    }
+}
+
+impl<T> TryGlResult for *mut T {
+    type Output = *mut T;
+
+    fn into_try_result(self, _: &'static str) -> Result<*mut T, GlError> {
+        Ok(self)
+    }
+}
+
+/// `get_program_binary` returns an empty binary when the driver can't
+/// provide one.
+impl TryGlResult for (Vec<u8>, GLenum) {
+    type Output = (Vec<u8>, GLenum);
+
+    fn into_try_result(self, method: &'static str) -> Result<(Vec<u8>, GLenum), GlError> {
+        if self.0.is_empty() && self.1 == NONE {
+            return Err(GlError::Unsupported { method });
+        }
+        Ok(self)
+    }
+}
+
+impl<T> TryGlResult for Result<T, PixelDataError> {
+    type Output = T;
+
+    fn into_try_result(self, method: &'static str) -> Result<T, GlError> {
+        self.map_err(|error| GlError::SizeMismatch { method, error })
+    }
+}
+
+/// A wrapper around GL context whose methods return `Result<T, GlError>`
+/// where the `Gl` methods would panic, assert, or silently return empty
+/// values.
+///
+/// Driver errors are read with `get_error` after each call, so an error left
+/// pending by a call made directly on the wrapped context is reported by the
+/// next `TryGl` call.
+pub struct TryGl {
+    gl: Rc<dyn Gl>,
+}
+
+impl TryGl {
+    pub fn wrap(fns: Rc<dyn Gl>) -> TryGl {
+        TryGl { gl: fns }
+    }
+
+    /// Returns the wrapped context.
+    pub fn gl(&self) -> &Rc<dyn Gl> {
+        &self.gl
+    }
+
+    fn check(&self, call: &GlCall) -> Result<(), GlError> {
+        let method = call.method;
+        if !self.gl.capabilities().is_loaded(method) {
+            return Err(GlError::Unsupported { method });
+        }
+        if call.has_empty_output() {
+            return Err(GlError::BadArgument {
+                method,
+                reason: "the result slice is empty",
+            });
+        }
+        if method == "get_shader_precision_format" {
+            match call.enum_arg("precision_type") {
+                ffi::LOW_FLOAT
+                | ffi::MEDIUM_FLOAT
+                | ffi::HIGH_FLOAT
+                | ffi::LOW_INT
+                | ffi::MEDIUM_INT
+                | ffi::HIGH_INT => {}
+                _ => {
+                    return Err(GlError::BadArgument {
+                        method,
+                        reason: "invalid precision type",
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn driver_error(&self, method: &'static str) -> Result<(), GlError> {
+        if !self.gl.capabilities().is_loaded("get_error") {
+            return Ok(());
+        }
+        match self.gl.get_error() {
+            NO_ERROR => Ok(()),
+            error => Err(GlError::Driver { method, error }),
+        }
+    }
+}
@@ -0,0 +1,414 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+gl_enum! {
+    /// What a buffer is bound to.
+    pub enum BufferTarget {
+        Array = ARRAY_BUFFER,
+        ElementArray = ELEMENT_ARRAY_BUFFER,
+        CopyRead = COPY_READ_BUFFER,
+        CopyWrite = COPY_WRITE_BUFFER,
+        PixelPack = PIXEL_PACK_BUFFER,
+        PixelUnpack = PIXEL_UNPACK_BUFFER,
+        TransformFeedback = TRANSFORM_FEEDBACK_BUFFER,
+        Uniform = UNIFORM_BUFFER,
+        Texture = TEXTURE_BUFFER,
+        ShaderStorage = SHADER_STORAGE_BUFFER,
+    }
+}
+
+gl_enum! {
+    /// How the contents of a buffer are used.
+    pub enum BufferUsage {
+        StreamDraw = STREAM_DRAW,
+        StreamRead = STREAM_READ,
+        StreamCopy = STREAM_COPY,
+        StaticDraw = STATIC_DRAW,
+        StaticRead = STATIC_READ,
+        StaticCopy = STATIC_COPY,
+        DynamicDraw = DYNAMIC_DRAW,
+        DynamicRead = DYNAMIC_READ,
+        DynamicCopy = DYNAMIC_COPY,
+    }
+}
+
+gl_enum! {
+    /// What a texture is bound to.
+    pub enum TextureTarget {
+        Texture2D = TEXTURE_2D,
+        Texture3D = TEXTURE_3D,
+        Texture2DArray = TEXTURE_2D_ARRAY,
+        Texture2DMultisample = TEXTURE_2D_MULTISAMPLE,
+        CubeMap = TEXTURE_CUBE_MAP,
+        Rectangle = TEXTURE_RECTANGLE,
+        External = TEXTURE_EXTERNAL_OES,
+        Buffer = TEXTURE_BUFFER,
+    }
+}
+
+gl_enum! {
+    /// A parameter set with `tex_parameter_i` or `tex_parameter_f`.
+    pub enum TextureParameter {
+        MinFilter = TEXTURE_MIN_FILTER,
+        MagFilter = TEXTURE_MAG_FILTER,
+        WrapS = TEXTURE_WRAP_S,
+        WrapT = TEXTURE_WRAP_T,
+        WrapR = TEXTURE_WRAP_R,
+        BaseLevel = TEXTURE_BASE_LEVEL,
+        MaxLevel = TEXTURE_MAX_LEVEL,
+        MinLod = TEXTURE_MIN_LOD,
+        MaxLod = TEXTURE_MAX_LOD,
+        CompareMode = TEXTURE_COMPARE_MODE,
+        CompareFunc = TEXTURE_COMPARE_FUNC,
+        SwizzleR = TEXTURE_SWIZZLE_R,
+        SwizzleG = TEXTURE_SWIZZLE_G,
+        SwizzleB = TEXTURE_SWIZZLE_B,
+        SwizzleA = TEXTURE_SWIZZLE_A,
+        MaxAnisotropy = TEXTURE_MAX_ANISOTROPY_EXT,
+    }
+}
+
+gl_enum! {
+    /// The sized format textures and renderbuffers store their texels in.
+    pub enum InternalFormat {
+        R8 = R8,
+        RG8 = RG8,
+        RGB8 = RGB8,
+        RGBA8 = RGBA8,
+        BGRA8 = BGRA8_EXT,
+        SRGB8 = SRGB8,
+        SRGB8Alpha8 = SRGB8_ALPHA8,
+        RGB565 = RGB565,
+        RGBA4 = RGBA4,
+        RGB5A1 = RGB5_A1,
+        RGB10A2 = RGB10_A2,
+        R11FG11FB10F = R11F_G11F_B10F,
+        R16F = R16F,
+        RG16F = RG16F,
+        RGBA16F = RGBA16F,
+        R32F = R32F,
+        RG32F = RG32F,
+        RGBA32F = RGBA32F,
+        R8UI = R8UI,
+        R16UI = R16UI,
+        R32UI = R32UI,
+        RGBA8UI = RGBA8UI,
+        RGBA32UI = RGBA32UI,
+        R32I = R32I,
+        RGBA32I = RGBA32I,
+        DepthComponent16 = DEPTH_COMPONENT16,
+        DepthComponent24 = DEPTH_COMPONENT24,
+        DepthComponent32F = DEPTH_COMPONENT32F,
+        Depth24Stencil8 = DEPTH24_STENCIL8,
+        Depth32FStencil8 = DEPTH32F_STENCIL8,
+        StencilIndex8 = STENCIL_INDEX8,
+    }
+}
+
+gl_enum! {
+    /// What a framebuffer is bound to.
+    pub enum FramebufferTarget {
+        /// Both the draw and the read framebuffer.
+        Framebuffer = FRAMEBUFFER,
+        Draw = DRAW_FRAMEBUFFER,
+        Read = READ_FRAMEBUFFER,
+    }
+}
+
+gl_enum! {
+    /// How vertices are assembled into primitives.
+    pub enum PrimitiveMode {
+        Points = POINTS,
+        Lines = LINES,
+        LineLoop = LINE_LOOP,
+        LineStrip = LINE_STRIP,
+        Triangles = TRIANGLES,
+        TriangleStrip = TRIANGLE_STRIP,
+        TriangleFan = TRIANGLE_FAN,
+    }
+}
+
+gl_enum! {
+    /// The type of the indices of `draw_elements`.
+    pub enum IndexType {
+        UnsignedByte = UNSIGNED_BYTE,
+        UnsignedShort = UNSIGNED_SHORT,
+        UnsignedInt = UNSIGNED_INT,
+    }
+}
+
+gl_enum! {
+    /// What a source or destination color is multiplied by when blending.
+    pub enum BlendFactor {
+        Zero = ZERO,
+        One = ONE,
+        SrcColor = SRC_COLOR,
+        OneMinusSrcColor = ONE_MINUS_SRC_COLOR,
+        DstColor = DST_COLOR,
+        OneMinusDstColor = ONE_MINUS_DST_COLOR,
+        SrcAlpha = SRC_ALPHA,
+        OneMinusSrcAlpha = ONE_MINUS_SRC_ALPHA,
+        DstAlpha = DST_ALPHA,
+        OneMinusDstAlpha = ONE_MINUS_DST_ALPHA,
+        ConstantColor = CONSTANT_COLOR,
+        OneMinusConstantColor = ONE_MINUS_CONSTANT_COLOR,
+        ConstantAlpha = CONSTANT_ALPHA,
+        OneMinusConstantAlpha = ONE_MINUS_CONSTANT_ALPHA,
+        SrcAlphaSaturate = SRC_ALPHA_SATURATE,
+        /// The second output of the fragment shader, with dual-source
+        /// blending.
+        Src1Color = SRC1_COLOR,
+        OneMinusSrc1Color = ONE_MINUS_SRC1_COLOR,
+        Src1Alpha = SRC1_ALPHA,
+        OneMinusSrc1Alpha = ONE_MINUS_SRC1_ALPHA,
+    }
+}
+
+gl_enum! {
+    /// How the source and destination colors are combined when blending.
+    pub enum BlendEquation {
+        Add = FUNC_ADD,
+        Subtract = FUNC_SUBTRACT,
+        ReverseSubtract = FUNC_REVERSE_SUBTRACT,
+        Min = MIN,
+        Max = MAX,
+    }
+}
+
+gl_enum! {
+    /// A comparison, as made by the depth and stencil tests.
+    pub enum CompareFunc {
+        Never = NEVER,
+        Less = LESS,
+        Equal = EQUAL,
+        LessOrEqual = LEQUAL,
+        Greater = GREATER,
+        NotEqual = NOTEQUAL,
+        GreaterOrEqual = GEQUAL,
+        Always = ALWAYS,
+    }
+}
+
+gl_enum! {
+    /// A capability turned on and off with `enable` and `disable`.
+    pub enum Capability {
+        Blend = BLEND,
+        CullFace = CULL_FACE,
+        DepthTest = DEPTH_TEST,
+        StencilTest = STENCIL_TEST,
+        ScissorTest = SCISSOR_TEST,
+        Dither = DITHER,
+        PolygonOffsetFill = POLYGON_OFFSET_FILL,
+        SampleAlphaToCoverage = SAMPLE_ALPHA_TO_COVERAGE,
+        SampleCoverage = SAMPLE_COVERAGE,
+        RasterizerDiscard = RASTERIZER_DISCARD,
+        PrimitiveRestartFixedIndex = PRIMITIVE_RESTART_FIXED_INDEX,
+        FramebufferSrgb = FRAMEBUFFER_SRGB,
+        DebugOutput = DEBUG_OUTPUT,
+        DebugOutputSynchronous = DEBUG_OUTPUT_SYNCHRONOUS,
+    }
+}
+
+/// The `Gl` methods that take enums, taking the enums of the values they
+/// accept instead of any `GLenum`, so that a texture target can't be passed
+/// where a buffer target is expected.
+///
+/// The raw context is still reachable with `raw`, for the methods and values
+/// the facade doesn't cover.
+///
+/// ```no_run
+/// # use gleam::gl::*;
+/// # fn upload(gl: &dyn Gl, buffer: GLuint, vertices: &[f32]) {
+/// let typed = TypedGl::new(gl);
+/// typed.bind_buffer(BufferTarget::Array, buffer);
+/// typed.buffer_data(BufferTarget::Array, vertices, BufferUsage::StaticDraw);
+/// typed.draw_arrays(PrimitiveMode::Triangles, 0, 3);
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct TypedGl<'a> {
+    gl: &'a dyn Gl,
+}
+
+impl<'a> TypedGl<'a> {
+    pub fn new(gl: &'a dyn Gl) -> TypedGl<'a> {
+        TypedGl { gl }
+    }
+
+    pub fn raw(&self) -> &'a dyn Gl {
+        self.gl
+    }
+
+    pub fn bind_buffer(&self, target: BufferTarget, buffer: GLuint) {
+        self.gl.bind_buffer(target.to_gl(), buffer)
+    }
+
+    pub fn bind_buffer_base(&self, target: BufferTarget, index: GLuint, buffer: GLuint) {
+        self.gl.bind_buffer_base(target.to_gl(), index, buffer)
+    }
+
+    pub fn buffer_data<T>(&self, target: BufferTarget, data: &[T], usage: BufferUsage) {
+        buffer_data(self.gl, target.to_gl(), data, usage.to_gl())
+    }
+
+    pub fn buffer_sub_data<T>(&self, target: BufferTarget, offset: isize, data: &[T]) {
+        buffer_sub_data(self.gl, target.to_gl(), offset, data)
+    }
+
+    pub fn bind_texture(&self, target: TextureTarget, texture: GLuint) {
+        self.gl.bind_texture(target.to_gl(), texture)
+    }
+
+    pub fn tex_parameter_i(&self, target: TextureTarget, pname: TextureParameter, param: GLint) {
+        self.gl
+            .tex_parameter_i(target.to_gl(), pname.to_gl(), param)
+    }
+
+    pub fn tex_parameter_f(&self, target: TextureTarget, pname: TextureParameter, param: GLfloat) {
+        self.gl
+            .tex_parameter_f(target.to_gl(), pname.to_gl(), param)
+    }
+
+    pub fn tex_storage_2d(
+        &self,
+        target: TextureTarget,
+        levels: GLint,
+        internal_format: InternalFormat,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.gl.tex_storage_2d(
+            target.to_gl(),
+            levels,
+            internal_format.to_gl(),
+            width,
+            height,
+        )
+    }
+
+    pub fn tex_storage_3d(
+        &self,
+        target: TextureTarget,
+        levels: GLint,
+        internal_format: InternalFormat,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+    ) {
+        self.gl.tex_storage_3d(
+            target.to_gl(),
+            levels,
+            internal_format.to_gl(),
+            width,
+            height,
+            depth,
+        )
+    }
+
+    pub fn renderbuffer_storage(
+        &self,
+        internal_format: InternalFormat,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.gl
+            .renderbuffer_storage(ffi::RENDERBUFFER, internal_format.to_gl(), width, height)
+    }
+
+    pub fn bind_framebuffer(&self, target: FramebufferTarget, framebuffer: GLuint) {
+        self.gl.bind_framebuffer(target.to_gl(), framebuffer)
+    }
+
+    pub fn enable(&self, cap: Capability) {
+        self.gl.enable(cap.to_gl())
+    }
+
+    pub fn disable(&self, cap: Capability) {
+        self.gl.disable(cap.to_gl())
+    }
+
+    pub fn is_enabled(&self, cap: Capability) -> bool {
+        self.gl.is_enabled(cap.to_gl()) != 0
+    }
+
+    pub fn blend_func(&self, sfactor: BlendFactor, dfactor: BlendFactor) {
+        self.gl.blend_func(sfactor.to_gl(), dfactor.to_gl())
+    }
+
+    pub fn blend_func_separate(
+        &self,
+        src_rgb: BlendFactor,
+        dest_rgb: BlendFactor,
+        src_alpha: BlendFactor,
+        dest_alpha: BlendFactor,
+    ) {
+        self.gl.blend_func_separate(
+            src_rgb.to_gl(),
+            dest_rgb.to_gl(),
+            src_alpha.to_gl(),
+            dest_alpha.to_gl(),
+        )
+    }
+
+    pub fn blend_equation(&self, mode: BlendEquation) {
+        self.gl.blend_equation(mode.to_gl())
+    }
+
+    pub fn blend_equation_separate(&self, mode_rgb: BlendEquation, mode_alpha: BlendEquation) {
+        self.gl
+            .blend_equation_separate(mode_rgb.to_gl(), mode_alpha.to_gl())
+    }
+
+    pub fn depth_func(&self, func: CompareFunc) {
+        self.gl.depth_func(func.to_gl())
+    }
+
+    pub fn draw_arrays(&self, mode: PrimitiveMode, first: GLint, count: GLsizei) {
+        self.gl.draw_arrays(mode.to_gl(), first, count)
+    }
+
+    pub fn draw_arrays_instanced(
+        &self,
+        mode: PrimitiveMode,
+        first: GLint,
+        count: GLsizei,
+        primcount: GLsizei,
+    ) {
+        self.gl
+            .draw_arrays_instanced(mode.to_gl(), first, count, primcount)
+    }
+
+    pub fn draw_elements(
+        &self,
+        mode: PrimitiveMode,
+        count: GLsizei,
+        element_type: IndexType,
+        indices_offset: GLuint,
+    ) {
+        self.gl
+            .draw_elements(mode.to_gl(), count, element_type.to_gl(), indices_offset)
+    }
+
+    pub fn draw_elements_instanced(
+        &self,
+        mode: PrimitiveMode,
+        count: GLsizei,
+        element_type: IndexType,
+        indices_offset: GLuint,
+        primcount: GLsizei,
+    ) {
+        self.gl.draw_elements_instanced(
+            mode.to_gl(),
+            count,
+            element_type.to_gl(),
+            indices_offset,
+            primcount,
+        )
+    }
+}
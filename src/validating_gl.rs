@@ -0,0 +1,2466 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The size of a texture level, or `None` when the level was defined from
+/// an external image whose size is not known.
+type ValidationLevel = Option<(GLsizei, GLsizei, GLsizei)>;
+
+#[derive(Default)]
+struct ValidationTexture {
+    target: Option<GLenum>,
+    levels: HashMap<(GLenum, GLint), ValidationLevel>,
+}
+
+impl ValidationTexture {
+    fn define(&mut self, target: GLenum, level: GLint, size: ValidationLevel) {
+        self.levels.insert((target, level), size);
+    }
+
+    fn define_chain(
+        &mut self,
+        target: GLenum,
+        levels: GLint,
+        (width, height, depth): (GLsizei, GLsizei, GLsizei),
+    ) {
+        let faces = if target == ffi::TEXTURE_CUBE_MAP {
+            (ffi::TEXTURE_CUBE_MAP_POSITIVE_X..=ffi::TEXTURE_CUBE_MAP_NEGATIVE_Z).collect()
+        } else {
+            vec![target]
+        };
+        let layered = target == ffi::TEXTURE_2D_ARRAY;
+        for level in 0..levels {
+            let size = (
+                (width >> level).max(1),
+                (height >> level).max(1),
+                if layered {
+                    depth
+                } else {
+                    (depth >> level).max(1)
+                },
+            );
+            for &face in &faces {
+                self.define(face, level, Some(size));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ValidationAttachment {
+    Texture(GLuint, GLenum, GLint),
+    Renderbuffer(GLuint),
+}
+
+/// The subset of GL state `ValidatingGl` needs to judge calls, shadowed from
+/// the calls that pass through it.
+///
+/// Objects created before the wrapper was installed are unknown to it, and
+/// checks that depend on their contents are skipped.
+#[derive(Default)]
+struct ValidationState {
+    buffers: HashMap<GLuint, GLsizeiptr>,
+    textures: HashMap<GLuint, ValidationTexture>,
+    default_textures: HashMap<GLenum, ValidationTexture>,
+    framebuffers: HashMap<GLuint, HashMap<GLenum, ValidationAttachment>>,
+    renderbuffers: HashMap<GLuint, bool>,
+    /// The element array buffer of each vertex array.
+    vertex_arrays: HashMap<GLuint, GLuint>,
+    programs: HashSet<GLuint>,
+    shaders: HashSet<GLuint>,
+    deleted: HashSet<(&'static str, GLuint)>,
+
+    buffer_bindings: HashMap<GLenum, GLuint>,
+    texture_bindings: HashMap<(GLuint, GLenum), GLuint>,
+    active_texture: GLuint,
+    program: GLuint,
+    vertex_array: GLuint,
+    draw_framebuffer: GLuint,
+    read_framebuffer: GLuint,
+    renderbuffer: GLuint,
+}
+
+impl ValidationState {
+    fn created(&mut self, kind: &'static str, names: &[GLuint]) {
+        for &name in names {
+            self.deleted.remove(&(kind, name));
+            match kind {
+                "buffer" => {
+                    self.buffers.insert(name, 0);
+                }
+                "texture" => {
+                    self.textures.insert(name, ValidationTexture::default());
+                }
+                "framebuffer" => {
+                    self.framebuffers.insert(name, HashMap::new());
+                }
+                "renderbuffer" => {
+                    self.renderbuffers.insert(name, false);
+                }
+                "vertex array" => {
+                    self.vertex_arrays.insert(name, 0);
+                }
+                "program" => {
+                    self.programs.insert(name);
+                }
+                _ => {
+                    self.shaders.insert(name);
+                }
+            }
+        }
+    }
+
+    fn deleted(&mut self, kind: &'static str, names: &[GLuint]) {
+        for &name in names.iter().filter(|&&name| name != 0) {
+            self.deleted.insert((kind, name));
+            match kind {
+                "buffer" => {
+                    self.buffers.remove(&name);
+                }
+                "texture" => {
+                    self.textures.remove(&name);
+                }
+                "framebuffer" => {
+                    self.framebuffers.remove(&name);
+                }
+                "renderbuffer" => {
+                    self.renderbuffers.remove(&name);
+                }
+                "vertex array" => {
+                    self.vertex_arrays.remove(&name);
+                }
+                "program" => {
+                    self.programs.remove(&name);
+                }
+                _ => {
+                    self.shaders.remove(&name);
+                }
+            }
+        }
+    }
+
+    fn check_live(&self, kind: &'static str, name: GLuint) -> Result<(), String> {
+        if self.deleted.contains(&(kind, name)) {
+            return Err(format!("{} {} has been deleted", kind, name));
+        }
+        Ok(())
+    }
+
+    fn check_unbound(&self, kind: &'static str, names: &[GLuint]) -> Result<(), String> {
+        for &name in names.iter().filter(|&&name| name != 0) {
+            let bound = match kind {
+                "buffer" => {
+                    if self.vertex_arrays.get(&self.vertex_array) == Some(&name) {
+                        return Err(format!(
+                            "buffer {} is still the element array buffer of the bound vertex array {}",
+                            name, self.vertex_array
+                        ));
+                    }
+                    self.buffer_bindings
+                        .iter()
+                        .find(|&(_, &bound)| bound == name)
+                        .map(|(&target, _)| {
+                            format!("buffer {} is still bound to {:#x}", name, target)
+                        })
+                }
+                "texture" => self
+                    .texture_bindings
+                    .iter()
+                    .find(|&(_, &bound)| bound == name)
+                    .map(|(&(unit, target), _)| {
+                        format!(
+                            "texture {} is still bound to {:#x} of unit {}",
+                            name, target, unit
+                        )
+                    }),
+                "framebuffer" if name == self.draw_framebuffer || name == self.read_framebuffer => {
+                    Some(format!("framebuffer {} is still bound", name))
+                }
+                "renderbuffer" if name == self.renderbuffer => {
+                    Some(format!("renderbuffer {} is still bound", name))
+                }
+                "vertex array" if name == self.vertex_array => {
+                    Some(format!("vertex array {} is still bound", name))
+                }
+                "program" if name == self.program => {
+                    Some(format!("program {} is still in use", name))
+                }
+                _ => None,
+            };
+            if let Some(reason) = bound {
+                return Err(reason);
+            }
+        }
+        Ok(())
+    }
+
+    fn bind_buffer(&mut self, target: GLenum, buffer: GLuint) {
+        if target == ffi::ELEMENT_ARRAY_BUFFER {
+            self.vertex_arrays.insert(self.vertex_array, buffer);
+        } else {
+            self.buffer_bindings.insert(target, buffer);
+        }
+    }
+
+    fn bound_buffer(&self, target: GLenum) -> GLuint {
+        if target == ffi::ELEMENT_ARRAY_BUFFER {
+            self.vertex_arrays
+                .get(&self.vertex_array)
+                .cloned()
+                .unwrap_or(0)
+        } else {
+            self.buffer_bindings.get(&target).cloned().unwrap_or(0)
+        }
+    }
+
+    fn check_buffer_bound(&self, target: GLenum) -> Result<(), String> {
+        if self.bound_buffer(target) == 0 {
+            return Err(format!("no buffer is bound to {:#x}", target));
+        }
+        Ok(())
+    }
+
+    fn check_buffer_range(
+        &self,
+        target: GLenum,
+        offset: isize,
+        size: GLsizeiptr,
+    ) -> Result<(), String> {
+        self.check_buffer_bound(target)?;
+        let buffer = self.bound_buffer(target);
+        let length = match self.buffers.get(&buffer) {
+            Some(&length) => length,
+            None => return Ok(()),
+        };
+        match offset.checked_add(size) {
+            Some(end) if offset >= 0 && size >= 0 && end <= length => Ok(()),
+            _ => Err(format!(
+                "range of {} bytes at {} is outside the {} bytes of buffer {}",
+                size, offset, length, buffer
+            )),
+        }
+    }
+
+    fn set_buffer_size(&mut self, target: GLenum, size: GLsizeiptr) {
+        let buffer = self.bound_buffer(target);
+        if let Some(length) = self.buffers.get_mut(&buffer) {
+            *length = size;
+        }
+    }
+
+    fn check_bind_texture(&self, target: GLenum, texture: GLuint) -> Result<(), String> {
+        self.check_live("texture", texture)?;
+        match self
+            .textures
+            .get(&texture)
+            .and_then(|texture| texture.target)
+        {
+            Some(created) if created != target => Err(format!(
+                "texture {} was first bound to {:#x} and cannot be bound to {:#x}",
+                texture, created, target
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn bind_texture(&mut self, target: GLenum, texture: GLuint) {
+        self.texture_bindings
+            .insert((self.active_texture, target), texture);
+        if let Some(texture) = self.textures.get_mut(&texture) {
+            texture.target.get_or_insert(target);
+        }
+    }
+
+    fn bound_texture(&self, target: GLenum) -> GLuint {
+        let target = mock_binding_target(target);
+        self.texture_bindings
+            .get(&(self.active_texture, target))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    fn texture_mut(&mut self, target: GLenum) -> Option<&mut ValidationTexture> {
+        let texture = self.bound_texture(target);
+        if texture == 0 {
+            Some(
+                self.default_textures
+                    .entry(mock_binding_target(target))
+                    .or_default(),
+            )
+        } else {
+            self.textures.get_mut(&texture)
+        }
+    }
+
+    fn define_level(&mut self, target: GLenum, level: GLint, size: ValidationLevel) {
+        if let Some(texture) = self.texture_mut(target) {
+            texture.define(target, level, size);
+        }
+    }
+
+    fn define_storage(&mut self, target: GLenum, levels: GLint, size: (GLsizei, GLsizei, GLsizei)) {
+        if let Some(texture) = self.texture_mut(target) {
+            texture.define_chain(target, levels, size);
+        }
+    }
+
+    fn generate_mipmap(&mut self, target: GLenum) {
+        let texture = match self.texture_mut(target) {
+            Some(texture) => texture,
+            None => return,
+        };
+        let base = if target == ffi::TEXTURE_CUBE_MAP {
+            ffi::TEXTURE_CUBE_MAP_POSITIVE_X
+        } else {
+            target
+        };
+        if let Some(&Some((width, height, depth))) = texture.levels.get(&(base, 0)) {
+            let levels = 32 - (width.max(height).max(1) as u32).leading_zeros();
+            texture.define_chain(target, levels as GLint, (width, height, depth));
+        }
+    }
+
+    fn check_sub_image(
+        &self,
+        target: GLenum,
+        level: GLint,
+        (x, y, z): (GLint, GLint, GLint),
+        (width, height, depth): (GLsizei, GLsizei, GLsizei),
+    ) -> Result<(), String> {
+        let name = self.bound_texture(target);
+        let texture = if name == 0 {
+            self.default_textures.get(&mock_binding_target(target))
+        } else {
+            self.textures.get(&name)
+        };
+        let texture = match texture {
+            Some(texture) => texture,
+            None => return Ok(()),
+        };
+        // An end past `GLint::MAX` is as far out of range as any other.
+        let exceeds = |offset: GLint, size: GLsizei, length: GLsizei| {
+            offset < 0 || offset.checked_add(size).is_none_or(|end| end > length)
+        };
+        match texture.levels.get(&(target, level)) {
+            None => Err(format!(
+                "level {} of texture {} is not defined",
+                level, name
+            )),
+            Some(&Some((w, h, d)))
+                if exceeds(x, width, w) || exceeds(y, height, h) || exceeds(z, depth, d) =>
+            {
+                Err(format!(
+                    "region {}x{}x{} at ({}, {}, {}) exceeds the {}x{}x{} level {} of texture {}",
+                    width, height, depth, x, y, z, w, h, d, level, name
+                ))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn check_client_pixels(&self, target: GLenum) -> Result<(), String> {
+        match self.buffer_bindings.get(&target) {
+            Some(&buffer) if buffer != 0 => Err(format!(
+                "client memory was passed while buffer {} is bound to {:#x}",
+                buffer, target
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn framebuffer_binding(&self, target: GLenum) -> GLuint {
+        if target == ffi::READ_FRAMEBUFFER {
+            self.read_framebuffer
+        } else {
+            self.draw_framebuffer
+        }
+    }
+
+    fn bind_framebuffer(&mut self, target: GLenum, framebuffer: GLuint) {
+        if target != ffi::READ_FRAMEBUFFER {
+            self.draw_framebuffer = framebuffer;
+        }
+        if target != ffi::DRAW_FRAMEBUFFER {
+            self.read_framebuffer = framebuffer;
+        }
+    }
+
+    fn renderbuffer_storage(&mut self) {
+        if let Some(storage) = self.renderbuffers.get_mut(&self.renderbuffer) {
+            *storage = true;
+        }
+    }
+
+    fn attach_layer(&mut self, target: GLenum, attachment: GLenum, texture: GLuint, level: GLint) {
+        let image_target = self
+            .textures
+            .get(&texture)
+            .and_then(|texture| texture.target);
+        let value = ValidationAttachment::Texture(texture, image_target.unwrap_or(0), level);
+        self.attach(target, attachment, Some(value).filter(|_| texture != 0));
+    }
+
+    fn attach(&mut self, target: GLenum, attachment: GLenum, value: Option<ValidationAttachment>) {
+        let framebuffer = self.framebuffer_binding(target);
+        if let Some(attachments) = self.framebuffers.get_mut(&framebuffer) {
+            let attachments_for = if attachment == ffi::DEPTH_STENCIL_ATTACHMENT {
+                vec![ffi::DEPTH_ATTACHMENT, ffi::STENCIL_ATTACHMENT]
+            } else {
+                vec![attachment]
+            };
+            for attachment in attachments_for {
+                match value {
+                    Some(value) => attachments.insert(attachment, value),
+                    None => attachments.remove(&attachment),
+                };
+            }
+        }
+    }
+
+    fn check_framebuffer(&self, framebuffer: GLuint) -> Result<(), String> {
+        let attachments = match self.framebuffers.get(&framebuffer) {
+            Some(attachments) => attachments,
+            None => return Ok(()),
+        };
+        if attachments.is_empty() {
+            return Err(format!("framebuffer {} has no attachments", framebuffer));
+        }
+        for (&attachment, value) in attachments {
+            let missing = match *value {
+                ValidationAttachment::Texture(texture, target, level) => self
+                    .textures
+                    .get(&texture)
+                    .is_some_and(|t| !t.levels.contains_key(&(target, level))),
+                ValidationAttachment::Renderbuffer(renderbuffer) => {
+                    self.renderbuffers.get(&renderbuffer) == Some(&false)
+                }
+            };
+            if missing {
+                return Err(format!(
+                    "attachment {:#x} of framebuffer {} has no storage",
+                    attachment, framebuffer
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_uniform(&self) -> Result<(), String> {
+        if self.program == 0 {
+            return Err("no program is in use".to_owned());
+        }
+        Ok(())
+    }
+
+    fn check_draw(&self) -> Result<(), String> {
+        self.check_uniform()?;
+        self.check_framebuffer(self.draw_framebuffer)
+    }
+
+    fn check_draw_elements(&self) -> Result<(), String> {
+        self.check_draw()?;
+        if self.bound_buffer(ffi::ELEMENT_ARRAY_BUFFER) == 0 {
+            return Err(format!(
+                "no element array buffer is bound to vertex array {}",
+                self.vertex_array
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_read(&self) -> Result<(), String> {
+        self.check_framebuffer(self.read_framebuffer)
+    }
+
+    fn check_attrib_pointer(&self) -> Result<(), String> {
+        self.check_buffer_bound(ffi::ARRAY_BUFFER)
+    }
+}
+
+/// A wrapper around GL context that shadows the bound objects and the sizes
+/// of the resources created through it, and rejects calls that would be
+/// invalid before they reach the driver.
+///
+/// The wrapper should be installed on a fresh context: bindings made before
+/// it was installed are not known to it.
+///
+/// Each rejected call is reported to the callback with the method name and a
+/// description of the problem, and is not forwarded. Rejected calls that
+/// return data return zeroes.
+pub struct ValidatingGl<F> {
+    gl: Rc<dyn Gl>,
+    state: RefCell<ValidationState>,
+    callback: F,
+}
+
+impl<F: 'static + Fn(&str, &str)> ValidatingGl<F> {
+    pub fn wrap(fns: Rc<dyn Gl>, callback: F) -> Rc<dyn Gl> {
+        let mut state = ValidationState::default();
+        state.vertex_arrays.insert(0, 0);
+        Rc::new(ValidatingGl {
+            gl: fns,
+            state: RefCell::new(state),
+            callback,
+        }) as Rc<dyn Gl>
+    }
+
+    fn allowed<C: FnOnce(&ValidationState) -> Result<(), String>>(
+        &self,
+        name: &str,
+        check: C,
+    ) -> bool {
+        match check(&self.state.borrow()) {
+            Ok(()) => true,
+            Err(reason) => {
+                (self.callback)(name, &reason);
+                false
+            }
+        }
+    }
+}
+
+impl<F: 'static + Fn(&str, &str)> Gl for ValidatingGl<F> {
+    fn capabilities(&self) -> &GlCapabilities {
+        self.gl.capabilities()
+    }
+
+    fn get_type(&self) -> GlType {
+        self.gl.get_type()
+    }
+
+    fn buffer_data_untyped(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        usage: GLenum,
+    ) {
+        if !self.allowed("buffer_data_untyped", |state| {
+            state.check_buffer_bound(target)
+        }) {
+            return;
+        }
+        self.gl.buffer_data_untyped(target, size, data, usage);
+        let state = &mut *self.state.borrow_mut();
+        state.set_buffer_size(target, size);
+    }
+
+    fn buffer_sub_data_untyped(
+        &self,
+        target: GLenum,
+        offset: isize,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+    ) {
+        if !self.allowed("buffer_sub_data_untyped", |state| {
+            state.check_buffer_range(target, offset, size)
+        }) {
+            return;
+        }
+        self.gl.buffer_sub_data_untyped(target, offset, size, data)
+    }
+
+    fn map_buffer(&self, target: GLenum, access: GLbitfield) -> *mut c_void {
+        if !self.allowed("map_buffer", |state| state.check_buffer_bound(target)) {
+            return ptr::null_mut();
+        }
+        self.gl.map_buffer(target, access)
+    }
+
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: GLintptr,
+        length: GLsizeiptr,
+        access: GLbitfield,
+    ) -> *mut c_void {
+        if !self.allowed("map_buffer_range", |state| {
+            state.check_buffer_range(target, offset, length)
+        }) {
+            return ptr::null_mut();
+        }
+        self.gl.map_buffer_range(target, offset, length, access)
+    }
+
+    fn unmap_buffer(&self, target: GLenum) -> GLboolean {
+        self.gl.unmap_buffer(target)
+    }
+
+    fn tex_buffer(&self, target: GLenum, internal_format: GLenum, buffer: GLuint) {
+        self.gl.tex_buffer(target, internal_format, buffer)
+    }
+
+    fn shader_source(&self, shader: GLuint, strings: &[&[u8]]) {
+        self.gl.shader_source(shader, strings)
+    }
+
+    fn read_buffer(&self, mode: GLenum) {
+        self.gl.read_buffer(mode)
+    }
+
+    fn read_pixels_into_buffer(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+        dst_buffer: &mut [u8],
+    ) -> Result<(), PixelDataError> {
+        if !self.allowed("read_pixels_into_buffer", |state| {
+            state.check_client_pixels(ffi::PIXEL_PACK_BUFFER)?;
+            state.check_read()
+        }) {
+            return Ok(());
+        }
+        self.gl
+            .read_pixels_into_buffer(x, y, width, height, format, pixel_type, dst_buffer)
+    }
+
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+    ) -> Result<Vec<u8>, PixelDataError> {
+        if !self.allowed("read_pixels", |state| {
+            state.check_client_pixels(ffi::PIXEL_PACK_BUFFER)?;
+            state.check_read()
+        }) {
+            return calculate_length(width, height, format, pixel_type).map(|len| vec![0; len]);
+        }
+        self.gl.read_pixels(x, y, width, height, format, pixel_type)
+    }
+
+    unsafe fn read_pixels_into_pbo(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        pixel_type: GLenum,
+    ) {
+        if !self.allowed("read_pixels_into_pbo", |state| {
+            state.check_buffer_bound(ffi::PIXEL_PACK_BUFFER)?;
+            state.check_read()
+        }) {
+            return;
+        }
+        self.gl
+            .read_pixels_into_pbo(x, y, width, height, format, pixel_type)
+    }
+
+    fn sample_coverage(&self, value: GLclampf, invert: bool) {
+        self.gl.sample_coverage(value, invert)
+    }
+
+    fn polygon_offset(&self, factor: GLfloat, units: GLfloat) {
+        self.gl.polygon_offset(factor, units)
+    }
+
+    fn pixel_store_i(&self, name: GLenum, param: GLint) {
+        self.gl.pixel_store_i(name, param)
+    }
+
+    fn gen_buffers(&self, n: GLsizei) -> Vec<GLuint> {
+        let rv = self.gl.gen_buffers(n);
+        let state = &mut *self.state.borrow_mut();
+        state.created("buffer", &rv);
+        rv
+    }
+
+    fn gen_renderbuffers(&self, n: GLsizei) -> Vec<GLuint> {
+        let rv = self.gl.gen_renderbuffers(n);
+        let state = &mut *self.state.borrow_mut();
+        state.created("renderbuffer", &rv);
+        rv
+    }
+
+    fn gen_framebuffers(&self, n: GLsizei) -> Vec<GLuint> {
+        let rv = self.gl.gen_framebuffers(n);
+        let state = &mut *self.state.borrow_mut();
+        state.created("framebuffer", &rv);
+        rv
+    }
+
+    fn gen_textures(&self, n: GLsizei) -> Vec<GLuint> {
+        let rv = self.gl.gen_textures(n);
+        let state = &mut *self.state.borrow_mut();
+        state.created("texture", &rv);
+        rv
+    }
+
+    fn gen_vertex_arrays(&self, n: GLsizei) -> Vec<GLuint> {
+        let rv = self.gl.gen_vertex_arrays(n);
+        let state = &mut *self.state.borrow_mut();
+        state.created("vertex array", &rv);
+        rv
+    }
+
+    fn gen_vertex_arrays_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        let rv = self.gl.gen_vertex_arrays_apple(n);
+        let state = &mut *self.state.borrow_mut();
+        state.created("vertex array", &rv);
+        rv
+    }
+
+    fn gen_queries(&self, n: GLsizei) -> Vec<GLuint> {
+        self.gl.gen_queries(n)
+    }
+
+    fn begin_query(&self, target: GLenum, id: GLuint) {
+        self.gl.begin_query(target, id)
+    }
+
+    fn end_query(&self, target: GLenum) {
+        self.gl.end_query(target)
+    }
+
+    fn query_counter(&self, id: GLuint, target: GLenum) {
+        self.gl.query_counter(id, target)
+    }
+
+    fn get_query_iv(&self, target: GLenum, pname: GLenum) -> i32 {
+        self.gl.get_query_iv(target, pname)
+    }
+
+    fn get_query_object_iv(&self, id: GLuint, pname: GLenum) -> i32 {
+        self.gl.get_query_object_iv(id, pname)
+    }
+
+    fn get_query_object_uiv(&self, id: GLuint, pname: GLenum) -> u32 {
+        self.gl.get_query_object_uiv(id, pname)
+    }
+
+    fn get_query_object_i64v(&self, id: GLuint, pname: GLenum) -> i64 {
+        self.gl.get_query_object_i64v(id, pname)
+    }
+
+    fn get_query_object_ui64v(&self, id: GLuint, pname: GLenum) -> u64 {
+        self.gl.get_query_object_ui64v(id, pname)
+    }
+
+    fn delete_queries(&self, queries: &[GLuint]) {
+        self.gl.delete_queries(queries)
+    }
+
+    fn delete_vertex_arrays(&self, vertex_arrays: &[GLuint]) {
+        if !self.allowed("delete_vertex_arrays", |state| {
+            state.check_unbound("vertex array", vertex_arrays)
+        }) {
+            return;
+        }
+        self.gl.delete_vertex_arrays(vertex_arrays);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("vertex array", vertex_arrays);
+    }
+
+    fn delete_vertex_arrays_apple(&self, vertex_arrays: &[GLuint]) {
+        if !self.allowed("delete_vertex_arrays_apple", |state| {
+            state.check_unbound("vertex array", vertex_arrays)
+        }) {
+            return;
+        }
+        self.gl.delete_vertex_arrays_apple(vertex_arrays);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("vertex array", vertex_arrays);
+    }
+
+    fn delete_buffers(&self, buffers: &[GLuint]) {
+        if !self.allowed("delete_buffers", |state| {
+            state.check_unbound("buffer", buffers)
+        }) {
+            return;
+        }
+        self.gl.delete_buffers(buffers);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("buffer", buffers);
+    }
+
+    fn delete_renderbuffers(&self, renderbuffers: &[GLuint]) {
+        if !self.allowed("delete_renderbuffers", |state| {
+            state.check_unbound("renderbuffer", renderbuffers)
+        }) {
+            return;
+        }
+        self.gl.delete_renderbuffers(renderbuffers);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("renderbuffer", renderbuffers);
+    }
+
+    fn delete_framebuffers(&self, framebuffers: &[GLuint]) {
+        if !self.allowed("delete_framebuffers", |state| {
+            state.check_unbound("framebuffer", framebuffers)
+        }) {
+            return;
+        }
+        self.gl.delete_framebuffers(framebuffers);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("framebuffer", framebuffers);
+    }
+
+    fn delete_textures(&self, textures: &[GLuint]) {
+        if !self.allowed("delete_textures", |state| {
+            state.check_unbound("texture", textures)
+        }) {
+            return;
+        }
+        self.gl.delete_textures(textures);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("texture", textures);
+    }
+
+    fn framebuffer_renderbuffer(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        renderbuffertarget: GLenum,
+        renderbuffer: GLuint,
+    ) {
+        self.gl
+            .framebuffer_renderbuffer(target, attachment, renderbuffertarget, renderbuffer);
+        let state = &mut *self.state.borrow_mut();
+        state.attach(
+            target,
+            attachment,
+            Some(ValidationAttachment::Renderbuffer(renderbuffer)).filter(|_| renderbuffer != 0),
+        );
+    }
+
+    fn renderbuffer_storage(
+        &self,
+        target: GLenum,
+        internalformat: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.gl
+            .renderbuffer_storage(target, internalformat, width, height);
+        let state = &mut *self.state.borrow_mut();
+        state.renderbuffer_storage();
+    }
+
+    fn depth_func(&self, func: GLenum) {
+        self.gl.depth_func(func)
+    }
+
+    fn active_texture(&self, texture: GLenum) {
+        self.gl.active_texture(texture);
+        let state = &mut *self.state.borrow_mut();
+        state.active_texture = texture - ffi::TEXTURE0;
+    }
+
+    fn attach_shader(&self, program: GLuint, shader: GLuint) {
+        self.gl.attach_shader(program, shader)
+    }
+
+    fn bind_attrib_location(&self, program: GLuint, index: GLuint, name: &str) {
+        self.gl.bind_attrib_location(program, index, name)
+    }
+
+    unsafe fn get_uniform_iv(&self, program: GLuint, location: GLint, result: &mut [GLint]) {
+        self.gl.get_uniform_iv(program, location, result)
+    }
+
+    unsafe fn get_uniform_fv(&self, program: GLuint, location: GLint, result: &mut [GLfloat]) {
+        self.gl.get_uniform_fv(program, location, result)
+    }
+
+    fn get_uniform_block_index(&self, program: GLuint, name: &str) -> GLuint {
+        self.gl.get_uniform_block_index(program, name)
+    }
+
+    fn get_uniform_indices(&self, program: GLuint, names: &[&str]) -> Vec<GLuint> {
+        self.gl.get_uniform_indices(program, names)
+    }
+
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, buffer: GLuint) {
+        if !self.allowed("bind_buffer_base", |state| {
+            state.check_live("buffer", buffer)
+        }) {
+            return;
+        }
+        self.gl.bind_buffer_base(target, index, buffer);
+        let state = &mut *self.state.borrow_mut();
+        state.bind_buffer(target, buffer);
+    }
+
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        size: GLsizeiptr,
+    ) {
+        if !self.allowed("bind_buffer_range", |state| {
+            state.check_live("buffer", buffer)
+        }) {
+            return;
+        }
+        self.gl
+            .bind_buffer_range(target, index, buffer, offset, size);
+        let state = &mut *self.state.borrow_mut();
+        state.bind_buffer(target, buffer);
+    }
+
+    fn uniform_block_binding(
+        &self,
+        program: GLuint,
+        uniform_block_index: GLuint,
+        uniform_block_binding: GLuint,
+    ) {
+        self.gl
+            .uniform_block_binding(program, uniform_block_index, uniform_block_binding)
+    }
+
+    fn bind_buffer(&self, target: GLenum, buffer: GLuint) {
+        if !self.allowed("bind_buffer", |state| state.check_live("buffer", buffer)) {
+            return;
+        }
+        self.gl.bind_buffer(target, buffer);
+        let state = &mut *self.state.borrow_mut();
+        state.bind_buffer(target, buffer);
+    }
+
+    fn bind_vertex_array(&self, vao: GLuint) {
+        if !self.allowed("bind_vertex_array", |state| {
+            state.check_live("vertex array", vao)
+        }) {
+            return;
+        }
+        self.gl.bind_vertex_array(vao);
+        let state = &mut *self.state.borrow_mut();
+        state.vertex_array = vao;
+    }
+
+    fn bind_vertex_array_apple(&self, vao: GLuint) {
+        if !self.allowed("bind_vertex_array_apple", |state| {
+            state.check_live("vertex array", vao)
+        }) {
+            return;
+        }
+        self.gl.bind_vertex_array_apple(vao);
+        let state = &mut *self.state.borrow_mut();
+        state.vertex_array = vao;
+    }
+
+    fn bind_renderbuffer(&self, target: GLenum, renderbuffer: GLuint) {
+        if !self.allowed("bind_renderbuffer", |state| {
+            state.check_live("renderbuffer", renderbuffer)
+        }) {
+            return;
+        }
+        self.gl.bind_renderbuffer(target, renderbuffer);
+        let state = &mut *self.state.borrow_mut();
+        state.renderbuffer = renderbuffer;
+    }
+
+    fn bind_framebuffer(&self, target: GLenum, framebuffer: GLuint) {
+        if !self.allowed("bind_framebuffer", |state| {
+            state.check_live("framebuffer", framebuffer)
+        }) {
+            return;
+        }
+        self.gl.bind_framebuffer(target, framebuffer);
+        let state = &mut *self.state.borrow_mut();
+        state.bind_framebuffer(target, framebuffer);
+    }
+
+    fn bind_texture(&self, target: GLenum, texture: GLuint) {
+        if !self.allowed("bind_texture", |state| {
+            state.check_bind_texture(target, texture)
+        }) {
+            return;
+        }
+        self.gl.bind_texture(target, texture);
+        let state = &mut *self.state.borrow_mut();
+        state.bind_texture(target, texture);
+    }
+
+    fn bind_vertex_buffer(
+        &self,
+        binding_index: GLuint,
+        buffer: GLuint,
+        offset: GLintptr,
+        stride: GLint,
+    ) {
+        self.gl
+            .bind_vertex_buffer(binding_index, buffer, offset, stride)
+    }
+
+    fn draw_buffers(&self, bufs: &[GLenum]) {
+        self.gl.draw_buffers(bufs)
+    }
+
+    fn tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ty: GLenum,
+        opt_data: Option<&[u8]>,
+    ) -> Result<(), PixelDataError> {
+        if !self.allowed("tex_image_2d", |state| {
+            state.check_client_pixels(ffi::PIXEL_UNPACK_BUFFER)
+        }) {
+            return Ok(());
+        }
+        let rv = self.gl.tex_image_2d(
+            target,
+            level,
+            internal_format,
+            width,
+            height,
+            border,
+            format,
+            ty,
+            opt_data,
+        );
+        if rv.is_ok() {
+            self.state
+                .borrow_mut()
+                .define_level(target, level, Some((width, height, 1)));
+        }
+        rv
+    }
+
+    fn compressed_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        data: &[u8],
+    ) {
+        self.gl.compressed_tex_image_2d(
+            target,
+            level,
+            internal_format,
+            width,
+            height,
+            border,
+            data,
+        );
+        let state = &mut *self.state.borrow_mut();
+        state.define_level(target, level, Some((width, height, 1)));
+    }
+
+    fn compressed_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        data: &[u8],
+    ) {
+        if !self.allowed("compressed_tex_sub_image_2d", |state| {
+            state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1))
+        }) {
+            return;
+        }
+        self.gl.compressed_tex_sub_image_2d(
+            target, level, xoffset, yoffset, width, height, format, data,
+        )
+    }
+
+    fn tex_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        border: GLint,
+        format: GLenum,
+        ty: GLenum,
+        opt_data: Option<&[u8]>,
+    ) -> Result<(), PixelDataError> {
+        if !self.allowed("tex_image_3d", |state| {
+            state.check_client_pixels(ffi::PIXEL_UNPACK_BUFFER)
+        }) {
+            return Ok(());
+        }
+        let rv = self.gl.tex_image_3d(
+            target,
+            level,
+            internal_format,
+            width,
+            height,
+            depth,
+            border,
+            format,
+            ty,
+            opt_data,
+        );
+        if rv.is_ok() {
+            self.state
+                .borrow_mut()
+                .define_level(target, level, Some((width, height, depth)));
+        }
+        rv
+    }
+
+    fn copy_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLenum,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+    ) {
+        if !self.allowed("copy_tex_image_2d", |state| state.check_read()) {
+            return;
+        }
+        self.gl
+            .copy_tex_image_2d(target, level, internal_format, x, y, width, height, border);
+        let state = &mut *self.state.borrow_mut();
+        state.define_level(target, level, Some((width, height, 1)));
+    }
+
+    fn copy_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        if !self.allowed("copy_tex_sub_image_2d", |state| {
+            state.check_read()?;
+            state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1))
+        }) {
+            return;
+        }
+        self.gl
+            .copy_tex_sub_image_2d(target, level, xoffset, yoffset, x, y, width, height)
+    }
+
+    fn copy_tex_sub_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        if !self.allowed("copy_tex_sub_image_3d", |state| {
+            state.check_read()?;
+            state.check_sub_image(
+                target,
+                level,
+                (xoffset, yoffset, zoffset),
+                (width, height, 1),
+            )
+        }) {
+            return;
+        }
+        self.gl.copy_tex_sub_image_3d(
+            target, level, xoffset, yoffset, zoffset, x, y, width, height,
+        )
+    }
+
+    fn tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        data: &[u8],
+    ) -> Result<(), PixelDataError> {
+        if !self.allowed("tex_sub_image_2d", |state| {
+            state.check_client_pixels(ffi::PIXEL_UNPACK_BUFFER)?;
+            state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1))
+        }) {
+            return Ok(());
+        }
+        self.gl.tex_sub_image_2d(
+            target, level, xoffset, yoffset, width, height, format, ty, data,
+        )
+    }
+
+    fn tex_sub_image_2d_pbo(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        offset: usize,
+    ) {
+        if !self.allowed("tex_sub_image_2d_pbo", |state| {
+            state.check_buffer_bound(ffi::PIXEL_UNPACK_BUFFER)?;
+            state.check_sub_image(target, level, (xoffset, yoffset, 0), (width, height, 1))
+        }) {
+            return;
+        }
+        self.gl.tex_sub_image_2d_pbo(
+            target, level, xoffset, yoffset, width, height, format, ty, offset,
+        )
+    }
+
+    fn tex_sub_image_3d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        data: &[u8],
+    ) -> Result<(), PixelDataError> {
+        if !self.allowed("tex_sub_image_3d", |state| {
+            state.check_client_pixels(ffi::PIXEL_UNPACK_BUFFER)?;
+            state.check_sub_image(
+                target,
+                level,
+                (xoffset, yoffset, zoffset),
+                (width, height, depth),
+            )
+        }) {
+            return Ok(());
+        }
+        self.gl.tex_sub_image_3d(
+            target, level, xoffset, yoffset, zoffset, width, height, depth, format, ty, data,
+        )
+    }
+
+    fn tex_sub_image_3d_pbo(
+        &self,
+        target: GLenum,
+        level: GLint,
+        xoffset: GLint,
+        yoffset: GLint,
+        zoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        format: GLenum,
+        ty: GLenum,
+        offset: usize,
+    ) {
+        if !self.allowed("tex_sub_image_3d_pbo", |state| {
+            state.check_buffer_bound(ffi::PIXEL_UNPACK_BUFFER)?;
+            state.check_sub_image(
+                target,
+                level,
+                (xoffset, yoffset, zoffset),
+                (width, height, depth),
+            )
+        }) {
+            return;
+        }
+        self.gl.tex_sub_image_3d_pbo(
+            target, level, xoffset, yoffset, zoffset, width, height, depth, format, ty, offset,
+        )
+    }
+
+    fn tex_storage_2d(
+        &self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.gl
+            .tex_storage_2d(target, levels, internal_format, width, height);
+        let state = &mut *self.state.borrow_mut();
+        state.define_storage(target, levels, (width, height, 1));
+    }
+
+    fn tex_storage_3d(
+        &self,
+        target: GLenum,
+        levels: GLint,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+    ) {
+        self.gl
+            .tex_storage_3d(target, levels, internal_format, width, height, depth);
+        let state = &mut *self.state.borrow_mut();
+        state.define_storage(target, levels, (width, height, depth));
+    }
+
+    fn get_tex_image_into_buffer(
+        &self,
+        target: GLenum,
+        level: GLint,
+        format: GLenum,
+        ty: GLenum,
+        output: &mut [u8],
+    ) -> Result<(), PixelDataError> {
+        self.gl
+            .get_tex_image_into_buffer(target, level, format, ty, output)
+    }
+
+    unsafe fn copy_image_sub_data(
+        &self,
+        src_name: GLuint,
+        src_target: GLenum,
+        src_level: GLint,
+        src_x: GLint,
+        src_y: GLint,
+        src_z: GLint,
+        dst_name: GLuint,
+        dst_target: GLenum,
+        dst_level: GLint,
+        dst_x: GLint,
+        dst_y: GLint,
+        dst_z: GLint,
+        src_width: GLsizei,
+        src_height: GLsizei,
+        src_depth: GLsizei,
+    ) {
+        self.gl.copy_image_sub_data(
+            src_name, src_target, src_level, src_x, src_y, src_z, dst_name, dst_target, dst_level,
+            dst_x, dst_y, dst_z, src_width, src_height, src_depth,
+        )
+    }
+
+    fn invalidate_framebuffer(&self, target: GLenum, attachments: &[GLenum]) {
+        self.gl.invalidate_framebuffer(target, attachments)
+    }
+
+    fn invalidate_sub_framebuffer(
+        &self,
+        target: GLenum,
+        attachments: &[GLenum],
+        xoffset: GLint,
+        yoffset: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.gl
+            .invalidate_sub_framebuffer(target, attachments, xoffset, yoffset, width, height)
+    }
+
+    unsafe fn get_integer_v(&self, name: GLenum, result: &mut [GLint]) {
+        self.gl.get_integer_v(name, result)
+    }
+
+    unsafe fn get_integer_64v(&self, name: GLenum, result: &mut [GLint64]) {
+        self.gl.get_integer_64v(name, result)
+    }
+
+    unsafe fn get_integer_iv(&self, name: GLenum, index: GLuint, result: &mut [GLint]) {
+        self.gl.get_integer_iv(name, index, result)
+    }
+
+    unsafe fn get_integer_64iv(&self, name: GLenum, index: GLuint, result: &mut [GLint64]) {
+        self.gl.get_integer_64iv(name, index, result)
+    }
+
+    unsafe fn get_boolean_v(&self, name: GLenum, result: &mut [GLboolean]) {
+        self.gl.get_boolean_v(name, result)
+    }
+
+    unsafe fn get_float_v(&self, name: GLenum, result: &mut [GLfloat]) {
+        self.gl.get_float_v(name, result)
+    }
+
+    fn get_framebuffer_attachment_parameter_iv(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        self.gl
+            .get_framebuffer_attachment_parameter_iv(target, attachment, pname)
+    }
+
+    fn get_renderbuffer_parameter_iv(&self, target: GLenum, pname: GLenum) -> GLint {
+        self.gl.get_renderbuffer_parameter_iv(target, pname)
+    }
+
+    fn get_tex_parameter_iv(&self, target: GLenum, name: GLenum) -> GLint {
+        self.gl.get_tex_parameter_iv(target, name)
+    }
+
+    fn get_tex_parameter_fv(&self, target: GLenum, name: GLenum) -> GLfloat {
+        self.gl.get_tex_parameter_fv(target, name)
+    }
+
+    fn tex_parameter_i(&self, target: GLenum, pname: GLenum, param: GLint) {
+        self.gl.tex_parameter_i(target, pname, param)
+    }
+
+    fn tex_parameter_f(&self, target: GLenum, pname: GLenum, param: GLfloat) {
+        self.gl.tex_parameter_f(target, pname, param)
+    }
+
+    fn framebuffer_texture_2d(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        textarget: GLenum,
+        texture: GLuint,
+        level: GLint,
+    ) {
+        self.gl
+            .framebuffer_texture_2d(target, attachment, textarget, texture, level);
+        let state = &mut *self.state.borrow_mut();
+        state.attach(
+            target,
+            attachment,
+            Some(ValidationAttachment::Texture(texture, textarget, level)).filter(|_| texture != 0),
+        );
+    }
+
+    fn framebuffer_texture_layer(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        texture: GLuint,
+        level: GLint,
+        layer: GLint,
+    ) {
+        self.gl
+            .framebuffer_texture_layer(target, attachment, texture, level, layer);
+        let state = &mut *self.state.borrow_mut();
+        state.attach_layer(target, attachment, texture, level);
+    }
+
+    fn blit_framebuffer(
+        &self,
+        src_x0: GLint,
+        src_y0: GLint,
+        src_x1: GLint,
+        src_y1: GLint,
+        dst_x0: GLint,
+        dst_y0: GLint,
+        dst_x1: GLint,
+        dst_y1: GLint,
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        if !self.allowed("blit_framebuffer", |state| {
+            state.check_read()?;
+            state.check_framebuffer(state.draw_framebuffer)
+        }) {
+            return;
+        }
+        self.gl.blit_framebuffer(
+            src_x0, src_y0, src_x1, src_y1, dst_x0, dst_y0, dst_x1, dst_y1, mask, filter,
+        )
+    }
+
+    fn vertex_attrib_4f(&self, index: GLuint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+        self.gl.vertex_attrib_4f(index, x, y, z, w)
+    }
+
+    fn vertex_attrib_binding(&self, attrib_index: GLuint, binding_index: GLuint) {
+        self.gl.vertex_attrib_binding(attrib_index, binding_index)
+    }
+
+    fn vertex_attrib_pointer_f32(
+        &self,
+        index: GLuint,
+        size: GLint,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        if !self.allowed("vertex_attrib_pointer_f32", |state| {
+            state.check_attrib_pointer()
+        }) {
+            return;
+        }
+        self.gl
+            .vertex_attrib_pointer_f32(index, size, normalized, stride, offset)
+    }
+
+    fn vertex_attrib_pointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        if !self.allowed("vertex_attrib_pointer", |state| {
+            state.check_attrib_pointer()
+        }) {
+            return;
+        }
+        self.gl
+            .vertex_attrib_pointer(index, size, type_, normalized, stride, offset)
+    }
+
+    fn vertex_attrib_i_pointer(
+        &self,
+        index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        stride: GLsizei,
+        offset: GLuint,
+    ) {
+        if !self.allowed("vertex_attrib_i_pointer", |state| {
+            state.check_attrib_pointer()
+        }) {
+            return;
+        }
+        self.gl
+            .vertex_attrib_i_pointer(index, size, type_, stride, offset)
+    }
+
+    fn vertex_attrib_divisor(&self, index: GLuint, divisor: GLuint) {
+        self.gl.vertex_attrib_divisor(index, divisor)
+    }
+
+    fn vertex_attrib_format(
+        &self,
+        attrib_index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        normalized: bool,
+        relative_offset: GLuint,
+    ) {
+        self.gl
+            .vertex_attrib_format(attrib_index, size, type_, normalized, relative_offset)
+    }
+
+    fn vertex_attrib_i_format(
+        &self,
+        attrib_index: GLuint,
+        size: GLint,
+        type_: GLenum,
+        relative_offset: GLuint,
+    ) {
+        self.gl
+            .vertex_attrib_i_format(attrib_index, size, type_, relative_offset)
+    }
+
+    fn vertex_binding_divisor(&self, binding_index: GLuint, divisor: GLuint) {
+        self.gl.vertex_binding_divisor(binding_index, divisor)
+    }
+
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.gl.viewport(x, y, width, height)
+    }
+
+    fn scissor(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.gl.scissor(x, y, width, height)
+    }
+
+    fn line_width(&self, width: GLfloat) {
+        self.gl.line_width(width)
+    }
+
+    fn use_program(&self, program: GLuint) {
+        if !self.allowed("use_program", |state| state.check_live("program", program)) {
+            return;
+        }
+        self.gl.use_program(program);
+        let state = &mut *self.state.borrow_mut();
+        state.program = program;
+    }
+
+    fn validate_program(&self, program: GLuint) {
+        self.gl.validate_program(program)
+    }
+
+    fn draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        if !self.allowed("draw_arrays", |state| state.check_draw()) {
+            return;
+        }
+        self.gl.draw_arrays(mode, first, count)
+    }
+
+    fn draw_arrays_instanced(
+        &self,
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        primcount: GLsizei,
+    ) {
+        if !self.allowed("draw_arrays_instanced", |state| state.check_draw()) {
+            return;
+        }
+        self.gl.draw_arrays_instanced(mode, first, count, primcount)
+    }
+
+    fn draw_elements(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        element_type: GLenum,
+        indices_offset: GLuint,
+    ) {
+        if !self.allowed("draw_elements", |state| state.check_draw_elements()) {
+            return;
+        }
+        self.gl
+            .draw_elements(mode, count, element_type, indices_offset)
+    }
+
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        element_type: GLenum,
+        indices_offset: GLuint,
+        primcount: GLsizei,
+    ) {
+        if !self.allowed("draw_elements_instanced", |state| {
+            state.check_draw_elements()
+        }) {
+            return;
+        }
+        self.gl
+            .draw_elements_instanced(mode, count, element_type, indices_offset, primcount)
+    }
+
+    fn blend_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.gl.blend_color(r, g, b, a)
+    }
+
+    fn blend_func(&self, sfactor: GLenum, dfactor: GLenum) {
+        self.gl.blend_func(sfactor, dfactor)
+    }
+
+    fn blend_func_separate(
+        &self,
+        src_rgb: GLenum,
+        dest_rgb: GLenum,
+        src_alpha: GLenum,
+        dest_alpha: GLenum,
+    ) {
+        self.gl
+            .blend_func_separate(src_rgb, dest_rgb, src_alpha, dest_alpha)
+    }
+
+    fn blend_equation(&self, mode: GLenum) {
+        self.gl.blend_equation(mode)
+    }
+
+    fn blend_equation_separate(&self, mode_rgb: GLenum, mode_alpha: GLenum) {
+        self.gl.blend_equation_separate(mode_rgb, mode_alpha)
+    }
+
+    fn color_mask(&self, r: bool, g: bool, b: bool, a: bool) {
+        self.gl.color_mask(r, g, b, a)
+    }
+
+    fn cull_face(&self, mode: GLenum) {
+        self.gl.cull_face(mode)
+    }
+
+    fn front_face(&self, mode: GLenum) {
+        self.gl.front_face(mode)
+    }
+
+    fn enable(&self, cap: GLenum) {
+        self.gl.enable(cap)
+    }
+
+    fn disable(&self, cap: GLenum) {
+        self.gl.disable(cap)
+    }
+
+    fn hint(&self, param_name: GLenum, param_val: GLenum) {
+        self.gl.hint(param_name, param_val)
+    }
+
+    fn is_enabled(&self, cap: GLenum) -> GLboolean {
+        self.gl.is_enabled(cap)
+    }
+
+    fn is_shader(&self, shader: GLuint) -> GLboolean {
+        self.gl.is_shader(shader)
+    }
+
+    fn is_texture(&self, texture: GLenum) -> GLboolean {
+        self.gl.is_texture(texture)
+    }
+
+    fn is_framebuffer(&self, framebuffer: GLenum) -> GLboolean {
+        self.gl.is_framebuffer(framebuffer)
+    }
+
+    fn is_renderbuffer(&self, renderbuffer: GLenum) -> GLboolean {
+        self.gl.is_renderbuffer(renderbuffer)
+    }
+
+    fn check_frame_buffer_status(&self, target: GLenum) -> GLenum {
+        self.gl.check_frame_buffer_status(target)
+    }
+
+    fn enable_vertex_attrib_array(&self, index: GLuint) {
+        self.gl.enable_vertex_attrib_array(index)
+    }
+
+    fn disable_vertex_attrib_array(&self, index: GLuint) {
+        self.gl.disable_vertex_attrib_array(index)
+    }
+
+    fn uniform_1f(&self, location: GLint, v0: GLfloat) {
+        if !self.allowed("uniform_1f", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_1f(location, v0)
+    }
+
+    fn uniform_1fv(&self, location: GLint, values: &[f32]) {
+        if !self.allowed("uniform_1fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_1fv(location, values)
+    }
+
+    fn uniform_1i(&self, location: GLint, v0: GLint) {
+        if !self.allowed("uniform_1i", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_1i(location, v0)
+    }
+
+    fn uniform_1iv(&self, location: GLint, values: &[i32]) {
+        if !self.allowed("uniform_1iv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_1iv(location, values)
+    }
+
+    fn uniform_1ui(&self, location: GLint, v0: GLuint) {
+        if !self.allowed("uniform_1ui", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_1ui(location, v0)
+    }
+
+    fn uniform_2f(&self, location: GLint, v0: GLfloat, v1: GLfloat) {
+        if !self.allowed("uniform_2f", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_2f(location, v0, v1)
+    }
+
+    fn uniform_2fv(&self, location: GLint, values: &[f32]) {
+        if !self.allowed("uniform_2fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_2fv(location, values)
+    }
+
+    fn uniform_2i(&self, location: GLint, v0: GLint, v1: GLint) {
+        if !self.allowed("uniform_2i", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_2i(location, v0, v1)
+    }
+
+    fn uniform_2iv(&self, location: GLint, values: &[i32]) {
+        if !self.allowed("uniform_2iv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_2iv(location, values)
+    }
+
+    fn uniform_2ui(&self, location: GLint, v0: GLuint, v1: GLuint) {
+        if !self.allowed("uniform_2ui", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_2ui(location, v0, v1)
+    }
+
+    fn uniform_3f(&self, location: GLint, v0: GLfloat, v1: GLfloat, v2: GLfloat) {
+        if !self.allowed("uniform_3f", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_3f(location, v0, v1, v2)
+    }
+
+    fn uniform_3fv(&self, location: GLint, values: &[f32]) {
+        if !self.allowed("uniform_3fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_3fv(location, values)
+    }
+
+    fn uniform_3i(&self, location: GLint, v0: GLint, v1: GLint, v2: GLint) {
+        if !self.allowed("uniform_3i", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_3i(location, v0, v1, v2)
+    }
+
+    fn uniform_3iv(&self, location: GLint, values: &[i32]) {
+        if !self.allowed("uniform_3iv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_3iv(location, values)
+    }
+
+    fn uniform_3ui(&self, location: GLint, v0: GLuint, v1: GLuint, v2: GLuint) {
+        if !self.allowed("uniform_3ui", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_3ui(location, v0, v1, v2)
+    }
+
+    fn uniform_4f(&self, location: GLint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+        if !self.allowed("uniform_4f", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_4f(location, x, y, z, w)
+    }
+
+    fn uniform_4i(&self, location: GLint, x: GLint, y: GLint, z: GLint, w: GLint) {
+        if !self.allowed("uniform_4i", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_4i(location, x, y, z, w)
+    }
+
+    fn uniform_4iv(&self, location: GLint, values: &[i32]) {
+        if !self.allowed("uniform_4iv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_4iv(location, values)
+    }
+
+    fn uniform_4ui(&self, location: GLint, x: GLuint, y: GLuint, z: GLuint, w: GLuint) {
+        if !self.allowed("uniform_4ui", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_4ui(location, x, y, z, w)
+    }
+
+    fn uniform_4fv(&self, location: GLint, values: &[f32]) {
+        if !self.allowed("uniform_4fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_4fv(location, values)
+    }
+
+    fn uniform_matrix_2fv(&self, location: GLint, transpose: bool, value: &[f32]) {
+        if !self.allowed("uniform_matrix_2fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_matrix_2fv(location, transpose, value)
+    }
+
+    fn uniform_matrix_3fv(&self, location: GLint, transpose: bool, value: &[f32]) {
+        if !self.allowed("uniform_matrix_3fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_matrix_3fv(location, transpose, value)
+    }
+
+    fn uniform_matrix_4fv(&self, location: GLint, transpose: bool, value: &[f32]) {
+        if !self.allowed("uniform_matrix_4fv", |state| state.check_uniform()) {
+            return;
+        }
+        self.gl.uniform_matrix_4fv(location, transpose, value)
+    }
+
+    fn depth_mask(&self, flag: bool) {
+        self.gl.depth_mask(flag)
+    }
+
+    fn depth_range(&self, near: f64, far: f64) {
+        self.gl.depth_range(near, far)
+    }
+
+    fn get_active_attrib(&self, program: GLuint, index: GLuint) -> (i32, u32, String) {
+        self.gl.get_active_attrib(program, index)
+    }
+
+    fn get_active_uniform(&self, program: GLuint, index: GLuint) -> (i32, u32, String) {
+        self.gl.get_active_uniform(program, index)
+    }
+
+    fn get_active_uniforms_iv(
+        &self,
+        program: GLuint,
+        indices: Vec<GLuint>,
+        pname: GLenum,
+    ) -> Vec<GLint> {
+        self.gl.get_active_uniforms_iv(program, indices, pname)
+    }
+
+    fn get_active_uniform_block_i(&self, program: GLuint, index: GLuint, pname: GLenum) -> GLint {
+        self.gl.get_active_uniform_block_i(program, index, pname)
+    }
+
+    fn get_active_uniform_block_iv(
+        &self,
+        program: GLuint,
+        index: GLuint,
+        pname: GLenum,
+    ) -> Vec<GLint> {
+        self.gl.get_active_uniform_block_iv(program, index, pname)
+    }
+
+    fn get_active_uniform_block_name(&self, program: GLuint, index: GLuint) -> String {
+        self.gl.get_active_uniform_block_name(program, index)
+    }
+
+    fn get_program_interface_i(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        pname: GLenum,
+    ) -> GLint {
+        self.gl
+            .get_program_interface_i(program, program_interface, pname)
+    }
+
+    fn get_program_resource_name(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+    ) -> String {
+        self.gl
+            .get_program_resource_name(program, program_interface, index)
+    }
+
+    fn get_program_resource_iv(
+        &self,
+        program: GLuint,
+        program_interface: GLenum,
+        index: GLuint,
+        props: &[GLenum],
+    ) -> Vec<GLint> {
+        self.gl
+            .get_program_resource_iv(program, program_interface, index, props)
+    }
+
+    fn get_attrib_location(&self, program: GLuint, name: &str) -> c_int {
+        self.gl.get_attrib_location(program, name)
+    }
+
+    fn get_frag_data_location(&self, program: GLuint, name: &str) -> c_int {
+        self.gl.get_frag_data_location(program, name)
+    }
+
+    fn get_uniform_location(&self, program: GLuint, name: &str) -> c_int {
+        self.gl.get_uniform_location(program, name)
+    }
+
+    fn get_program_info_log(&self, program: GLuint) -> String {
+        self.gl.get_program_info_log(program)
+    }
+
+    unsafe fn get_program_iv(&self, program: GLuint, pname: GLenum, result: &mut [GLint]) {
+        self.gl.get_program_iv(program, pname, result)
+    }
+
+    fn get_program_binary(&self, program: GLuint) -> (Vec<u8>, GLenum) {
+        self.gl.get_program_binary(program)
+    }
+
+    fn program_binary(&self, program: GLuint, format: GLenum, binary: &[u8]) {
+        self.gl.program_binary(program, format, binary)
+    }
+
+    fn program_parameter_i(&self, program: GLuint, pname: GLenum, value: GLint) {
+        self.gl.program_parameter_i(program, pname, value)
+    }
+
+    unsafe fn get_vertex_attrib_iv(&self, index: GLuint, pname: GLenum, result: &mut [GLint]) {
+        self.gl.get_vertex_attrib_iv(index, pname, result)
+    }
+
+    unsafe fn get_vertex_attrib_fv(&self, index: GLuint, pname: GLenum, result: &mut [GLfloat]) {
+        self.gl.get_vertex_attrib_fv(index, pname, result)
+    }
+
+    fn get_vertex_attrib_pointer_v(&self, index: GLuint, pname: GLenum) -> GLsizeiptr {
+        self.gl.get_vertex_attrib_pointer_v(index, pname)
+    }
+
+    fn get_buffer_parameter_iv(&self, target: GLuint, pname: GLenum) -> GLint {
+        self.gl.get_buffer_parameter_iv(target, pname)
+    }
+
+    fn get_shader_info_log(&self, shader: GLuint) -> String {
+        self.gl.get_shader_info_log(shader)
+    }
+
+    fn get_string(&self, which: GLenum) -> String {
+        self.gl.get_string(which)
+    }
+
+    fn get_string_i(&self, which: GLenum, index: GLuint) -> String {
+        self.gl.get_string_i(which, index)
+    }
+
+    unsafe fn get_shader_iv(&self, shader: GLuint, pname: GLenum, result: &mut [GLint]) {
+        self.gl.get_shader_iv(shader, pname, result)
+    }
+
+    fn get_shader_precision_format(
+        &self,
+        shader_type: GLuint,
+        precision_type: GLuint,
+    ) -> (GLint, GLint, GLint) {
+        self.gl
+            .get_shader_precision_format(shader_type, precision_type)
+    }
+
+    fn compile_shader(&self, shader: GLuint) {
+        self.gl.compile_shader(shader)
+    }
+
+    fn create_program(&self) -> GLuint {
+        let rv = self.gl.create_program();
+        let state = &mut *self.state.borrow_mut();
+        state.created("program", &[rv]);
+        rv
+    }
+
+    fn delete_program(&self, program: GLuint) {
+        if !self.allowed("delete_program", |state| {
+            state.check_unbound("program", &[program])
+        }) {
+            return;
+        }
+        self.gl.delete_program(program);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("program", &[program]);
+    }
+
+    fn create_shader(&self, shader_type: GLenum) -> GLuint {
+        let rv = self.gl.create_shader(shader_type);
+        let state = &mut *self.state.borrow_mut();
+        state.created("shader", &[rv]);
+        rv
+    }
+
+    fn delete_shader(&self, shader: GLuint) {
+        self.gl.delete_shader(shader);
+        let state = &mut *self.state.borrow_mut();
+        state.deleted("shader", &[shader]);
+    }
+
+    fn detach_shader(&self, program: GLuint, shader: GLuint) {
+        self.gl.detach_shader(program, shader)
+    }
+
+    fn link_program(&self, program: GLuint) {
+        self.gl.link_program(program)
+    }
+
+    fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.gl.clear_color(r, g, b, a)
+    }
+
+    fn clear(&self, buffer_mask: GLbitfield) {
+        if !self.allowed("clear", |state| {
+            state.check_framebuffer(state.draw_framebuffer)
+        }) {
+            return;
+        }
+        self.gl.clear(buffer_mask)
+    }
+
+    fn clear_depth(&self, depth: f64) {
+        self.gl.clear_depth(depth)
+    }
+
+    fn clear_stencil(&self, s: GLint) {
+        self.gl.clear_stencil(s)
+    }
+
+    fn flush(&self) {
+        self.gl.flush()
+    }
+
+    fn finish(&self) {
+        self.gl.finish()
+    }
+
+    fn get_error(&self) -> GLenum {
+        self.gl.get_error()
+    }
+
+    fn stencil_mask(&self, mask: GLuint) {
+        self.gl.stencil_mask(mask)
+    }
+
+    fn stencil_mask_separate(&self, face: GLenum, mask: GLuint) {
+        self.gl.stencil_mask_separate(face, mask)
+    }
+
+    fn stencil_func(&self, func: GLenum, ref_: GLint, mask: GLuint) {
+        self.gl.stencil_func(func, ref_, mask)
+    }
+
+    fn stencil_func_separate(&self, face: GLenum, func: GLenum, ref_: GLint, mask: GLuint) {
+        self.gl.stencil_func_separate(face, func, ref_, mask)
+    }
+
+    fn stencil_op(&self, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        self.gl.stencil_op(sfail, dpfail, dppass)
+    }
+
+    fn stencil_op_separate(&self, face: GLenum, sfail: GLenum, dpfail: GLenum, dppass: GLenum) {
+        self.gl.stencil_op_separate(face, sfail, dpfail, dppass)
+    }
+
+    fn egl_image_target_texture2d_oes(&self, target: GLenum, image: GLeglImageOES) {
+        self.gl.egl_image_target_texture2d_oes(target, image);
+        let state = &mut *self.state.borrow_mut();
+        state.define_level(target, 0, None);
+    }
+
+    fn egl_image_target_renderbuffer_storage_oes(&self, target: GLenum, image: GLeglImageOES) {
+        self.gl
+            .egl_image_target_renderbuffer_storage_oes(target, image);
+        let state = &mut *self.state.borrow_mut();
+        state.renderbuffer_storage();
+    }
+
+    fn generate_mipmap(&self, target: GLenum) {
+        self.gl.generate_mipmap(target);
+        let state = &mut *self.state.borrow_mut();
+        state.generate_mipmap(target);
+    }
+
+    fn insert_event_marker_ext(&self, message: &str) {
+        self.gl.insert_event_marker_ext(message)
+    }
+
+    fn push_group_marker_ext(&self, message: &str) {
+        self.gl.push_group_marker_ext(message)
+    }
+
+    fn pop_group_marker_ext(&self) {
+        self.gl.pop_group_marker_ext()
+    }
+
+    fn debug_message_insert_khr(
+        &self,
+        source: GLenum,
+        type_: GLenum,
+        id: GLuint,
+        severity: GLenum,
+        message: &str,
+    ) {
+        self.gl
+            .debug_message_insert_khr(source, type_, id, severity, message)
+    }
+
+    fn push_debug_group_khr(&self, source: GLenum, id: GLuint, message: &str) {
+        self.gl.push_debug_group_khr(source, id, message)
+    }
+
+    fn pop_debug_group_khr(&self) {
+        self.gl.pop_debug_group_khr()
+    }
+
+    fn fence_sync(&self, condition: GLenum, flags: GLbitfield) -> GLsync {
+        self.gl.fence_sync(condition, flags)
+    }
+
+    fn client_wait_sync(&self, sync: GLsync, flags: GLbitfield, timeout: GLuint64) -> GLenum {
+        self.gl.client_wait_sync(sync, flags, timeout)
+    }
+
+    fn wait_sync(&self, sync: GLsync, flags: GLbitfield, timeout: GLuint64) {
+        self.gl.wait_sync(sync, flags, timeout)
+    }
+
+    fn delete_sync(&self, sync: GLsync) {
+        self.gl.delete_sync(sync)
+    }
+
+    fn texture_range_apple(&self, target: GLenum, data: &[u8]) {
+        self.gl.texture_range_apple(target, data)
+    }
+
+    fn gen_fences_apple(&self, n: GLsizei) -> Vec<GLuint> {
+        self.gl.gen_fences_apple(n)
+    }
+
+    fn delete_fences_apple(&self, fences: &[GLuint]) {
+        self.gl.delete_fences_apple(fences)
+    }
+
+    fn set_fence_apple(&self, fence: GLuint) {
+        self.gl.set_fence_apple(fence)
+    }
+
+    fn finish_fence_apple(&self, fence: GLuint) {
+        self.gl.finish_fence_apple(fence)
+    }
+
+    fn test_fence_apple(&self, fence: GLuint) {
+        self.gl.test_fence_apple(fence)
+    }
+
+    fn test_object_apple(&self, object: GLenum, name: GLuint) -> GLboolean {
+        self.gl.test_object_apple(object, name)
+    }
+
+    fn finish_object_apple(&self, object: GLenum, name: GLuint) {
+        self.gl.finish_object_apple(object, name)
+    }
+
+    fn blend_barrier_khr(&self) {
+        self.gl.blend_barrier_khr()
+    }
+
+    fn bind_frag_data_location_indexed(
+        &self,
+        program: GLuint,
+        color_number: GLuint,
+        index: GLuint,
+        name: &str,
+    ) {
+        self.gl
+            .bind_frag_data_location_indexed(program, color_number, index, name)
+    }
+
+    fn get_frag_data_index(&self, program: GLuint, name: &str) -> GLint {
+        self.gl.get_frag_data_index(program, name)
+    }
+
+    fn get_debug_messages(&self) -> Vec<DebugMessage> {
+        self.gl.get_debug_messages()
+    }
+
+    fn set_debug_callback(&self, callback: Option<DebugCallback>) {
+        self.gl.set_debug_callback(callback)
+    }
+
+    fn debug_message_control(
+        &self,
+        source: GLenum,
+        ty: GLenum,
+        severity: GLenum,
+        ids: &[GLuint],
+        enabled: bool,
+    ) {
+        self.gl
+            .debug_message_control(source, ty, severity, ids, enabled)
+    }
+
+    fn object_label(&self, identifier: GLenum, name: GLuint, label: &str) {
+        self.gl.object_label(identifier, name, label)
+    }
+
+    fn get_object_label(&self, identifier: GLenum, name: GLuint) -> String {
+        self.gl.get_object_label(identifier, name)
+    }
+
+    fn object_ptr_label(&self, sync: GLsync, label: &str) {
+        self.gl.object_ptr_label(sync, label)
+    }
+
+    fn provoking_vertex_angle(&self, mode: GLenum) {
+        self.gl.provoking_vertex_angle(mode)
+    }
+
+    fn copy_texture_chromium(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        internal_format: GLint,
+        dest_type: GLenum,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.gl.copy_texture_chromium(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            internal_format,
+            dest_type,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn copy_sub_texture_chromium(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        x_offset: GLint,
+        y_offset: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.gl.copy_sub_texture_chromium(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            x_offset,
+            y_offset,
+            x,
+            y,
+            width,
+            height,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn copy_texture_3d_angle(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        internal_format: GLint,
+        dest_type: GLenum,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.gl.copy_texture_3d_angle(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            internal_format,
+            dest_type,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn copy_sub_texture_3d_angle(
+        &self,
+        source_id: GLuint,
+        source_level: GLint,
+        dest_target: GLenum,
+        dest_id: GLuint,
+        dest_level: GLint,
+        x_offset: GLint,
+        y_offset: GLint,
+        z_offset: GLint,
+        x: GLint,
+        y: GLint,
+        z: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        depth: GLsizei,
+        unpack_flip_y: GLboolean,
+        unpack_premultiply_alpha: GLboolean,
+        unpack_unmultiply_alpha: GLboolean,
+    ) {
+        self.gl.copy_sub_texture_3d_angle(
+            source_id,
+            source_level,
+            dest_target,
+            dest_id,
+            dest_level,
+            x_offset,
+            y_offset,
+            z_offset,
+            x,
+            y,
+            z,
+            width,
+            height,
+            depth,
+            unpack_flip_y,
+            unpack_premultiply_alpha,
+            unpack_unmultiply_alpha,
+        )
+    }
+
+    fn buffer_storage(
+        &self,
+        target: GLenum,
+        size: GLsizeiptr,
+        data: *const GLvoid,
+        flags: GLbitfield,
+    ) {
+        if !self.allowed("buffer_storage", |state| state.check_buffer_bound(target)) {
+            return;
+        }
+        self.gl.buffer_storage(target, size, data, flags);
+        let state = &mut *self.state.borrow_mut();
+        state.set_buffer_size(target, size);
+    }
+
+    fn flush_mapped_buffer_range(&self, target: GLenum, offset: GLintptr, length: GLsizeiptr) {
+        self.gl.flush_mapped_buffer_range(target, offset, length)
+    }
+
+    fn start_tiling_qcom(
+        &self,
+        x: GLuint,
+        y: GLuint,
+        width: GLuint,
+        height: GLuint,
+        preserve_mask: GLbitfield,
+    ) {
+        self.gl
+            .start_tiling_qcom(x, y, width, height, preserve_mask)
+    }
+
+    fn end_tiling_qcom(&self, preserve_mask: GLbitfield) {
+        self.gl.end_tiling_qcom(preserve_mask)
+    }
+}
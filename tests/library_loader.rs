@@ -0,0 +1,132 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Loads a stub GL library, built from C with the system compiler, through
+//! `LibraryLoader`.
+
+#![cfg(all(feature = "dlopen", unix))]
+
+extern crate gleam;
+
+use gleam::gl::{GlLoader, LibraryLoader, VERSION};
+use std::env;
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::{c_char, c_int, c_uint};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A library exporting `glGetString` and a fake `stubGetProcAddress`, which
+/// answers like `wglGetProcAddress` does for some functions: with a
+/// different implementation than the exported one, with one of the `-1..=3`
+/// sentinels, or with null.
+const STUB_SOURCE: &str = r#"
+#include <stddef.h>
+#include <string.h>
+
+const unsigned char *glGetString(unsigned int name) {
+    return name == 0x1F02 ? (const unsigned char *)"OpenGL ES 3.0 stub" : NULL;
+}
+
+int glStubExported(void) { return 1; }
+static int stub_from_proc(void) { return 2; }
+
+int glStubSentinelOne(void) { return 3; }
+int glStubSentinelMinusOne(void) { return 4; }
+
+void *stubGetProcAddress(const char *name) {
+    if (strcmp(name, "glStubExported") == 0) {
+        return (void *)stub_from_proc;
+    }
+    if (strcmp(name, "glGetString") == 0) {
+        return (void *)2;
+    }
+    if (strcmp(name, "glStubSentinelOne") == 0) {
+        return (void *)1;
+    }
+    if (strcmp(name, "glStubSentinelMinusOne") == 0) {
+        return (void *)-1;
+    }
+    return NULL;
+}
+"#;
+
+type GetStringFn = extern "C" fn(c_uint) -> *const u8;
+type StubFn = extern "C" fn() -> c_int;
+
+/// Builds the stub library into a directory of its own for each test, as
+/// tests run in parallel.
+fn build_stub(test: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(test);
+    fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("stub.c");
+    let library = dir.join("libglstub.so");
+    fs::write(&source, STUB_SOURCE).unwrap();
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(&library)
+        .arg(&source)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "failed to build the stub library");
+    library
+}
+
+fn call(loader: &mut LibraryLoader, symbol: &str) -> c_int {
+    let address = loader.get_proc_address(symbol);
+    assert!(!address.is_null(), "{} wasn't found", symbol);
+    let function: StubFn = unsafe { std::mem::transmute(address) };
+    function()
+}
+
+#[test]
+fn get_proc_address_then_library_symbols() {
+    let library = build_stub("get_proc_address_then_library_symbols");
+    let mut loader = unsafe { LibraryLoader::open(&[&library], Some("stubGetProcAddress")) }
+        .expect("failed to open the stub library");
+
+    // `stubGetProcAddress` is asked first.
+    assert_eq!(call(&mut loader, "glStubExported"), 2);
+
+    // Its sentinels fall back to the exported symbol.
+    let get_string = loader.get_proc_address("glGetString");
+    assert!(!get_string.is_null());
+    let get_string: GetStringFn = unsafe { std::mem::transmute(get_string) };
+    let version = unsafe { CStr::from_ptr(get_string(VERSION) as *const c_char) };
+    assert_eq!(version.to_str(), Ok("OpenGL ES 3.0 stub"));
+    assert_eq!(call(&mut loader, "glStubSentinelOne"), 3);
+    assert_eq!(call(&mut loader, "glStubSentinelMinusOne"), 4);
+
+    assert!(loader.get_proc_address("glMissing").is_null());
+    assert!(loader.get_proc_address("gl\0Nul").is_null());
+}
+
+#[test]
+fn library_symbols_only() {
+    let library = build_stub("library_symbols_only");
+
+    // Without `*GetProcAddress`, the exported symbols are used as they are.
+    let mut loader = unsafe { LibraryLoader::open(&[&library], None) }.unwrap();
+    assert_eq!(call(&mut loader, "glStubExported"), 1);
+
+    // Naming a function the libraries don't export is the same as none.
+    let mut loader =
+        unsafe { LibraryLoader::open(&[&library], Some("missingGetProcAddress")) }.unwrap();
+    assert_eq!(call(&mut loader, "glStubExported"), 1);
+}
+
+#[test]
+fn missing_library() {
+    let error = unsafe { LibraryLoader::open(&["/nonexistent/libGL.so"], None) }
+        .err()
+        .expect("opened a missing library");
+    assert_eq!(error.path, "/nonexistent/libGL.so");
+    assert!(!error.reason.is_empty());
+}
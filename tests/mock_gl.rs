@@ -0,0 +1,168 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks the object model and errors of `MockGl`.
+
+extern crate gleam;
+
+use gleam::gl::{self, Gl, GlType, MockGl};
+use std::ptr;
+
+fn integer(gl: &dyn Gl, name: gl::GLenum) -> gl::GLint {
+    let mut value = [0];
+    unsafe { gl.get_integer_v(name, &mut value) };
+    value[0]
+}
+
+#[test]
+fn gen_hands_out_unused_names() {
+    let gl = MockGl::new(GlType::Gl);
+    let buffers = gl.gen_buffers(3);
+    let textures = gl.gen_textures(2);
+    assert_eq!(buffers.len(), 3);
+    assert_eq!(textures.len(), 2);
+    let mut names = [&buffers[..], &textures[..]].concat();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), 5);
+    assert!(!names.contains(&0));
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+
+    assert!(gl.gen_buffers(-1).is_empty());
+    assert_eq!(gl.get_error(), gl::INVALID_VALUE);
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+}
+
+#[test]
+fn bind_and_delete() {
+    let gl = MockGl::new(GlType::Gl);
+    let framebuffer = gl.gen_framebuffers(1)[0];
+    // Names are only objects once they are bound.
+    assert_eq!(gl.is_framebuffer(framebuffer), gl::FALSE);
+    gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffer);
+    assert_eq!(gl.is_framebuffer(framebuffer), gl::TRUE);
+    assert_eq!(integer(&*gl, gl::FRAMEBUFFER_BINDING), framebuffer as i32);
+
+    // Deleting a bound object unbinds it.
+    gl.delete_framebuffers(&[framebuffer]);
+    assert_eq!(gl.is_framebuffer(framebuffer), gl::FALSE);
+    assert_eq!(integer(&*gl, gl::FRAMEBUFFER_BINDING), 0);
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    assert_eq!(integer(&*gl, gl::ARRAY_BUFFER_BINDING), buffer as i32);
+    gl.delete_buffers(&[buffer]);
+    assert_eq!(integer(&*gl, gl::ARRAY_BUFFER_BINDING), 0);
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    assert_eq!(gl.get_error(), gl::INVALID_OPERATION);
+    gl.bind_buffer(gl::TEXTURE_2D, 0);
+    assert_eq!(gl.get_error(), gl::INVALID_ENUM);
+    gl.bind_framebuffer(gl::FRAMEBUFFER, 1234);
+    assert_eq!(gl.get_error(), gl::INVALID_OPERATION);
+    assert_eq!(integer(&*gl, gl::FRAMEBUFFER_BINDING), 0);
+}
+
+#[test]
+fn framebuffer_status() {
+    let gl = MockGl::new(GlType::Gl);
+    assert_eq!(
+        gl.check_frame_buffer_status(gl::FRAMEBUFFER),
+        gl::FRAMEBUFFER_COMPLETE
+    );
+    assert_eq!(gl.check_frame_buffer_status(gl::TEXTURE_2D), 0);
+    assert_eq!(gl.get_error(), gl::INVALID_ENUM);
+
+    let framebuffer = gl.gen_framebuffers(1)[0];
+    gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffer);
+    assert_eq!(
+        gl.check_frame_buffer_status(gl::FRAMEBUFFER),
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT
+    );
+
+    let renderbuffers = gl.gen_renderbuffers(2);
+    gl.bind_renderbuffer(gl::RENDERBUFFER, renderbuffers[0]);
+    gl.framebuffer_renderbuffer(
+        gl::FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        gl::RENDERBUFFER,
+        renderbuffers[0],
+    );
+    assert_eq!(
+        gl.check_frame_buffer_status(gl::FRAMEBUFFER),
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT
+    );
+    gl.renderbuffer_storage(gl::RENDERBUFFER, gl::RGBA8, 4, 4);
+    assert_eq!(
+        gl.check_frame_buffer_status(gl::FRAMEBUFFER),
+        gl::FRAMEBUFFER_COMPLETE
+    );
+
+    gl.bind_renderbuffer(gl::RENDERBUFFER, renderbuffers[1]);
+    gl.renderbuffer_storage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, 8, 8);
+    gl.framebuffer_renderbuffer(
+        gl::FRAMEBUFFER,
+        gl::DEPTH_ATTACHMENT,
+        gl::RENDERBUFFER,
+        renderbuffers[1],
+    );
+    assert_eq!(
+        gl.check_frame_buffer_status(gl::FRAMEBUFFER),
+        gl::FRAMEBUFFER_INCOMPLETE_DIMENSIONS
+    );
+
+    gl.delete_framebuffers(&[framebuffer]);
+    assert_eq!(
+        gl.check_frame_buffer_status(gl::FRAMEBUFFER),
+        gl::FRAMEBUFFER_COMPLETE
+    );
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+}
+
+#[test]
+fn overflowing_ranges() {
+    let gl = MockGl::new(GlType::Gl);
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    gl.buffer_data_untyped(gl::ARRAY_BUFFER, 16, ptr::null(), gl::DYNAMIC_DRAW);
+    let data = [1u8; 8];
+    gl.buffer_sub_data_untyped(gl::ARRAY_BUFFER, isize::MAX, 8, data.as_ptr() as *const _);
+    assert_eq!(gl.get_error(), gl::INVALID_VALUE);
+    let mapped = gl.map_buffer_range(gl::ARRAY_BUFFER, isize::MAX, 8, gl::MAP_WRITE_BIT);
+    assert!(mapped.is_null());
+    assert_eq!(gl.get_error(), gl::INVALID_VALUE);
+
+    let texture = gl.gen_textures(1)[0];
+    gl.bind_texture(gl::TEXTURE_2D, texture);
+    gl.tex_image_2d(
+        gl::TEXTURE_2D,
+        0,
+        gl::RGBA as i32,
+        4,
+        4,
+        0,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        None,
+    )
+    .unwrap();
+    gl.tex_sub_image_2d(
+        gl::TEXTURE_2D,
+        0,
+        i32::MAX,
+        0,
+        1,
+        1,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        &[0; 4],
+    )
+    .unwrap();
+    assert_eq!(gl.get_error(), gl::INVALID_VALUE);
+}
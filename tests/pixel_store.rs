@@ -0,0 +1,130 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks client-memory transfers against pixel store state set behind the
+//! backend's back, through a fake driver.
+
+extern crate gleam;
+
+use gleam::gl::{self, GLenum, GLint, GLsizei, Gl, GlFns, GlesFns, PixelDataError};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::os::raw::c_void;
+use std::ptr;
+use std::rc::Rc;
+
+thread_local! {
+    static VERSION: Cell<&'static [u8]> = Cell::new(b"\0");
+    static PIXEL_STORE: RefCell<HashMap<GLenum, GLint>> = RefCell::new(HashMap::new());
+    static UPLOADS: Cell<usize> = Cell::new(0);
+}
+
+extern "system" fn get_string(name: GLenum) -> *const u8 {
+    match name {
+        gl::VERSION => VERSION.with(Cell::get).as_ptr(),
+        _ => ptr::null(),
+    }
+}
+
+extern "system" fn get_integer_v(name: GLenum, value: *mut GLint) {
+    let stored = PIXEL_STORE.with(|store| store.borrow().get(&name).cloned());
+    let initial = match name {
+        gl::PACK_ALIGNMENT | gl::UNPACK_ALIGNMENT => 4,
+        _ => 0,
+    };
+    unsafe { *value = stored.unwrap_or(initial) };
+}
+
+extern "system" fn pixel_store_i(name: GLenum, param: GLint) {
+    PIXEL_STORE.with(|store| store.borrow_mut().insert(name, param));
+}
+
+extern "system" fn tex_sub_image_2d(
+    _target: GLenum,
+    _level: GLint,
+    _xoffset: GLint,
+    _yoffset: GLint,
+    _width: GLsizei,
+    _height: GLsizei,
+    _format: GLenum,
+    _ty: GLenum,
+    _data: *const c_void,
+) {
+    UPLOADS.with(|uploads| uploads.set(uploads.get() + 1));
+}
+
+fn load(
+    version: &'static [u8],
+    load_with: unsafe fn(&mut dyn FnMut(&str) -> *const c_void) -> Rc<dyn Gl>,
+) -> Rc<dyn Gl> {
+    VERSION.with(|v| v.set(version));
+    unsafe {
+        load_with(&mut |symbol| match symbol {
+            "glGetString" => get_string as *const c_void,
+            "glGetIntegerv" => get_integer_v as *const c_void,
+            "glPixelStorei" => pixel_store_i as *const c_void,
+            "glTexSubImage2D" => tex_sub_image_2d as *const c_void,
+            _ => ptr::null(),
+        })
+    }
+}
+
+unsafe fn load_gl(loadfn: &mut dyn FnMut(&str) -> *const c_void) -> Rc<dyn Gl> {
+    GlFns::load_with(loadfn)
+}
+
+unsafe fn load_gles(loadfn: &mut dyn FnMut(&str) -> *const c_void) -> Rc<dyn Gl> {
+    GlesFns::load_with(loadfn)
+}
+
+fn upload(gl: &dyn Gl, data: &[u8]) -> Result<(), PixelDataError> {
+    gl.tex_sub_image_2d(
+        gl::TEXTURE_2D,
+        0,
+        0,
+        0,
+        4,
+        4,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        data,
+    )
+}
+
+/// Sets `UNPACK_ROW_LENGTH` straight through the driver, as a context
+/// sharing the backend's would, then uploads a slice sized for tight rows.
+fn check_external_row_length(gl: &dyn Gl) {
+    UPLOADS.with(|uploads| uploads.set(0));
+    PIXEL_STORE.with(|store| store.borrow_mut().clear());
+    let tight = vec![0; 4 * 4 * 4];
+    assert_eq!(upload(gl, &tight), Ok(()));
+
+    pixel_store_i(gl::UNPACK_ROW_LENGTH, 8);
+    assert_eq!(
+        upload(gl, &tight),
+        Err(PixelDataError::BufferTooSmall {
+            required: (8 * 3 + 4) * 4,
+            actual: tight.len(),
+        })
+    );
+    assert_eq!(upload(gl, &vec![0; (8 * 3 + 4) * 4]), Ok(()));
+    assert_eq!(UPLOADS.with(Cell::get), 2);
+}
+
+#[test]
+fn gl_queries_the_unpack_state() {
+    let gl = load(b"4.5 fake\0", load_gl);
+    check_external_row_length(&*gl);
+}
+
+#[test]
+fn gles_queries_the_unpack_state() {
+    let gl = load(b"OpenGL ES 3.0 fake\0", load_gles);
+    check_external_row_length(&*gl);
+}
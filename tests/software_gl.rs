@@ -0,0 +1,113 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Renders with `SoftwareGl` and reads the results back.
+
+#![cfg(feature = "software")]
+
+extern crate gleam;
+
+use gleam::gl::{self, Gl, GlType, SoftwareGl};
+use std::mem;
+
+const WIDTH: i32 = 8;
+const HEIGHT: i32 = 8;
+
+fn pixel(pixels: &[u8], x: i32, y: i32) -> &[u8] {
+    let start = ((y * WIDTH + x) * 4) as usize;
+    &pixels[start..start + 4]
+}
+
+fn read_back(gl: &dyn Gl) -> Vec<u8> {
+    let pixels = gl
+        .read_pixels(0, 0, WIDTH, HEIGHT, gl::RGBA, gl::UNSIGNED_BYTE)
+        .unwrap();
+    assert_eq!(pixels.len(), (WIDTH * HEIGHT * 4) as usize);
+    pixels
+}
+
+#[test]
+fn clear_and_read_back() {
+    let gl = SoftwareGl::new(GlType::Gl, WIDTH, HEIGHT);
+    gl.clear_color(1.0, 0.0, 0.0, 1.0);
+    gl.clear(gl::COLOR_BUFFER_BIT);
+    let pixels = read_back(&*gl);
+    assert!(pixels.chunks(4).all(|p| p == [255, 0, 0, 255]));
+
+    // Clears are limited to the scissor box when the test is enabled.
+    gl.enable(gl::SCISSOR_TEST);
+    gl.scissor(0, 0, 2, 2);
+    gl.clear_color(0.0, 0.0, 1.0, 1.0);
+    gl.clear(gl::COLOR_BUFFER_BIT);
+    let pixels = read_back(&*gl);
+    assert_eq!(pixel(&pixels, 1, 1), [0, 0, 255, 255]);
+    assert_eq!(pixel(&pixels, 2, 2), [255, 0, 0, 255]);
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+}
+
+#[test]
+fn draw_and_read_back() {
+    let gl = SoftwareGl::new(GlType::Gl, WIDTH, HEIGHT);
+    gl.register_vertex_shader("passthrough_vs", |_, vertex, _| {
+        let position = vertex.attribs[0];
+        [position[0], position[1], 0.0, 1.0]
+    });
+    gl.register_fragment_shader("uniform_color_fs", |context, _| {
+        let color = context.uniform("color");
+        Some([color[0], color[1], color[2], color[3]])
+    });
+
+    let shaders = [
+        (gl::VERTEX_SHADER, "// passthrough_vs"),
+        (gl::FRAGMENT_SHADER, "// uniform_color_fs"),
+    ];
+    let program = gl.create_program();
+    for &(ty, source) in &shaders {
+        let shader = gl.create_shader(ty);
+        gl.shader_source(shader, &[source.as_bytes()]);
+        gl.compile_shader(shader);
+        gl.attach_shader(program, shader);
+    }
+    gl.bind_attrib_location(program, 0, "position");
+    gl.link_program(program);
+    gl.use_program(program);
+    gl.uniform_4f(
+        gl.get_uniform_location(program, "color"),
+        0.0,
+        1.0,
+        0.0,
+        1.0,
+    );
+
+    // A triangle covering the lower left half of the viewport.
+    let vertices: [f32; 6] = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0];
+    let vao = gl.gen_vertex_arrays(1)[0];
+    gl.bind_vertex_array(vao);
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    gl.buffer_data_untyped(
+        gl::ARRAY_BUFFER,
+        mem::size_of_val(&vertices) as isize,
+        vertices.as_ptr() as *const _,
+        gl::STATIC_DRAW,
+    );
+    gl.vertex_attrib_pointer(0, 2, gl::FLOAT, false, 0, 0);
+    gl.enable_vertex_attrib_array(0);
+
+    gl.clear_color(0.0, 0.0, 0.0, 1.0);
+    gl.clear(gl::COLOR_BUFFER_BIT);
+    gl.draw_arrays(gl::TRIANGLES, 0, 3);
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+
+    let pixels = read_back(&*gl);
+    assert_eq!(pixel(&pixels, 0, 0), [0, 255, 0, 255]);
+    assert_eq!(pixel(&pixels, 2, 3), [0, 255, 0, 255]);
+    assert_eq!(pixel(&pixels, WIDTH - 1, HEIGHT - 1), [0, 0, 0, 255]);
+    assert_eq!(pixel(&pixels, 5, 6), [0, 0, 0, 255]);
+}
@@ -0,0 +1,83 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Records calls over `MockGl` and replays them on another one.
+
+extern crate gleam;
+
+use gleam::gl::{self, replay, Gl, GlType, MockGl, RecordingGl, Trace};
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+fn buffer_contents(gl: &dyn Gl, buffer: u32, len: usize) -> Vec<u8> {
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    let mapped = gl.map_buffer_range(gl::ARRAY_BUFFER, 0, len as isize, gl::MAP_READ_BIT);
+    assert!(!mapped.is_null());
+    let contents = unsafe { slice::from_raw_parts(mapped as *const u8, len) }.to_vec();
+    gl.unmap_buffer(gl::ARRAY_BUFFER);
+    contents
+}
+
+#[test]
+fn replays_writes_through_mapped_buffers() {
+    let recording = RecordingGl::wrap(MockGl::new(GlType::Gl));
+    let gl: Rc<dyn Gl> = recording.clone();
+    let buffers = gl.gen_buffers(2);
+
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffers[0]);
+    gl.buffer_data_untyped(gl::ARRAY_BUFFER, 8, ptr::null(), gl::DYNAMIC_DRAW);
+    let mapped = gl.map_buffer_range(gl::ARRAY_BUFFER, 4, 4, gl::MAP_WRITE_BIT);
+    unsafe { slice::from_raw_parts_mut(mapped as *mut u8, 4) }.copy_from_slice(&[1, 2, 3, 4]);
+    gl.unmap_buffer(gl::ARRAY_BUFFER);
+
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffers[1]);
+    gl.buffer_data_untyped(gl::ARRAY_BUFFER, 4, ptr::null(), gl::DYNAMIC_DRAW);
+    let mapped = gl.map_buffer(gl::ARRAY_BUFFER, gl::WRITE_ONLY);
+    unsafe { slice::from_raw_parts_mut(mapped as *mut u8, 4) }.copy_from_slice(&[5, 6, 7, 8]);
+    gl.unmap_buffer(gl::ARRAY_BUFFER);
+
+    // Through the binary format, as traces usually are replayed elsewhere.
+    let mut file = Vec::new();
+    recording.take_trace().write_to(&mut file).unwrap();
+    let trace = Trace::read_from(&mut &file[..]).unwrap();
+
+    let replayed = MockGl::new(GlType::Gl);
+    replay(&trace, &*replayed).unwrap();
+    assert_eq!(replayed.get_error(), gl::NO_ERROR);
+    // The mock hands out the same names, so they can be read back directly.
+    assert_eq!(
+        buffer_contents(&*replayed, buffers[0], 8),
+        [0, 0, 0, 0, 1, 2, 3, 4]
+    );
+    assert_eq!(buffer_contents(&*replayed, buffers[1], 4), [5, 6, 7, 8]);
+}
+
+#[test]
+fn read_only_mappings_are_not_captured() {
+    let recording = RecordingGl::wrap(MockGl::new(GlType::Gl));
+    let gl: Rc<dyn Gl> = recording.clone();
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    gl.buffer_data_untyped(
+        gl::ARRAY_BUFFER,
+        4,
+        [9u8; 4].as_ptr() as *const _,
+        gl::STATIC_DRAW,
+    );
+    gl.map_buffer(gl::ARRAY_BUFFER, gl::READ_ONLY);
+    gl.unmap_buffer(gl::ARRAY_BUFFER);
+
+    let trace = recording.take_trace();
+    let map = trace.calls.iter().find(|call| call.name == "map_buffer");
+    assert!(matches!(map.unwrap().ret, gl::TraceValue::Pointer(_)));
+    let replayed = MockGl::new(GlType::Gl);
+    replay(&trace, &*replayed).unwrap();
+    assert_eq!(buffer_contents(&*replayed, buffer, 4), [9; 4]);
+}
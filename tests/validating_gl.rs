@@ -0,0 +1,214 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Runs `ValidatingGl` over `MockGl` and checks what it rejects.
+
+extern crate gleam;
+
+use gleam::gl::{self, Gl, GlType, MockGl, ValidatingGl};
+use std::cell::RefCell;
+use std::ptr;
+use std::rc::Rc;
+
+type Rejections = Rc<RefCell<Vec<(String, String)>>>;
+
+fn validating_gl() -> (Rc<dyn Gl>, Rejections) {
+    let rejections = Rejections::default();
+    let log = rejections.clone();
+    let gl = ValidatingGl::wrap(
+        MockGl::new(GlType::Gl),
+        move |method: &str, reason: &str| {
+            log.borrow_mut()
+                .push((method.to_string(), reason.to_string()))
+        },
+    );
+    (gl, rejections)
+}
+
+/// Takes the rejected method names, asserting each reason mentions `reason`.
+fn rejected(rejections: &Rejections, reason: &str) -> Vec<String> {
+    rejections
+        .borrow_mut()
+        .drain(..)
+        .map(|(method, why)| {
+            assert!(why.contains(reason), "{}: {:?}", method, why);
+            method
+        })
+        .collect()
+}
+
+fn texture_2d(gl: &dyn Gl, width: i32, height: i32) -> u32 {
+    let texture = gl.gen_textures(1)[0];
+    gl.bind_texture(gl::TEXTURE_2D, texture);
+    gl.tex_image_2d(
+        gl::TEXTURE_2D,
+        0,
+        gl::RGBA as i32,
+        width,
+        height,
+        0,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        None,
+    )
+    .unwrap();
+    texture
+}
+
+#[test]
+fn use_after_delete() {
+    let (gl, rejections) = validating_gl();
+    let texture = gl.gen_textures(1)[0];
+    gl.delete_textures(&[texture]);
+    gl.bind_texture(gl::TEXTURE_2D, texture);
+    let buffer = gl.gen_buffers(1)[0];
+    gl.delete_buffers(&[buffer]);
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    assert_eq!(
+        rejected(&rejections, "has been deleted"),
+        ["bind_texture", "bind_buffer"]
+    );
+}
+
+#[test]
+fn wrong_texture_target() {
+    let (gl, rejections) = validating_gl();
+    let texture = gl.gen_textures(1)[0];
+    gl.bind_texture(gl::TEXTURE_2D, texture);
+    gl.bind_texture(gl::TEXTURE_2D, 0);
+    gl.bind_texture(gl::TEXTURE_CUBE_MAP, texture);
+    assert_eq!(
+        rejected(&rejections, "cannot be bound to"),
+        ["bind_texture"]
+    );
+}
+
+#[test]
+fn sub_image_out_of_bounds() {
+    let (gl, rejections) = validating_gl();
+    texture_2d(&*gl, 4, 4);
+    let pixels = [0; 4 * 4 * 4];
+    let upload = |x, y, width, height| {
+        gl.tex_sub_image_2d(
+            gl::TEXTURE_2D,
+            0,
+            x,
+            y,
+            width,
+            height,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            &pixels,
+        )
+        .unwrap()
+    };
+    upload(0, 0, 4, 4);
+    assert!(rejected(&rejections, "").is_empty());
+    upload(2, 0, 4, 1);
+    upload(-1, 0, 1, 1);
+    // The end of the region overflows `GLint`.
+    upload(0, i32::MAX - 1, 1, 4);
+    assert_eq!(
+        rejected(&rejections, "exceeds the 4x4x1 level 0"),
+        ["tex_sub_image_2d"; 3]
+    );
+}
+
+#[test]
+fn buffer_range_out_of_bounds() {
+    let (gl, rejections) = validating_gl();
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    gl.buffer_data_untyped(gl::ARRAY_BUFFER, 16, ptr::null(), gl::STATIC_DRAW);
+    let data = [0u8; 16];
+    gl.buffer_sub_data_untyped(gl::ARRAY_BUFFER, 8, 8, data.as_ptr() as *const _);
+    assert!(rejected(&rejections, "").is_empty());
+    gl.buffer_sub_data_untyped(gl::ARRAY_BUFFER, 12, 8, data.as_ptr() as *const _);
+    // The end of the range overflows `isize`.
+    gl.buffer_sub_data_untyped(gl::ARRAY_BUFFER, isize::MAX, 8, data.as_ptr() as *const _);
+    assert_eq!(
+        rejected(&rejections, "outside the 16 bytes"),
+        ["buffer_sub_data_untyped"; 2]
+    );
+}
+
+#[test]
+fn draw_without_program() {
+    let (gl, rejections) = validating_gl();
+    gl.draw_arrays(gl::TRIANGLES, 0, 3);
+    assert_eq!(
+        rejected(&rejections, "no program is in use"),
+        ["draw_arrays"]
+    );
+}
+
+#[test]
+fn incomplete_framebuffer() {
+    let (gl, rejections) = validating_gl();
+    let program = gl.create_program();
+    gl.use_program(program);
+    let framebuffer = gl.gen_framebuffers(1)[0];
+    gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffer);
+    gl.clear(gl::COLOR_BUFFER_BIT);
+    assert_eq!(rejected(&rejections, "has no attachments"), ["clear"]);
+
+    let renderbuffer = gl.gen_renderbuffers(1)[0];
+    gl.bind_renderbuffer(gl::RENDERBUFFER, renderbuffer);
+    gl.framebuffer_renderbuffer(
+        gl::FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        gl::RENDERBUFFER,
+        renderbuffer,
+    );
+    gl.draw_arrays(gl::TRIANGLES, 0, 3);
+    assert_eq!(rejected(&rejections, "has no storage"), ["draw_arrays"]);
+
+    gl.renderbuffer_storage(gl::RENDERBUFFER, gl::RGBA8, 4, 4);
+    gl.draw_arrays(gl::TRIANGLES, 0, 3);
+    assert!(rejected(&rejections, "").is_empty());
+}
+
+#[test]
+fn client_pointer_with_pixel_buffer_bound() {
+    let (gl, rejections) = validating_gl();
+    let texture = texture_2d(&*gl, 4, 4);
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::PIXEL_UNPACK_BUFFER, buffer);
+    let pixels = [0; 4 * 4 * 4];
+    gl.tex_sub_image_2d(
+        gl::TEXTURE_2D,
+        0,
+        0,
+        0,
+        4,
+        4,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        &pixels,
+    )
+    .unwrap();
+    gl.bind_buffer(gl::PIXEL_UNPACK_BUFFER, 0);
+    gl.bind_buffer(gl::PIXEL_PACK_BUFFER, buffer);
+    let framebuffer = gl.gen_framebuffers(1)[0];
+    gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffer);
+    gl.framebuffer_texture_2d(
+        gl::FRAMEBUFFER,
+        gl::COLOR_ATTACHMENT0,
+        gl::TEXTURE_2D,
+        texture,
+        0,
+    );
+    let mut read = [0; 4 * 4 * 4];
+    gl.read_pixels_into_buffer(0, 0, 4, 4, gl::RGBA, gl::UNSIGNED_BYTE, &mut read)
+        .unwrap();
+    assert_eq!(
+        rejected(&rejections, "client memory was passed"),
+        ["tex_sub_image_2d", "read_pixels_into_buffer"]
+    );
+}
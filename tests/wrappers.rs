@@ -0,0 +1,292 @@
+// Copyright 2014 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Drives the wrappers that look at the arguments of calls by method and
+//! argument name over `MockGl`, through every method each of them matches
+//! on, so that a renamed method or argument fails here rather than panicking
+//! in an application.
+
+extern crate gleam;
+
+use gleam::gl::{
+    self, DebugGroupCheckingGl, Gl, GlError, GlType, LeakTrackingGl, LiveObjects, MockGl,
+    StateCachingGl, StatsGl, TryGl, UnbalancedDebugGroups,
+};
+use std::cell::RefCell;
+use std::ptr;
+use std::rc::Rc;
+
+fn mock() -> Rc<dyn Gl> {
+    MockGl::new(GlType::Gl)
+}
+
+#[test]
+fn stats() {
+    let stats = StatsGl::wrap(mock());
+    let gl: Rc<dyn Gl> = stats.clone();
+
+    let buffer = gl.gen_buffers(1)[0];
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffer);
+    let data = [0u8; 32];
+    gl.buffer_data_untyped(gl::ARRAY_BUFFER, 32, ptr::null(), gl::STATIC_DRAW);
+    gl.buffer_data_untyped(
+        gl::ARRAY_BUFFER,
+        32,
+        data.as_ptr() as *const _,
+        gl::STATIC_DRAW,
+    );
+    gl.buffer_sub_data_untyped(gl::ARRAY_BUFFER, 8, 16, data.as_ptr() as *const _);
+
+    let texture = gl.gen_textures(2);
+    gl.bind_texture(gl::TEXTURE_2D, texture[0]);
+    gl.tex_storage_2d(gl::TEXTURE_2D, 1, gl::RGBA8, 2, 2);
+    gl.tex_sub_image_2d(
+        gl::TEXTURE_2D,
+        0,
+        0,
+        0,
+        2,
+        2,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        &[0; 16],
+    )
+    .unwrap();
+    gl.bind_texture(gl::TEXTURE_2D_ARRAY, texture[1]);
+    gl.tex_storage_3d(gl::TEXTURE_2D_ARRAY, 1, gl::RGBA8, 2, 2, 2);
+    gl.tex_sub_image_3d(
+        gl::TEXTURE_2D_ARRAY,
+        0,
+        0,
+        0,
+        0,
+        2,
+        2,
+        2,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        &[0; 32],
+    )
+    .unwrap();
+
+    gl.draw_arrays(gl::TRIANGLES, 0, 6);
+    gl.draw_arrays_instanced(gl::TRIANGLE_STRIP, 0, 4, 3);
+    gl.draw_elements(gl::LINES, 4, gl::UNSIGNED_SHORT, 0);
+    gl.draw_elements_instanced(gl::TRIANGLES, 3, gl::UNSIGNED_SHORT, 0, 5);
+
+    let stats = stats.reset();
+    assert_eq!(stats.buffer_bytes, 32 + 16);
+    assert_eq!(stats.texture_bytes, 16 + 32);
+    assert_eq!(stats.draw_calls(), 4);
+    assert_eq!(stats.draws[&gl::TRIANGLES].calls, 2);
+    assert_eq!(stats.draws[&gl::TRIANGLES].primitives, 2 + 5);
+    assert_eq!(stats.draws[&gl::TRIANGLE_STRIP].primitives, 2 * 3);
+    assert_eq!(stats.draws[&gl::LINES].primitives, 2);
+    assert_eq!(stats.calls["draw_arrays"], 1);
+    assert_eq!(stats.state_changes, 3);
+}
+
+#[test]
+fn state_caching() {
+    let caching = StateCachingGl::wrap(mock());
+    let gl: Rc<dyn Gl> = caching.clone();
+    let program = gl.create_program();
+    let shader = gl.create_shader(gl::COMPUTE_SHADER);
+    gl.shader_source(shader, &[b"void main() {}"]);
+    gl.compile_shader(shader);
+    gl.attach_shader(program, shader);
+    gl.link_program(program);
+    let textures = gl.gen_textures(1);
+    let buffers = gl.gen_buffers(1);
+    let vaos = gl.gen_vertex_arrays(1);
+    let framebuffers = gl.gen_framebuffers(1);
+    let renderbuffers = gl.gen_renderbuffers(1);
+
+    // Each state change, made twice in a row.
+    let changes: Vec<Box<dyn Fn(&dyn Gl)>> = vec![
+        Box::new(|gl| gl.active_texture(gl::TEXTURE1)),
+        Box::new(|gl| gl.bind_texture(gl::TEXTURE_2D, textures[0])),
+        Box::new(|gl| gl.use_program(program)),
+        Box::new(|gl| gl.enable(gl::BLEND)),
+        Box::new(|gl| gl.disable(gl::BLEND)),
+        Box::new(|gl| gl.blend_func(gl::ONE, gl::ZERO)),
+        Box::new(|gl| gl.blend_func_separate(gl::ONE, gl::ONE, gl::ZERO, gl::ZERO)),
+        Box::new(|gl| gl.blend_equation(gl::FUNC_ADD)),
+        Box::new(|gl| gl.blend_equation_separate(gl::FUNC_ADD, gl::MAX)),
+        Box::new(|gl| gl.bind_buffer(gl::ARRAY_BUFFER, buffers[0])),
+        Box::new(|gl| gl.bind_vertex_array(vaos[0])),
+        Box::new(|gl| gl.bind_vertex_array_apple(0)),
+        Box::new(|gl| gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffers[0])),
+        Box::new(|gl| gl.bind_renderbuffer(gl::RENDERBUFFER, renderbuffers[0])),
+    ];
+    for (i, change) in changes.iter().enumerate() {
+        change(&*gl);
+        change(&*gl);
+        assert_eq!(caching.elided_calls(), i + 1);
+    }
+    assert_eq!(gl.get_error(), gl::NO_ERROR);
+
+    // Indexed bindings also bind the generic binding point.
+    gl.bind_buffer_base(gl::UNIFORM_BUFFER, 0, buffers[0]);
+    gl.bind_buffer(gl::UNIFORM_BUFFER, buffers[0]);
+    gl.bind_buffer_range(gl::UNIFORM_BUFFER, 1, 0, 0, 0);
+    gl.bind_buffer(gl::UNIFORM_BUFFER, 0);
+    assert_eq!(caching.elided_calls(), changes.len() + 2);
+
+    // Deleting an object unbinds it, and its name may be reused.
+    let elided = caching.elided_calls();
+    gl.delete_textures(&textures);
+    gl.bind_texture(gl::TEXTURE_2D, textures[0]);
+    gl.delete_program(program);
+    gl.use_program(program);
+    gl.delete_buffers(&buffers);
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffers[0]);
+    gl.bind_vertex_array(vaos[0]);
+    gl.delete_vertex_arrays(&vaos);
+    gl.bind_vertex_array(vaos[0]);
+    gl.delete_vertex_arrays_apple(&vaos);
+    gl.bind_vertex_array_apple(vaos[0]);
+    gl.delete_framebuffers(&framebuffers);
+    gl.bind_framebuffer(gl::FRAMEBUFFER, framebuffers[0]);
+    gl.delete_renderbuffers(&renderbuffers);
+    gl.bind_renderbuffer(gl::RENDERBUFFER, renderbuffers[0]);
+    assert_eq!(caching.elided_calls(), elided);
+
+    gl.enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+    gl.set_debug_callback(None);
+    gl.enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+    assert_eq!(caching.elided_calls(), elided);
+}
+
+#[test]
+fn leak_tracking() {
+    let leaks = Rc::new(RefCell::new(LiveObjects::new()));
+    let reported = leaks.clone();
+    let tracking = LeakTrackingGl::wrap(mock(), move |live: &LiveObjects| {
+        *reported.borrow_mut() = live.clone()
+    });
+    let gl: Rc<dyn Gl> = tracking.clone();
+
+    let buffers = gl.gen_buffers(2);
+    let textures = gl.gen_textures(1);
+    let framebuffers = gl.gen_framebuffers(1);
+    let renderbuffers = gl.gen_renderbuffers(1);
+    let vaos = gl.gen_vertex_arrays(1);
+    let apple_vaos = gl.gen_vertex_arrays_apple(1);
+    let queries = gl.gen_queries(1);
+    let fences = gl.gen_fences_apple(1);
+    let shader = gl.create_shader(gl::VERTEX_SHADER);
+    let program = gl.create_program();
+    let sync = gl.fence_sync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0);
+    assert_eq!(tracking.live_objects().len(), 10);
+
+    gl.bind_buffer(gl::ARRAY_BUFFER, buffers[1]);
+    gl.object_label(gl::BUFFER, buffers[1], "vertices");
+    gl.object_ptr_label(sync, "frame fence");
+    let live = tracking.live_objects();
+    assert_eq!(live["buffer"][1].label.as_deref(), Some("vertices"));
+    assert_eq!(live["sync"][0].label.as_deref(), Some("frame fence"));
+
+    gl.delete_buffers(&buffers[..1]);
+    gl.delete_textures(&textures);
+    gl.delete_framebuffers(&framebuffers);
+    gl.delete_renderbuffers(&renderbuffers);
+    gl.delete_vertex_arrays(&vaos);
+    gl.delete_vertex_arrays_apple(&apple_vaos);
+    gl.delete_queries(&queries);
+    gl.delete_fences_apple(&fences);
+    gl.delete_shader(shader);
+    gl.delete_program(program);
+    gl.delete_sync(sync);
+    let live = tracking.live_objects();
+    assert_eq!(live.keys().cloned().collect::<Vec<_>>(), ["buffer"]);
+    assert_eq!(live["buffer"][0].name, buffers[1] as usize);
+
+    drop(gl);
+    drop(tracking);
+    assert_eq!(leaks.borrow()["buffer"].len(), 1);
+}
+
+#[test]
+fn debug_group_checking() {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let reported = reports.clone();
+    let checking = DebugGroupCheckingGl::wrap(mock(), move |groups: &UnbalancedDebugGroups| {
+        reported.borrow_mut().push(groups.clone())
+    });
+    let gl: Rc<dyn Gl> = checking.clone();
+
+    gl.push_debug_group_khr(gl::DEBUG_SOURCE_APPLICATION, 0, "khr");
+    gl.push_group_marker_ext("ext");
+    gl.pop_group_marker_ext();
+    gl.pop_debug_group_khr();
+    checking.end_frame();
+    assert!(reports.borrow().is_empty());
+
+    gl.pop_debug_group_khr();
+    gl.pop_group_marker_ext();
+    gl.pop_group_marker_ext();
+    checking.end_frame();
+    assert_eq!(
+        reports.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [UnbalancedDebugGroups {
+            khr_debug_extra_pops: 1,
+            ext_debug_marker_extra_pops: 2,
+            ..UnbalancedDebugGroups::default()
+        }]
+    );
+}
+
+#[test]
+fn try_gl_precision_type() {
+    let gl = TryGl::wrap(MockGl::new(GlType::Gles));
+    assert!(gl
+        .get_shader_precision_format(gl::FRAGMENT_SHADER, gl::HIGH_FLOAT)
+        .is_ok());
+    assert_eq!(
+        gl.get_shader_precision_format(gl::FRAGMENT_SHADER, gl::FLOAT),
+        Err(GlError::BadArgument {
+            method: "get_shader_precision_format",
+            reason: "invalid precision type",
+        })
+    );
+}
+
+#[test]
+fn debug_group_spanning_frames() {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let reported = reports.clone();
+    let checking = DebugGroupCheckingGl::wrap(mock(), move |groups: &UnbalancedDebugGroups| {
+        reported.borrow_mut().push(groups.clone())
+    });
+    let gl: Rc<dyn Gl> = checking.clone();
+
+    gl.push_debug_group_khr(gl::DEBUG_SOURCE_APPLICATION, 0, "loading");
+    gl.pop_group_marker_ext();
+    checking.end_frame();
+    // The group is still open when the next frame ends, but the extra pop
+    // was only made in the first one.
+    checking.end_frame();
+    gl.pop_debug_group_khr();
+    checking.end_frame();
+    let open = UnbalancedDebugGroups {
+        khr_debug: vec!["loading".to_string()],
+        ..UnbalancedDebugGroups::default()
+    };
+    assert_eq!(
+        *reports.borrow(),
+        [
+            UnbalancedDebugGroups {
+                ext_debug_marker_extra_pops: 1,
+                ..open.clone()
+            },
+            open,
+        ]
+    );
+}